@@ -0,0 +1,463 @@
+//! Bot competitions over TCP, played on the rules core without a window.
+//! A bot connects, sends a `Hello` line, then gets an `ArenaMessage::Turn` line whenever it has to act
+//! and answers each with one `Action` as a JSON line. Every game ends with an `ArenaMessage::End`.
+//! A bot that takes longer than the move time forfeits the game, and the answer it still owes is thrown away
+//! before its next game; one that never sends it is dropped and forfeits the rest.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{BoardLayout, TilePool},
+    history::MovementRules,
+    rules::{Action, CardPlace, Phase, Run, RulesError},
+    special_cards::SpecialCardPool,
+};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+pub const DEFAULT_MOVE_TIME: Duration = Duration::from_secs(1);
+/// Move times a bot that took too long gets to send the answer it still owes, before it is dropped.
+const LATE_ANSWER_MOVES: u32 = 4;
+
+#[derive(Debug, Clone)]
+pub struct ArenaConfig {
+    /// Bots waited for before the tournament starts.
+    pub bots: usize,
+    /// One round per seed, in which every bot plays a safari of its own on that seed.
+    pub seeds: Vec<u64>,
+    /// How long a bot may think about one action before it forfeits the game.
+    pub move_time: Duration,
+    pub layout: BoardLayout,
+    pub rules: MovementRules,
+    pub tiles: TilePool,
+    pub special_cards: SpecialCardPool,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        ArenaConfig {
+            bots: 2,
+            seeds: (0..10).collect(),
+            move_time: DEFAULT_MOVE_TIME,
+            layout: BoardLayout::default(),
+            rules: MovementRules::default(),
+            tiles: TilePool::default(),
+            special_cards: SpecialCardPool::default(),
+        }
+    }
+}
+
+impl ArenaConfig {
+    /// A fresh run on `seed`, played with this configuration.
+    pub fn new_run(&self, seed: u64) -> Result<Run, RulesError> {
+        Run::with_pools(
+            seed,
+            self.layout.clone(),
+            self.rules.clone(),
+            &self.tiles,
+            &self.special_cards,
+        )
+    }
+}
+
+/// The first line a bot sends.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Hello {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ArenaMessage {
+    Turn {
+        seed: u64,
+        state: RunView,
+        legal_actions: Vec<Action>,
+    },
+    End {
+        seed: u64,
+        won: bool,
+        turns_left: i32,
+        /// Why the bot lost the game without playing it out.
+        forfeit: Option<String>,
+    },
+}
+
+/// What a bot is shown of a run: the same as a player, so nothing about unrevealed tiles or cards on the board.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunView {
+    pub phase: Phase,
+    pub turns_left: i32,
+    pub movement_points: i32,
+    pub current: u32,
+    pub visited: Vec<u32>,
+    pub next_tiles: Vec<u32>,
+    pub tiles: Vec<TileView>,
+    pub drawn: Vec<u32>,
+    pub deck_remaining: usize,
+    pub special_cards: Vec<SpecialCardView>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TileView {
+    pub tile: u32,
+    pub cost: Option<u32>,
+    pub tile_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpecialCardView {
+    pub slot: usize,
+    pub place: CardPlace,
+    pub name: Option<String>,
+    pub value: Option<i32>,
+    pub playable: bool,
+}
+
+impl RunView {
+    pub fn of(run: &Run) -> Self {
+        RunView {
+            phase: run.phase,
+            turns_left: run.turns_left,
+            movement_points: run.movement_points,
+            current: run.current,
+            visited: run.visited.clone(),
+            next_tiles: run.next_tiles.clone(),
+            tiles: run
+                .tiles
+                .iter()
+                .zip(&run.revealed)
+                .map(|(tile, revealed)| TileView {
+                    tile: tile.number,
+                    cost: revealed.then_some(tile.cost),
+                    tile_type: revealed.then(|| format!("{:?}", tile.tile_type)),
+                })
+                .collect(),
+            drawn: run.drawn.iter().map(|card| card.value).collect(),
+            deck_remaining: run.deck.len(),
+            special_cards: run
+                .special_cards
+                .iter()
+                .enumerate()
+                .map(|(slot, (card, place))| {
+                    let face_up = matches!(place, CardPlace::Hand | CardPlace::Pending);
+
+                    SpecialCardView {
+                        slot,
+                        place: *place,
+                        name: face_up.then(|| card.name.clone()),
+                        value: face_up.then_some(card.value),
+                        playable: *place != CardPlace::Discarded && run.is_playable(slot),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameResult {
+    pub seed: u64,
+    pub won: bool,
+    pub turns_left: i32,
+    pub forfeit: Option<String>,
+}
+
+/// How one bot did over the whole tournament.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BotStanding {
+    pub name: String,
+    pub games: usize,
+    pub wins: usize,
+    /// Turns left at the end of every won game, added up.
+    pub surplus_turns: i64,
+    pub forfeits: usize,
+}
+
+impl BotStanding {
+    pub fn record(&mut self, result: &GameResult) {
+        self.games += 1;
+
+        if result.won {
+            self.wins += 1;
+            self.surplus_turns += result.turns_left as i64;
+        }
+
+        if result.forfeit.is_some() {
+            self.forfeits += 1;
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+
+        self.wins as f64 / self.games as f64
+    }
+
+    /// Turns to spare per won game.
+    pub fn average_surplus_turns(&self) -> f64 {
+        if self.wins == 0 {
+            return 0.0;
+        }
+
+        self.surplus_turns as f64 / self.wins as f64
+    }
+}
+
+pub struct Arena {
+    listener: TcpListener,
+    config: ArenaConfig,
+}
+
+impl Arena {
+    pub fn bind(address: impl ToSocketAddrs, config: ArenaConfig) -> io::Result<Self> {
+        Ok(Arena {
+            listener: TcpListener::bind(address)?,
+            config,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Waits for the bots, then plays every seed with all of them, each bot on a thread of its own.
+    pub fn run(&self) -> io::Result<Vec<BotStanding>> {
+        // A seed whose board cannot be built is the organiser's mistake, not a bot's.
+        for seed in &self.config.seeds {
+            self.config
+                .new_run(*seed)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+        }
+
+        let mut bots = self.accept_bots()?;
+        let mut standings: Vec<BotStanding> = bots
+            .iter()
+            .map(|bot| BotStanding {
+                name: bot.name.clone(),
+                ..BotStanding::default()
+            })
+            .collect();
+
+        for seed in &self.config.seeds {
+            let results: Vec<GameResult> = thread::scope(|scope| {
+                let games: Vec<_> = bots
+                    .iter_mut()
+                    .map(|bot| scope.spawn(move || bot.play(*seed, &self.config)))
+                    .collect();
+
+                games
+                    .into_iter()
+                    .map(|game| game.join().expect("arena game thread panicked"))
+                    .collect()
+            });
+
+            for (standing, result) in standings.iter_mut().zip(&results) {
+                standing.record(result);
+            }
+        }
+
+        Ok(standings)
+    }
+
+    fn accept_bots(&self) -> io::Result<Vec<BotConnection>> {
+        let mut bots: Vec<BotConnection> = vec![];
+
+        while bots.len() < self.config.bots {
+            let (stream, address) = self.listener.accept()?;
+
+            match BotConnection::greet(stream, self.config.move_time) {
+                Ok(mut bot) => {
+                    if bots.iter().any(|b| b.name == bot.name) {
+                        bot.name = format!("{}-{}", bot.name, bots.len() + 1);
+                    }
+
+                    bots.push(bot);
+                }
+                Err(err) => warn!("Turned away a bot from {}: {}", address, err),
+            }
+        }
+
+        Ok(bots)
+    }
+}
+
+struct BotConnection {
+    name: String,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    /// Took too long to answer, so the answer is still on its way and must not be read as the next one.
+    owes_answer: bool,
+    /// Never sent the answer it owed; it forfeits every game left without being asked anything.
+    dropped: bool,
+}
+
+impl BotConnection {
+    fn greet(stream: TcpStream, move_time: Duration) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+
+        let mut bot = BotConnection {
+            name: String::new(),
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            owes_answer: false,
+            dropped: false,
+        };
+
+        let hello: Hello = serde_json::from_str(&bot.read_line(Instant::now() + move_time)?)?;
+        bot.name = hello.name;
+
+        Ok(bot)
+    }
+
+    fn play(&mut self, seed: u64, config: &ArenaConfig) -> GameResult {
+        let mut run = config
+            .new_run(seed)
+            .expect("seeds are checked before the tournament");
+        let mut forfeit = self.catch_up(config.move_time).err();
+
+        while forfeit.is_none() && !run.is_over() {
+            forfeit = self.turn(&mut run, config.move_time).err();
+        }
+
+        let result = GameResult {
+            seed,
+            won: forfeit.is_none() && run.phase == Phase::Won,
+            turns_left: run.turns_left,
+            forfeit,
+        };
+
+        // The bot may be gone already; the result stands either way.
+        let _ = self.send(&ArenaMessage::End {
+            seed,
+            won: result.won,
+            turns_left: result.turns_left,
+            forfeit: result.forfeit.clone(),
+        });
+
+        result
+    }
+
+    /// Asks for one action and plays it, or says why the bot forfeits.
+    fn turn(&mut self, run: &mut Run, move_time: Duration) -> Result<(), String> {
+        let message = ArenaMessage::Turn {
+            seed: run.seed,
+            state: RunView::of(run),
+            legal_actions: run.legal_actions(),
+        };
+
+        self.send(&message).map_err(|err| format!("could not be reached: {}", err))?;
+
+        let line = self.read_line(Instant::now() + move_time).map_err(|err| match err.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+                self.owes_answer = true;
+                format!("took longer than {} ms", move_time.as_millis())
+            }
+            _ => format!("could not be read: {}", err),
+        })?;
+
+        let action: Action = serde_json::from_str(&line)
+            .map_err(|err| format!("sent an unreadable action: {}", err))?;
+
+        run.apply(action).map_err(|err| err.to_string())
+    }
+
+    fn send(&mut self, message: &ArenaMessage) -> io::Result<()> {
+        let line = serde_json::to_string(message)?;
+        writeln!(self.writer, "{}", line)?;
+        self.writer.flush()
+    }
+
+    /// Throws away the answer owed from a game lost on time, so the next game starts in step,
+    /// or drops the bot if that answer does not come.
+    fn catch_up(&mut self, move_time: Duration) -> Result<(), String> {
+        if self.dropped {
+            return Err(String::from("was dropped for not answering"));
+        }
+
+        if !self.owes_answer {
+            return Ok(());
+        }
+
+        match self.read_line(Instant::now() + move_time * LATE_ANSWER_MOVES) {
+            Ok(_) => {
+                self.owes_answer = false;
+                Ok(())
+            }
+            Err(err) => {
+                self.dropped = true;
+                let _ = self.writer.shutdown(Shutdown::Both);
+                Err(format!("never answered an earlier turn: {}", err))
+            }
+        }
+    }
+
+    /// Reads one line, all of it before `deadline`, however the bot splits it up.
+    fn read_line(&mut self, deadline: Instant) -> io::Result<String> {
+        let mut line = vec![];
+
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+
+            if left.is_zero() {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+
+            self.reader.get_ref().set_read_timeout(Some(left))?;
+            let available = self.reader.fill_buf()?;
+
+            if available.is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            let (used, done) = match available.iter().position(|byte| *byte == b'\n') {
+                Some(end) => (end + 1, true),
+                None => (available.len(), false),
+            };
+
+            line.extend_from_slice(&available[..used]);
+            self.reader.consume(used);
+
+            if done {
+                return String::from_utf8(line).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err));
+            }
+        }
+    }
+}
+
+/// Writes a header, then one row per bot.
+pub fn write_standings_csv(standings: &[BotStanding], mut out: impl Write) -> io::Result<()> {
+    writeln!(out, "bot,games,wins,forfeits,win_rate,average_surplus_turns")?;
+
+    for standing in standings {
+        writeln!(
+            out,
+            "{},{},{},{},{:.3},{:.2}",
+            csv_field(&standing.name),
+            standing.games,
+            standing.wins,
+            standing.forfeits,
+            standing.win_rate(),
+            standing.average_surplus_turns()
+        )?;
+    }
+
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
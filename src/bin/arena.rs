@@ -0,0 +1,118 @@
+//! Runs a bot tournament on localhost and writes the standings to a CSV file.
+
+use std::{fs::File, path::Path, process, time::Duration};
+
+use treacherous_safari::{
+    arena::{write_standings_csv, Arena, ArenaConfig, DEFAULT_ADDRESS},
+    board::TilePool,
+    special_cards::SpecialCardPool,
+};
+
+const USAGE: &str = "\
+Usage: arena [options]
+
+  --address <host:port>   where bots connect (default 127.0.0.1:7878)
+  --bots <n>              bots to wait for before starting (default 2)
+  --seeds <a..b|a,b,c>    seeds to play, one round each (default 0..10)
+  --move-time-ms <ms>     time a bot has for each action (default 1000)
+  --tiles <file>          JSON list of the tiles boards are drawn from
+  --cards <file>          JSON list of the special cards runs are dealt from
+  --out <file>            CSV file for the standings (default standings.csv)";
+
+fn main() {
+    let mut config = ArenaConfig::default();
+    let mut address = DEFAULT_ADDRESS.to_string();
+    let mut out = String::from("standings.csv");
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" {
+            println!("{}", USAGE);
+            return;
+        }
+
+        let Some(value) = args.next() else {
+            fail(&format!("{} needs a value", arg));
+        };
+
+        let parsed = match arg.as_str() {
+            "--address" => {
+                address = value.clone();
+                Some(())
+            }
+            "--bots" => value.parse().ok().map(|bots| config.bots = bots),
+            "--seeds" => parse_seeds(&value).map(|seeds| config.seeds = seeds),
+            "--move-time-ms" => value
+                .parse()
+                .ok()
+                .map(|ms| config.move_time = Duration::from_millis(ms)),
+            "--tiles" => {
+                config.tiles = TilePool::load(Path::new(&value))
+                    .unwrap_or_else(|err| fail(&format!("could not read {}: {}", value, err)));
+                Some(())
+            }
+            "--cards" => {
+                config.special_cards = SpecialCardPool::load(Path::new(&value))
+                    .unwrap_or_else(|err| fail(&format!("could not read {}: {}", value, err)));
+                Some(())
+            }
+            "--out" => {
+                out = value.clone();
+                Some(())
+            }
+            _ => fail(&format!("unknown option {}", arg)),
+        };
+
+        if parsed.is_none() {
+            fail(&format!("bad value for {}: {}", arg, value));
+        }
+    }
+
+    let arena = Arena::bind(&address, config.clone())
+        .unwrap_or_else(|err| fail(&format!("could not listen on {}: {}", address, err)));
+
+    println!(
+        "Waiting for {} bots on {} to play {} seeds",
+        config.bots,
+        address,
+        config.seeds.len()
+    );
+
+    let standings = arena
+        .run()
+        .unwrap_or_else(|err| fail(&format!("tournament stopped: {}", err)));
+
+    for standing in &standings {
+        println!(
+            "{}: {}/{} won, {:.2} turns to spare",
+            standing.name,
+            standing.wins,
+            standing.games,
+            standing.average_surplus_turns()
+        );
+    }
+
+    let file = File::create(&out).unwrap_or_else(|err| fail(&format!("could not create {}: {}", out, err)));
+
+    if let Err(err) = write_standings_csv(&standings, file) {
+        fail(&format!("could not write {}: {}", out, err));
+    }
+
+    println!("Standings written to {}", out);
+}
+
+fn parse_seeds(value: &str) -> Option<Vec<u64>> {
+    if let Some((start, end)) = value.split_once("..") {
+        let start: u64 = start.parse().ok()?;
+        let end: u64 = end.parse().ok()?;
+        return Some((start..end).collect());
+    }
+
+    value.split(',').map(|seed| seed.trim().parse().ok()).collect()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
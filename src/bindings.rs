@@ -0,0 +1,465 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::Path,
+};
+
+use bevy::{
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, Enum, TypeInfo, Typed},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::ConfigDirectory, settings::settings_screen_closed};
+
+pub const BINDINGS_FILE: &str = "bindings.json";
+
+/// Keeps the keys bound to every `GameAction` in an `ActionMap`, loaded from the config directory at startup,
+/// and a screen to rebind them.
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<ConfigDirectory>() {
+            app.insert_resource(ConfigDirectory::from_env());
+        }
+
+        if !app.world.contains_resource::<ActionMap>() {
+            let path = app.world.resource::<ConfigDirectory>().file(BINDINGS_FILE);
+            app.insert_resource(ActionMap::load_or_default(path.as_deref()));
+        }
+
+        app.init_resource::<BindingsScreen>()
+            .add_systems(Startup, setup_bindings_screen)
+            .add_systems(
+                Update,
+                (
+                    bindings_screen_input.run_if(settings_screen_closed),
+                    update_bindings_screen.run_if(
+                        resource_changed::<BindingsScreen>().or_else(resource_changed::<ActionMap>()),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Something the player can do with a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Cancel,
+    Ledger,
+    Console,
+    Inspector,
+    KeyBindings,
+    Settings,
+    ZoomIn,
+    ZoomOut,
+    /// Zooms out to show the whole board again.
+    FitView,
+    /// Not in the game yet: a confirmed move cannot be taken back, so pressing it only says so.
+    Undo,
+    /// Not in the game yet: nothing suggests a move, so pressing it only says so.
+    Hint,
+}
+
+impl GameAction {
+    pub const ALL: [GameAction; 16] = [
+        GameAction::Up,
+        GameAction::Down,
+        GameAction::Left,
+        GameAction::Right,
+        GameAction::Confirm,
+        GameAction::Cancel,
+        GameAction::Ledger,
+        GameAction::Console,
+        GameAction::Inspector,
+        GameAction::KeyBindings,
+        GameAction::Settings,
+        GameAction::ZoomIn,
+        GameAction::ZoomOut,
+        GameAction::FitView,
+        GameAction::Undo,
+        GameAction::Hint,
+    ];
+
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            GameAction::Up => vec![KeyCode::Up, KeyCode::W],
+            GameAction::Down => vec![KeyCode::Down, KeyCode::S],
+            GameAction::Left => vec![KeyCode::Left, KeyCode::A],
+            GameAction::Right => vec![KeyCode::Right, KeyCode::D],
+            GameAction::Confirm => vec![KeyCode::Return, KeyCode::Space],
+            GameAction::Cancel => vec![KeyCode::Back],
+            GameAction::Ledger => vec![KeyCode::L],
+            GameAction::Console => vec![KeyCode::Grave],
+            GameAction::Inspector => vec![KeyCode::Escape],
+            GameAction::KeyBindings => vec![KeyCode::F1],
+            GameAction::Settings => vec![KeyCode::F2],
+            GameAction::ZoomIn => vec![KeyCode::Equals, KeyCode::NumpadAdd],
+            GameAction::ZoomOut => vec![KeyCode::Minus, KeyCode::NumpadSubtract],
+            GameAction::FitView => vec![KeyCode::Home],
+            GameAction::Undo => vec![KeyCode::Z],
+            GameAction::Hint => vec![KeyCode::H],
+        }
+    }
+}
+
+impl fmt::Display for GameAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GameAction::Up => "Up",
+            GameAction::Down => "Down",
+            GameAction::Left => "Left",
+            GameAction::Right => "Right",
+            GameAction::Confirm => "Confirm",
+            GameAction::Cancel => "Cancel",
+            GameAction::Ledger => "History panel",
+            GameAction::Console => "Developer console",
+            GameAction::Inspector => "World inspector",
+            GameAction::KeyBindings => "Key bindings",
+            GameAction::Settings => "Settings",
+            GameAction::ZoomIn => "Zoom in",
+            GameAction::ZoomOut => "Zoom out",
+            GameAction::FitView => "Show the whole board",
+            GameAction::Undo => "Undo",
+            GameAction::Hint => "Hint",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The keys bound to each action. A key belongs to one action at most.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ActionMap {
+    keys: BTreeMap<GameAction, Vec<KeyCode>>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        ActionMap {
+            keys: GameAction::ALL
+                .iter()
+                .map(|action| (*action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+/// How bindings are saved: key names by action.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct BindingsFile {
+    bindings: BTreeMap<GameAction, Vec<String>>,
+}
+
+impl ActionMap {
+    pub fn keys(&self, action: GameAction) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], |keys| keys.as_slice())
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<GameAction> {
+        self.keys
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn just_pressed(&self, action: GameAction, input: &Input<KeyCode>) -> bool {
+        input.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    /// Whether any key of `action` is held down.
+    pub fn pressed(&self, action: GameAction, input: &Input<KeyCode>) -> bool {
+        input.any_pressed(self.keys(action).iter().copied())
+    }
+
+    /// Makes `key` the only key for `action`, unless another action has it, which is returned instead.
+    pub fn rebind(&mut self, action: GameAction, key: KeyCode) -> Result<(), GameAction> {
+        match self.action(key) {
+            Some(other) if other != action => Err(other),
+            _ => {
+                self.keys.insert(action, vec![key]);
+                Ok(())
+            }
+        }
+    }
+
+    /// Keys bound to more than one action, with the actions sharing them.
+    pub fn conflicts(&self) -> Vec<(KeyCode, Vec<GameAction>)> {
+        let mut conflicts: Vec<(KeyCode, Vec<GameAction>)> = vec![];
+
+        for (action, keys) in &self.keys {
+            for key in keys {
+                match conflicts.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, actions)) => actions.push(*action),
+                    None => conflicts.push((*key, vec![*action])),
+                }
+            }
+        }
+
+        conflicts.retain(|(_, actions)| actions.len() > 1);
+        conflicts
+    }
+
+    /// Reads bindings saved by `save`. Actions missing from the file keep their default keys.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file: BindingsFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut map = ActionMap::default();
+
+        for (action, names) in file.bindings {
+            let keys = names
+                .iter()
+                .map(|name| {
+                    key_from_name(name).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, format!("unknown key {}", name))
+                    })
+                })
+                .collect::<io::Result<Vec<KeyCode>>>()?;
+
+            map.keys.insert(action, keys);
+        }
+
+        if let Some((key, actions)) = map.conflicts().first() {
+            let message = format!("{} is bound to {:?}", key_name(*key), actions);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+
+        Ok(map)
+    }
+
+    /// The saved bindings, or the defaults when there are none or they cannot be used.
+    pub fn load_or_default(path: Option<&Path>) -> Self {
+        let Some(path) = path.filter(|path| path.exists()) else {
+            return ActionMap::default();
+        };
+
+        ActionMap::load(path).unwrap_or_else(|err| {
+            warn!("Ignored the key bindings in {}: {}", path.display(), err);
+            ActionMap::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = BindingsFile {
+            bindings: self
+                .keys
+                .iter()
+                .map(|(action, keys)| (*action, keys.iter().map(|key| key_name(*key)).collect()))
+                .collect(),
+        };
+
+        fs::write(path, serde_json::to_string_pretty(&file)?)
+    }
+}
+
+pub fn key_name(key: KeyCode) -> String {
+    key.variant_name().to_string()
+}
+
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    // Building a key from a variant that does not exist panics, so the name is looked up first.
+    let TypeInfo::Enum(info) = KeyCode::type_info() else {
+        return None;
+    };
+
+    if !info.contains_variant(name) {
+        return None;
+    }
+
+    KeyCode::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
+}
+
+/// Like `input_toggle_active`, for the keys bound to `action`.
+pub fn action_toggle_active(
+    default: bool,
+    action: GameAction,
+) -> impl FnMut(Res<Input<KeyCode>>, Res<ActionMap>, Local<Option<bool>>) -> bool + Clone {
+    move |input: Res<Input<KeyCode>>, map: Res<ActionMap>, mut active: Local<Option<bool>>| {
+        let active = active.get_or_insert(default);
+
+        if map.just_pressed(action, &input) {
+            *active = !*active;
+        }
+
+        *active
+    }
+}
+
+/// The rebinding screen, opened with the `KeyBindings` key.
+#[derive(Resource, Default, Debug)]
+pub struct BindingsScreen {
+    pub open: bool,
+    /// Row of `GameAction::ALL`, or one past it for resetting to the defaults.
+    pub selected: usize,
+    /// Waiting for the key to bind to the selected action.
+    pub listening: bool,
+    pub message: Option<String>,
+}
+
+/// Whether the rebinding screen is closed, so the keys it uses mean what they do in the game.
+pub fn bindings_screen_closed(screen: Option<Res<BindingsScreen>>) -> bool {
+    !screen.is_some_and(|screen| screen.open)
+}
+
+#[derive(Component)]
+pub struct BindingsScreenText;
+
+pub fn setup_bindings_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    left: Val::Percent(25.0),
+                    top: Val::Percent(15.0),
+                    width: Val::Percent(50.0),
+                    height: Val::Percent(70.0),
+                    padding: UiRect::all(Val::Px(16.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.9).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            BindingsScreenText,
+        ))
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                ..default()
+            });
+        });
+}
+
+pub fn bindings_screen_input(
+    keys: Res<Input<KeyCode>>,
+    mut screen: ResMut<BindingsScreen>,
+    mut map: ResMut<ActionMap>,
+    config: Res<ConfigDirectory>,
+) {
+    if !screen.open {
+        if map.just_pressed(GameAction::KeyBindings, &keys) {
+            *screen = BindingsScreen {
+                open: true,
+                ..default()
+            };
+        }
+
+        return;
+    }
+
+    if screen.listening {
+        let Some(key) = keys.get_just_pressed().next().copied() else {
+            return;
+        };
+
+        let action = GameAction::ALL[screen.selected];
+        screen.listening = false;
+
+        screen.message = Some(match map.rebind(action, key) {
+            Ok(()) => save_bindings(&map, &config),
+            Err(other) => format!("{} is already bound to {}", key_name(key), other),
+        });
+
+        return;
+    }
+
+    let rows = GameAction::ALL.len() + 1;
+
+    if map.just_pressed(GameAction::Up, &keys) {
+        screen.selected = (screen.selected + rows - 1) % rows;
+    } else if map.just_pressed(GameAction::Down, &keys) {
+        screen.selected = (screen.selected + 1) % rows;
+    } else if map.just_pressed(GameAction::Confirm, &keys) {
+        if screen.selected == GameAction::ALL.len() {
+            *map = ActionMap::default();
+            screen.message = Some(save_bindings(&map, &config));
+        } else {
+            screen.listening = true;
+            screen.message = None;
+        }
+    } else if map.just_pressed(GameAction::Cancel, &keys)
+        || map.just_pressed(GameAction::KeyBindings, &keys)
+    {
+        screen.open = false;
+    }
+}
+
+/// Saves the bindings, returning what to tell the player.
+fn save_bindings(map: &ActionMap, config: &ConfigDirectory) -> String {
+    let Some(path) = config.file(BINDINGS_FILE) else {
+        return String::from("Bindings changed for this session");
+    };
+
+    match map.save(&path) {
+        Ok(()) => String::from("Bindings saved"),
+        Err(err) => {
+            warn!("Could not save key bindings to {}: {}", path.display(), err);
+            format!("Could not save bindings: {}", err)
+        }
+    }
+}
+
+pub fn update_bindings_screen(
+    screen: Res<BindingsScreen>,
+    map: Res<ActionMap>,
+    mut panel: Query<(&mut Visibility, &Children), With<BindingsScreenText>>,
+    mut texts: Query<&mut Text>,
+) {
+    let mut lines = vec![String::from("Key bindings"), String::new()];
+
+    for (row, action) in GameAction::ALL.iter().enumerate() {
+        let keys: Vec<String> = map.keys(*action).iter().map(|key| key_name(*key)).collect();
+        let keys = if screen.listening && row == screen.selected {
+            String::from("press a key...")
+        } else {
+            keys.join(", ")
+        };
+
+        lines.push(format!("{} {}: {}", cursor(&screen, row), action, keys));
+    }
+
+    lines.push(format!("{} Reset to defaults", cursor(&screen, GameAction::ALL.len())));
+    lines.push(String::new());
+    lines.push(screen.message.clone().unwrap_or_default());
+
+    for (mut vis, children) in &mut panel {
+        *vis = if screen.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = lines.join("\n");
+            }
+        }
+    }
+}
+
+fn cursor(screen: &BindingsScreen, row: usize) -> &'static str {
+    if row == screen.selected {
+        ">"
+    } else {
+        " "
+    }
+}
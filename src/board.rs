@@ -0,0 +1,434 @@
+use std::{fs, io, path::Path};
+
+use bevy::prelude::*;
+use rand::{distributions::WeightedIndex, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    movement::{generate_cards, CARDS_TO_DRAW},
+    tiles::{generate_tiles, Tile, TileType, TURNS_INIT_VALUE},
+};
+
+const MAX_ATTEMPTS: u32 = 200;
+const REPAIR_ATTEMPTS: u32 = 8;
+/// Seeds tried, the run's own first, before giving up on building a board.
+pub const BOARD_SEED_ATTEMPTS: u64 = 100;
+
+/// Shape of the board: the rows present in each column, left to right.
+/// Tiles are numbered column by column, top to bottom.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardLayout {
+    pub columns: Vec<Vec<u32>>,
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        BoardLayout {
+            columns: vec![vec![1], vec![0, 1, 2], vec![0, 1, 2], vec![0, 1, 2], vec![1]],
+        }
+    }
+}
+
+impl BoardLayout {
+    pub fn tile_count(&self) -> u32 {
+        self.columns.iter().map(|c| c.len() as u32).sum()
+    }
+
+    pub fn last_tile(&self) -> u32 {
+        self.tile_count() - 1
+    }
+
+    /// Column and row of a tile number.
+    pub fn position(&self, number: u32) -> Option<(usize, u32)> {
+        let mut first = 0;
+
+        for (x, column) in self.columns.iter().enumerate() {
+            let len = column.len() as u32;
+            if number < first + len {
+                return Some((x, column[(number - first) as usize]));
+            }
+            first += len;
+        }
+
+        None
+    }
+
+    pub fn number_at(&self, x: usize, y: u32) -> Option<u32> {
+        let first: usize = self.columns.iter().take(x).map(|c| c.len()).sum();
+        let index = self.columns.get(x)?.iter().position(|row| *row == y)?;
+
+        Some((first + index) as u32)
+    }
+
+    /// Tiles reachable from `number`: the adjacent rows of its column and the touching rows of the next column.
+    pub fn neighbours(&self, number: u32) -> Vec<u32> {
+        let Some((x, y)) = self.position(number) else {
+            return vec![];
+        };
+
+        if number == self.last_tile() {
+            return vec![0];
+        }
+
+        let mut neighbours: Vec<u32> = [y.wrapping_sub(1), y + 1]
+            .into_iter()
+            .filter_map(|row| self.number_at(x, row))
+            .collect();
+        neighbours.extend(self.touching(x + 1, y));
+
+        neighbours.sort();
+        neighbours
+    }
+
+    /// Touching rows of the previous column, which `neighbours` leaves out.
+    pub fn backward_neighbours(&self, number: u32) -> Vec<u32> {
+        match self.position(number) {
+            Some((x, y)) if x > 0 => self.touching(x - 1, y),
+            _ => vec![],
+        }
+    }
+
+    fn touching(&self, x: usize, y: u32) -> Vec<u32> {
+        [y.wrapping_sub(1), y, y + 1]
+            .into_iter()
+            .filter_map(|row| self.number_at(x, row))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TilePoolEntry {
+    pub tile: Tile,
+    /// Relative chance of being picked among the entries allowed in a column.
+    pub weight: u32,
+    /// Unique tiles appear at most once per board, matched by description.
+    pub unique: bool,
+}
+
+/// The tiles boards are drawn from.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TilePool {
+    pub entries: Vec<TilePoolEntry>,
+}
+
+impl Default for TilePool {
+    fn default() -> Self {
+        TilePool::from_tiles(generate_tiles())
+    }
+}
+
+impl TilePool {
+    /// Weighs each tile by its kind: plain ground is common, hazards less so and big rewards are one of a kind.
+    pub fn from_tiles(tiles: Vec<Tile>) -> Self {
+        let entries = tiles
+            .into_iter()
+            .map(|tile| {
+                let (weight, unique) = match tile.tile_type {
+                    TileType::Plain => (3, false),
+                    TileType::Blessing => (1, true),
+                    TileType::MovementPointsUpdate | TileType::TurnUpdate if tile.value < 0 => {
+                        (2, false)
+                    }
+                    TileType::MovementPointsUpdate | TileType::TurnUpdate => (1, tile.value > 1),
+                };
+
+                TilePoolEntry {
+                    tile,
+                    weight,
+                    unique,
+                }
+            })
+            .collect();
+
+        TilePool { entries }
+    }
+
+    /// Reads a JSON list of tiles, such as the ones `generate_tiles` makes.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let tiles: Vec<Tile> = serde_json::from_str(&json)?;
+
+        if tiles.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no tiles in the file"));
+        }
+
+        Ok(TilePool::from_tiles(tiles))
+    }
+
+    /// The entries a covered tile in a column of cost `tier` may turn out to be, once the tiles in `face_up`
+    /// are known: a unique tile already showing elsewhere on the board is not among them.
+    pub fn covered_options(&self, (min, max): (u32, u32), face_up: &[&Tile]) -> TilePool {
+        let entries = self
+            .entries
+            .iter()
+            .filter(|e| (min..=max).contains(&e.tile.cost))
+            .filter(|e| !e.unique || face_up.iter().all(|t| t.description != e.tile.description))
+            .cloned()
+            .collect();
+
+        TilePool { entries }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BoardConstraints {
+    /// Inclusive cost range allowed in each column, one per column of the layout.
+    pub cost_tiers: Vec<(u32, u32)>,
+    pub max_hazards: usize,
+    pub turns: i32,
+    pub expected_points_per_turn: f32,
+}
+
+impl BoardConstraints {
+    /// The inclusive cost range of `column`; a column past the tiers takes any cost.
+    pub fn cost_tier(&self, column: usize) -> (u32, u32) {
+        self.cost_tiers.get(column).copied().unwrap_or((1, 9))
+    }
+}
+
+impl Default for BoardConstraints {
+    fn default() -> Self {
+        let deck = generate_cards();
+        let mean = deck.iter().map(|c| c.value as f32).sum::<f32>() / deck.len() as f32;
+
+        BoardConstraints {
+            cost_tiers: vec![(1, 2), (1, 3), (4, 6), (7, 9), (7, 9)],
+            max_hazards: 4,
+            turns: TURNS_INIT_VALUE,
+            expected_points_per_turn: mean * CARDS_TO_DRAW as f32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardError {
+    EmptyTier(usize),
+    Unsolvable { attempts: u32 },
+}
+
+impl std::fmt::Display for BoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BoardError::EmptyTier(column) => write!(f, "no tiles fit the cost tier of column {}", column),
+            BoardError::Unsolvable { attempts } => {
+                write!(f, "no valid board found after {} attempts", attempts)
+            }
+        }
+    }
+}
+
+pub struct BoardGenerator {
+    pub layout: BoardLayout,
+    pub pool: TilePool,
+    pub constraints: BoardConstraints,
+}
+
+impl BoardGenerator {
+    pub fn new(layout: BoardLayout) -> Self {
+        BoardGenerator::with_pool(layout, TilePool::default())
+    }
+
+    pub fn with_pool(layout: BoardLayout, pool: TilePool) -> Self {
+        BoardGenerator {
+            layout,
+            pool,
+            constraints: BoardConstraints::default(),
+        }
+    }
+
+    /// Builds a valid board for `seed`, numbered in layout order. The same seed always gives the same board.
+    pub fn generate(&self, seed: u64) -> Result<Vec<Tile>, BoardError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        for _ in 0..MAX_ATTEMPTS {
+            let mut board = self.roll(&mut rng)?;
+
+            for _ in 0..REPAIR_ATTEMPTS {
+                if self.hazard_count(&board) <= self.constraints.max_hazards {
+                    break;
+                }
+                self.repair_hazard(&mut board, &mut rng);
+            }
+
+            if self.is_valid(&board) {
+                return Ok(board);
+            }
+        }
+
+        Err(BoardError::Unsolvable {
+            attempts: MAX_ATTEMPTS,
+        })
+    }
+
+    /// Builds the board for `seed`, or for the first of the seeds after it that gives one, and says which seed that was.
+    /// Fails with the last seed's error once `BOARD_SEED_ATTEMPTS` seeds have been tried,
+    /// or at once when a column has no tiles to draw from, which no seed can help.
+    pub fn generate_near(&self, seed: u64) -> Result<(u64, Vec<Tile>), BoardError> {
+        if let Some(column) = (0..self.layout.columns.len()).find(|x| self.candidates(*x, &[]).is_empty()) {
+            return Err(BoardError::EmptyTier(column));
+        }
+
+        let mut err = None;
+
+        for attempt in 0..BOARD_SEED_ATTEMPTS {
+            let tried = seed.wrapping_add(attempt);
+
+            match self.generate(tried) {
+                Ok(board) => return Ok((tried, board)),
+                Err(rejected) => {
+                    warn!("Board for seed {} rejected: {}", tried, rejected);
+                    err = Some(rejected);
+                }
+            }
+        }
+
+        Err(err.unwrap_or(BoardError::Unsolvable { attempts: 0 }))
+    }
+
+    pub fn is_valid(&self, board: &[Tile]) -> bool {
+        self.hazard_count(board) <= self.constraints.max_hazards
+            && !self.has_duplicate_unique(board)
+            && self.has_affordable_path(board)
+    }
+
+    fn candidates(&self, column: usize, board: &[Tile]) -> Vec<&TilePoolEntry> {
+        let (min, max) = self.constraints.cost_tier(column);
+
+        self.pool
+            .entries
+            .iter()
+            .filter(|e| e.tile.cost >= min && e.tile.cost <= max)
+            .filter(|e| !e.unique || board.iter().all(|t| t.description != e.tile.description))
+            .collect()
+    }
+
+    fn pick(&self, column: usize, board: &[Tile], rng: &mut StdRng) -> Option<Tile> {
+        let candidates = self.candidates(column, board);
+        let weights = WeightedIndex::new(candidates.iter().map(|e| e.weight)).ok()?;
+
+        Some(candidates[weights.sample(rng)].tile.clone())
+    }
+
+    fn roll(&self, rng: &mut StdRng) -> Result<Vec<Tile>, BoardError> {
+        let mut board: Vec<Tile> = Vec::with_capacity(self.layout.tile_count() as usize);
+
+        for (x, column) in self.layout.columns.iter().enumerate() {
+            for _ in column {
+                let mut tile = self.pick(x, &board, rng).ok_or(BoardError::EmptyTier(x))?;
+                tile.number = board.len() as u32;
+                board.push(tile);
+            }
+        }
+
+        Ok(board)
+    }
+
+    fn repair_hazard(&self, board: &mut [Tile], rng: &mut StdRng) {
+        let hazards: Vec<usize> = (0..board.len()).filter(|i| is_hazard(&board[*i])).collect();
+        let index = hazards[rng.gen_range(0..hazards.len())];
+        let (x, _) = self.layout.position(index as u32).unwrap();
+
+        let safe: Vec<&TilePoolEntry> = self
+            .candidates(x, board)
+            .into_iter()
+            .filter(|e| !is_hazard(&e.tile))
+            .collect();
+
+        if let Ok(weights) = WeightedIndex::new(safe.iter().map(|e| e.weight)) {
+            let mut tile = safe[weights.sample(rng)].tile.clone();
+            tile.number = index as u32;
+            board[index] = tile;
+        }
+    }
+
+    fn hazard_count(&self, board: &[Tile]) -> usize {
+        board.iter().filter(|t| is_hazard(t)).count()
+    }
+
+    fn has_duplicate_unique(&self, board: &[Tile]) -> bool {
+        self.pool.entries.iter().filter(|e| e.unique).any(|e| {
+            board
+                .iter()
+                .filter(|t| t.description == e.tile.description)
+                .count()
+                > 1
+        })
+    }
+
+    /// Whether some route from the first to the last tile can be paid for with the expected card draws.
+    fn has_affordable_path(&self, board: &[Tile]) -> bool {
+        let mut path = vec![0];
+        self.walk(board, &mut path)
+    }
+
+    fn walk(&self, board: &[Tile], path: &mut Vec<u32>) -> bool {
+        let current = *path.last().unwrap();
+
+        if current == self.layout.last_tile() {
+            return self.path_is_affordable(board, path);
+        }
+
+        for next in self.layout.neighbours(current) {
+            if path.contains(&next) {
+                continue;
+            }
+
+            path.push(next);
+            if self.walk(board, path) {
+                return true;
+            }
+            path.pop();
+        }
+
+        false
+    }
+
+    fn path_is_affordable(&self, board: &[Tile], path: &[u32]) -> bool {
+        let mut points = 0.0;
+        let mut turns = self.constraints.turns;
+        let mut afflictions: Vec<(f32, Option<i32>)> = Vec::new();
+
+        for number in path {
+            let tile = &board[*number as usize];
+
+            match tile.tile_type {
+                TileType::Plain => {}
+                TileType::MovementPointsUpdate if tile.duration != 0 => {
+                    let remaining = if tile.duration < 0 { None } else { Some(tile.duration) };
+                    afflictions.push((tile.value as f32, remaining));
+                }
+                TileType::MovementPointsUpdate => points += tile.value as f32,
+                TileType::TurnUpdate => turns += tile.value,
+                TileType::Blessing => afflictions.clear(),
+            }
+
+            loop {
+                if turns <= 0 {
+                    return false;
+                }
+
+                turns -= 1;
+                points += self.constraints.expected_points_per_turn;
+
+                for (value, remaining) in afflictions.iter_mut() {
+                    points += *value;
+                    *remaining = remaining.map(|r| r - 1);
+                }
+                afflictions.retain(|(_, remaining)| !remaining.is_some_and(|r| r <= 0));
+
+                if points >= tile.cost as f32 {
+                    break;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+fn is_hazard(tile: &Tile) -> bool {
+    matches!(
+        tile.tile_type,
+        TileType::MovementPointsUpdate | TileType::TurnUpdate
+    ) && tile.value < 0
+}
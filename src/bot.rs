@@ -0,0 +1,189 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Mutex,
+    },
+    thread,
+};
+
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diagnostics::RunSnapshot,
+    history::RunEndedEvent,
+    replay::{legal_actions, ActionQueue, PlayerAction},
+    tween::AnimationGate,
+};
+
+/// Frames the game is given after an action has been played before the state is reported.
+const SETTLE_FRAMES: u32 = 5;
+
+/// Lets another program play: reads one `PlayerAction` as JSON per line and answers each with a `BotResponse` line.
+/// Uses stdin and stdout unless a `BotIo` is set up front. Once the commands run out the app exits.
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<BotIo>() {
+            app.insert_resource(BotIo::stdio());
+        }
+
+        app.init_resource::<BotSession>()
+            .add_event::<RunEndedEvent>()
+            .add_systems(Update, record_run_end)
+            .add_systems(Last, run_bot_commands);
+    }
+}
+
+/// Where commands come from and responses go.
+#[derive(Resource)]
+pub struct BotIo {
+    commands: Mutex<Receiver<String>>,
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl BotIo {
+    pub fn new(commands: Receiver<String>, output: impl Write + Send + 'static) -> Self {
+        BotIo {
+            commands: Mutex::new(commands),
+            output: Mutex::new(Box::new(output)),
+        }
+    }
+
+    /// Reads stdin on a thread of its own, so the game keeps running while it waits.
+    pub fn stdio() -> Self {
+        let (sender, commands) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        BotIo::new(commands, io::stdout())
+    }
+}
+
+/// Sent once the game has set up and after every command.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BotResponse {
+    pub state: RunSnapshot,
+    pub legal_actions: Vec<PlayerAction>,
+    /// Whether the run was won, once it is over.
+    pub won: Option<bool>,
+    /// Why the last command was not played.
+    pub error: Option<String>,
+}
+
+#[derive(Resource, Debug)]
+pub struct BotSession {
+    /// An action is being played, or the game is still setting up.
+    waiting: bool,
+    settle: u32,
+    won: Option<bool>,
+}
+
+impl Default for BotSession {
+    fn default() -> Self {
+        BotSession {
+            waiting: true,
+            settle: SETTLE_FRAMES,
+            won: None,
+        }
+    }
+}
+
+pub fn record_run_end(mut events: EventReader<RunEndedEvent>, mut session: ResMut<BotSession>) {
+    for ev in events.read() {
+        session.won = Some(ev.won);
+    }
+}
+
+pub fn run_bot_commands(world: &mut World) {
+    // Answered once the turn has played out, animations and all, as a player would see it.
+    let animating = world.get_resource::<AnimationGate>().is_some_and(|gate| !gate.is_idle());
+    let idle = world.resource::<ActionQueue>().is_idle() && !animating;
+    let mut session = world.resource_mut::<BotSession>();
+
+    if session.waiting {
+        if !idle {
+            session.settle = SETTLE_FRAMES;
+        } else if session.settle > 0 {
+            session.settle -= 1;
+        } else {
+            session.waiting = false;
+
+            let error = world.resource_mut::<ActionQueue>().last_error.take();
+            respond(world, error);
+        }
+
+        return;
+    }
+
+    let line = world.resource::<BotIo>().commands.lock().unwrap().try_recv();
+
+    let line = match line {
+        Ok(line) => line,
+        Err(TryRecvError::Empty) => return,
+        Err(TryRecvError::Disconnected) => {
+            world.send_event(AppExit);
+            return;
+        }
+    };
+
+    if line.trim().is_empty() {
+        return;
+    }
+
+    let action = match serde_json::from_str::<PlayerAction>(&line) {
+        Ok(action) => action,
+        Err(err) => {
+            respond(world, Some(format!("unreadable command: {}", err)));
+            return;
+        }
+    };
+
+    if !current_legal_actions(world).contains(&action) {
+        respond(world, Some(format!("not a legal action now: {}", line.trim())));
+        return;
+    }
+
+    world.resource_mut::<ActionQueue>().push(action);
+
+    let mut session = world.resource_mut::<BotSession>();
+    session.waiting = true;
+    session.settle = SETTLE_FRAMES;
+}
+
+fn current_legal_actions(world: &mut World) -> Vec<PlayerAction> {
+    if world.resource::<BotSession>().won.is_some() {
+        return vec![];
+    }
+
+    legal_actions(world)
+}
+
+fn respond(world: &mut World, error: Option<String>) {
+    let response = BotResponse {
+        state: RunSnapshot::capture(world),
+        legal_actions: current_legal_actions(world),
+        won: world.resource::<BotSession>().won,
+        error,
+    };
+
+    let line = serde_json::to_string(&response).expect("bot responses serialize");
+    let io = world.resource::<BotIo>();
+    let mut output = io.output.lock().unwrap();
+
+    if let Err(err) = writeln!(output, "{}", line).and_then(|_| output.flush()) {
+        warn!("Could not write a bot response: {}", err);
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    fs, io, panic,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, TryLockError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{BoardLayout, TilePool},
+    diagnostics::RunSnapshot,
+    event_log::{write_gameplay_log, GameplayEvent, GameplayLog, LoggedEvent, RunDirectory},
+    game_state::RunSeed,
+    history::MovementRules,
+    replay::{ActionLog, ActionQueue, PlayerAction},
+    special_cards::SpecialCardPool,
+};
+
+pub const BUG_REPORT_FILE: &str = "report.json";
+
+/// Keeps the `CrashContext` up to date, for the panic hook installed with `CrashContext::install_panic_hook`
+/// to write a `BugReport` from.
+pub struct BugReportPlugin;
+
+impl Plugin for BugReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CrashContext>().add_systems(
+            Last,
+            update_crash_context
+                .after(write_gameplay_log)
+                .run_if(resource_changed::<ActionLog>().or_else(on_event::<GameplayEvent>())),
+        );
+    }
+}
+
+/// Everything needed to look into a crash and to play the run up to it again.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BugReport {
+    pub panic: Option<String>,
+    pub seed: u64,
+    pub layout: BoardLayout,
+    pub rules: MovementRules,
+    /// The pools the board and special cards were drawn from, which may have come from files.
+    #[serde(default)]
+    pub tiles: TilePool,
+    #[serde(default)]
+    pub special_cards: SpecialCardPool,
+    pub actions: Vec<PlayerAction>,
+    pub recent_events: Vec<LoggedEvent>,
+    pub snapshot: RunSnapshot,
+}
+
+impl BugReport {
+    pub fn capture(world: &mut World) -> Self {
+        BugReport {
+            panic: None,
+            seed: world.get_resource::<RunSeed>().map_or(0, |seed| seed.0),
+            layout: world.get_resource::<BoardLayout>().cloned().unwrap_or_default(),
+            rules: world.get_resource::<MovementRules>().cloned().unwrap_or_default(),
+            tiles: world.get_resource::<TilePool>().cloned().unwrap_or_default(),
+            special_cards: world.get_resource::<SpecialCardPool>().cloned().unwrap_or_default(),
+            actions: world
+                .get_resource::<ActionLog>()
+                .map_or(vec![], |log| log.0.clone()),
+            recent_events: world
+                .get_resource::<GameplayLog>()
+                .map_or(vec![], |log| log.recent.iter().cloned().collect()),
+            snapshot: RunSnapshot::capture(world),
+        }
+    }
+
+    /// Writes the report to a new directory under `base`, named after the seed and time, and returns the directory.
+    pub fn write(&self, base: &Path) -> io::Result<PathBuf> {
+        let written = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let dir = base.join(format!("bug-report-{}-{}", self.seed, written));
+
+        fs::create_dir_all(&dir)?;
+
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(dir.join(BUG_REPORT_FILE), json)?;
+
+        Ok(dir)
+    }
+
+    /// Reads a report from its file or from the directory holding it.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = if path.is_dir() {
+            path.join(BUG_REPORT_FILE)
+        } else {
+            path.to_path_buf()
+        };
+
+        let json = fs::read_to_string(file)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Sets `app` up to play the reported run again. Call before adding the game's plugins.
+    pub fn install_replay(&self, app: &mut App) {
+        let mut queue = ActionQueue::default();
+        queue.actions.extend(self.actions.iter().cloned());
+
+        app.insert_resource(RunSeed(self.seed))
+            .insert_resource(self.layout.clone())
+            .insert_resource(self.rules.clone())
+            .insert_resource(self.tiles.clone())
+            .insert_resource(self.special_cards.clone())
+            .insert_resource(queue);
+    }
+}
+
+#[derive(Default)]
+struct CrashState {
+    report: BugReport,
+    directory: Option<PathBuf>,
+}
+
+/// The latest report, shared with the panic hook, which cannot reach the world.
+#[derive(Resource, Default)]
+pub struct CrashContext(Arc<Mutex<CrashState>>);
+
+impl CrashContext {
+    /// Writes a report from this context whenever the process panics, then goes on to the hook set before.
+    /// The hook is process-wide, so call this once, from `main`, and insert the same context into the app.
+    pub fn install_panic_hook(&self) {
+        let shared = self.0.clone();
+        let previous = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if let Some(dir) = write_crash_report(&shared, info.to_string()) {
+                eprintln!("Bug report written to {}", dir.display());
+            }
+
+            previous(info);
+        }));
+    }
+}
+
+/// Copies what changed since the last update into the `CrashContext`.
+/// The pools, layout and rules are set once a run, and the action log only grows,
+/// so none of those are copied whole every time.
+pub fn update_crash_context(world: &mut World) {
+    let snapshot = RunSnapshot::capture(world);
+    let directory = world
+        .get_resource::<RunDirectory>()
+        .and_then(|dir| dir.0.clone());
+
+    let shared = world.resource::<CrashContext>().0.clone();
+    let Ok(mut state) = shared.lock() else {
+        return;
+    };
+
+    state.directory = directory;
+
+    let report = &mut state.report;
+    report.seed = world.get_resource::<RunSeed>().map_or(0, |seed| seed.0);
+    report.snapshot = snapshot;
+    report.recent_events = world
+        .get_resource::<GameplayLog>()
+        .map_or(vec![], |log| log.recent.iter().cloned().collect());
+
+    if let Some(layout) = changed::<BoardLayout>(world) {
+        report.layout = layout;
+    }
+
+    if let Some(rules) = changed::<MovementRules>(world) {
+        report.rules = rules;
+    }
+
+    if let Some(tiles) = changed::<TilePool>(world) {
+        report.tiles = tiles;
+    }
+
+    if let Some(special_cards) = changed::<SpecialCardPool>(world) {
+        report.special_cards = special_cards;
+    }
+
+    if let Some(log) = world.get_resource::<ActionLog>() {
+        let known = report.actions.len().min(log.0.len());
+        report.actions.truncate(known);
+        report.actions.extend_from_slice(&log.0[known..]);
+    }
+}
+
+/// A copy of `R` if it changed since the system asking last ran.
+fn changed<R: Resource + Clone>(world: &World) -> Option<R> {
+    world
+        .is_resource_changed::<R>()
+        .then(|| world.resource::<R>().clone())
+}
+
+fn write_crash_report(shared: &Mutex<CrashState>, message: String) -> Option<PathBuf> {
+    // The panic may have happened while the context was being updated on this thread.
+    let state = match shared.try_lock() {
+        Ok(state) => state,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => return None,
+    };
+
+    let mut report = state.report.clone();
+    report.panic = Some(message);
+
+    let base = state
+        .directory
+        .clone()
+        .unwrap_or_else(|| std::env::temp_dir().join("treacherous-safari"));
+
+    match report.write(&base) {
+        Ok(dir) => Some(dir),
+        Err(err) => {
+            eprintln!("Could not write bug report: {}", err);
+            None
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use bevy::{
+    ecs::system::SystemParam,
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+    render::view::VisibilitySystems,
+    transform::TransformSystem,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    bindings::{bindings_screen_closed, ActionMap, GameAction},
+    history::TrailSegment,
+    layout::{Layout, LayoutRoot, Zone},
+    modal::Modal,
+    settings::settings_screen_closed,
+    tiles::{Goal, Player, Tile},
+};
+
+/// How far past its zone a sprite may reach and still be shown, in world units.
+const CLIP_TOLERANCE: f32 = 0.5;
+/// How far in the view may go, as a multiple of the zoom that shows the whole board.
+const MAX_ZOOM: f32 = 4.0;
+/// Zoom change per mouse-wheel line.
+const WHEEL_STEP: f32 = 1.1;
+/// Zoom change per second while a zoom key is held.
+const KEY_ZOOM_RATE: f32 = 2.0;
+/// How quickly the view catches up with the player's piece; larger is snappier.
+const FOLLOW_RATE: f32 = 4.0;
+/// Mouse-wheel pixels taken as one line.
+const PIXELS_PER_LINE: f32 = 40.0;
+
+/// Pans and zooms the board inside its zone with a `BoardCamera`: fits it at the start and after a resize,
+/// zooms with the mouse wheel and keys, pans by dragging with the right or middle button,
+/// follows the player's piece when it moves, and keeps the view on the board.
+/// The wheel and dragging only count while the cursor is over the board.
+///
+/// Only the board moves, so the cards and the HUD stay where the layout puts them. The board is moved
+/// rather than seen through a camera of its own, as sprite picking only looks through one camera per window,
+/// so whatever a zoomed-in board pushes out of its zone is hidden instead of cut off.
+pub struct CameraControlPlugin;
+
+impl Plugin for CameraControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoardCamera>()
+            .add_event::<MouseWheel>()
+            .add_event::<MouseMotion>()
+            .add_systems(
+                Update,
+                (
+                    camera_input
+                        .run_if(bindings_screen_closed)
+                        .run_if(settings_screen_closed),
+                    follow_player,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                (fit_view.run_if(resource_changed::<Layout>()), move_board, clip_board)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate)
+                    .before(VisibilitySystems::VisibilityPropagate),
+            );
+    }
+}
+
+/// The part of the board shown in its zone.
+#[derive(Resource, Debug, Clone)]
+pub struct BoardCamera {
+    /// The point of the board shown at the centre of its zone, in board units.
+    pub center: Vec2,
+    /// 1 shows the whole board; larger zooms in.
+    pub zoom: f32,
+    /// World units per board unit, as the board was last placed.
+    pub scale: f32,
+    /// Whether the view is heading for the player's piece, after it has moved.
+    pub following: bool,
+    /// Whether the board is to be fitted again once it is in place.
+    fit_pending: bool,
+}
+
+impl Default for BoardCamera {
+    fn default() -> Self {
+        BoardCamera {
+            center: Vec2::ZERO,
+            zoom: 1.0,
+            scale: 1.0,
+            following: false,
+            fit_pending: true,
+        }
+    }
+}
+
+impl BoardCamera {
+    pub fn fit(&mut self) {
+        self.fit_pending = true;
+    }
+
+    /// Multiplies the zoom by `factor`, within the zoom limits; above 1 zooms in.
+    pub fn zoom_by(&mut self, factor: f32) {
+        self.zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+    }
+
+    /// Moves the view by `delta` world units and stops following the player until it moves again.
+    pub fn pan(&mut self, delta: Vec2) {
+        self.center += delta / self.scale;
+        self.following = false;
+    }
+
+    /// Keeps the view inside `board`, or centred on it along a side the zone shows all of.
+    fn clamp(&mut self, board: Rect, shown: Vec2) {
+        let half = shown / 2.0;
+        let clamp_axis = |value: f32, min: f32, max: f32| {
+            if min > max {
+                (min + max) / 2.0
+            } else {
+                value.clamp(min, max)
+            }
+        };
+
+        self.center.x = clamp_axis(self.center.x, board.min.x + half.x, board.max.x - half.x);
+        self.center.y = clamp_axis(self.center.y, board.min.y + half.y, board.max.y - half.y);
+    }
+}
+
+/// The mouse as the view sees it: the wheel and dragging only count while the cursor is over the board.
+#[derive(SystemParam)]
+pub struct BoardPointer<'w, 's> {
+    buttons: Res<'w, Input<MouseButton>>,
+    wheel: EventReader<'w, 's, MouseWheel>,
+    motion: EventReader<'w, 's, MouseMotion>,
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    layout: Res<'w, Layout>,
+}
+
+impl BoardPointer<'_, '_> {
+    /// The wheel lines scrolled and how far the board was dragged since the last frame.
+    fn read(&mut self) -> (f32, Vec2) {
+        let lines: f32 = self
+            .wheel
+            .read()
+            .map(|event| match event.unit {
+                MouseScrollUnit::Line => event.y,
+                MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+            })
+            .sum();
+
+        let dragging = self.buttons.any_pressed([MouseButton::Right, MouseButton::Middle]);
+        let dragged: Vec2 = self.motion.read().map(|event| event.delta).sum();
+
+        let over_board = self
+            .windows
+            .get_single()
+            .ok()
+            .and_then(|window| window.cursor_position())
+            .is_some_and(|cursor| self.layout.board.contains(self.layout.window_to_world(cursor)));
+
+        match (over_board, dragging) {
+            (false, _) => (0.0, Vec2::ZERO),
+            (true, false) => (lines, Vec2::ZERO),
+            (true, true) => (lines, dragged),
+        }
+    }
+}
+
+pub fn camera_input(
+    keys: Res<Input<KeyCode>>,
+    map: Res<ActionMap>,
+    mut pointer: BoardPointer,
+    time: Res<Time>,
+    mut camera: ResMut<BoardCamera>,
+) {
+    let (lines, dragged) = pointer.read();
+
+    let held = match (map.pressed(GameAction::ZoomIn, &keys), map.pressed(GameAction::ZoomOut, &keys)) {
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        _ => 0.0,
+    };
+
+    if map.just_pressed(GameAction::FitView, &keys) {
+        camera.fit();
+        return;
+    }
+
+    if lines != 0.0 {
+        camera.zoom_by(WHEEL_STEP.powf(lines));
+    }
+
+    if held != 0.0 {
+        camera.zoom_by(KEY_ZOOM_RATE.powf(held * time.delta_seconds()));
+    }
+
+    if dragged != Vec2::ZERO {
+        // The board moves with the pointer, and screen y points down.
+        camera.pan(Vec2::new(-dragged.x, dragged.y));
+    }
+}
+
+/// Starts following when the piece moves on the board; the board itself moving does not count.
+pub fn follow_player(players: Query<Ref<Transform>, With<Player>>, mut camera: ResMut<BoardCamera>) {
+    let moved = players
+        .iter()
+        .any(|transform| transform.is_changed() && !transform.is_added());
+
+    if moved {
+        camera.following = true;
+    }
+}
+
+/// A new window size changes where everything is, so the view starts over from the whole board.
+pub fn fit_view(mut camera: ResMut<BoardCamera>) {
+    camera.fit();
+}
+
+/// Places the board's roots so the view's centre sits in the middle of the board's zone, at the view's zoom.
+pub fn move_board(
+    layout: Res<Layout>,
+    time: Res<Time>,
+    mut camera: ResMut<BoardCamera>,
+    players: Query<&Transform, (With<Player>, Without<LayoutRoot>)>,
+    mut roots: Query<(&LayoutRoot, &mut Transform)>,
+) {
+    let Some(board) = roots
+        .iter()
+        .find(|(root, _)| root.zone == Zone::Board)
+        .map(|(root, _)| root.design)
+    else {
+        return;
+    };
+
+    if camera.fit_pending {
+        camera.zoom = 1.0;
+        camera.center = board.center();
+        camera.following = false;
+        camera.fit_pending = false;
+    }
+
+    let (fit, _) = layout.fit(Zone::Board, board);
+    let scale = fit * camera.zoom;
+    let shown = layout.board.size() / scale;
+    camera.scale = scale;
+
+    let player = players.get_single().ok().map(|player| player.translation.truncate());
+
+    if let Some(target) = player.filter(|_| camera.following) {
+        let before = camera.center;
+        let t = 1.0 - (-FOLLOW_RATE * time.delta_seconds()).exp();
+        camera.center = camera.center.lerp(target, t);
+        camera.clamp(board, shown);
+
+        // Done once there, or once the edge of the board stops the view.
+        if camera.center.distance(target) < 0.5 || camera.center.distance(before) < 0.01 {
+            camera.following = false;
+        }
+    }
+
+    camera.clamp(board, shown);
+
+    let offset = layout.board.center() - camera.center * scale;
+
+    for (root, mut transform) in &mut roots {
+        if root.zone != Zone::Board {
+            continue;
+        }
+
+        let placed = Transform {
+            translation: offset.extend(transform.translation.z),
+            scale: Vec3::new(scale, scale, transform.scale.z),
+            ..*transform
+        };
+        transform.set_if_neq(placed);
+    }
+}
+
+type ClippedFilter = Or<(With<Tile>, With<TrailSegment>, With<Goal>, With<Player>)>;
+
+/// Hides what the view has pushed partly out of the board's zone, so it does not cover the cards beside it.
+/// A sprite is measured at its own size, so a tile drawn larger while it is focused or shown in a modal stays.
+pub fn clip_board(
+    layout: Res<Layout>,
+    modal: Option<Res<Modal>>,
+    roots: Query<(&LayoutRoot, &Transform)>,
+    mut sprites: Query<(Entity, &Sprite, &Transform, &Parent, &mut Visibility), ClippedFilter>,
+) {
+    let zone = layout.board.inset(CLIP_TOLERANCE);
+    let shown = modal.and_then(|modal| modal.content().and_then(|content| content.subject()));
+
+    for (entity, sprite, transform, parent, mut visibility) in &mut sprites {
+        let (Some(size), Ok((root, placed))) = (sprite.custom_size, roots.get(parent.get())) else {
+            continue;
+        };
+
+        if root.zone != Zone::Board {
+            continue;
+        }
+
+        let half = size / 2.0;
+        let corners = [-half, Vec2::new(half.x, -half.y), half, Vec2::new(-half.x, half.y)];
+        let inside = corners.into_iter().all(|corner| {
+            // From the sprite's own units to the board's, then to the world's.
+            let on_board = transform.translation.truncate() + (transform.rotation * corner.extend(0.0)).truncate();
+            zone.contains(placed.translation.truncate() + on_board * placed.scale.truncate())
+        });
+
+        let clipped = if inside || shown == Some(entity) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(clipped);
+    }
+}
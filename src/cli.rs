@@ -0,0 +1,242 @@
+//! Command-line options of the game binary, so a scenario can be launched straight from a script or a bug report.
+
+use std::{fmt, path::PathBuf};
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{BoardGenerator, BoardLayout, TilePool},
+    bug_report::BugReport,
+    game_state::RunSeed,
+    settings::{Choice, Difficulty, DisplayMode, Settings, WINDOW_HEIGHT, WINDOW_WIDTH},
+    special_cards::SpecialCardPool,
+    tween::FastForward,
+};
+
+pub const USAGE: &str = "\
+Usage: treacherous_safari [options]
+
+  --seed <n>            play the run on this seed
+  --preset <name>       rules for this run: easy, normal or hard (default from the settings)
+  --load <save>         continue a saved run: a bug report or its directory, caught up to where it stopped
+                        without animations, then played on
+  --replay <file>       watch a recorded run played back; with --headless, quit once it has been played
+  --tiles <file>        JSON list of the tiles boards are drawn from
+  --cards <file>        JSON list of the special cards runs are dealt from
+  --window-size <WxH>   window size for this session, e.g. 1600x900
+  --fullscreen          fullscreen for this session
+  --headless            no window or renderer
+  --bot                 take commands on stdin and answer with the state on stdout
+  --help                show this and quit";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliOptions {
+    pub seed: Option<u64>,
+    pub preset: Option<Difficulty>,
+    pub load: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub tiles: Option<PathBuf>,
+    pub cards: Option<PathBuf>,
+    pub window_size: Option<(u32, u32)>,
+    pub fullscreen: bool,
+    pub headless: bool,
+    pub bot: bool,
+    pub help: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    UnknownOption(String),
+    MissingValue(String),
+    BadValue {
+        option: String,
+        value: String,
+        expected: String,
+    },
+    /// Two options that cannot be used together.
+    Conflict(&'static str, &'static str),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::UnknownOption(option) => write!(f, "unknown option {}", option),
+            CliError::MissingValue(option) => write!(f, "{} needs a value", option),
+            CliError::BadValue {
+                option,
+                value,
+                expected,
+            } => write!(f, "bad value for {}: {:?}, expected {}", option, value, expected),
+            CliError::Conflict(a, b) => write!(f, "{} cannot be used with {}", a, b),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl CliOptions {
+    /// Reads the options, without the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = CliOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--help" | "-h" => options.help = true,
+                "--headless" => options.headless = true,
+                "--bot" => options.bot = true,
+                "--fullscreen" => options.fullscreen = true,
+                "--seed" | "--preset" | "--load" | "--replay" | "--tiles" | "--cards" | "--window-size" => {
+                    let value = args.next().ok_or_else(|| CliError::MissingValue(arg.clone()))?;
+                    options.read_value(&arg, value)?;
+                }
+                _ => return Err(CliError::UnknownOption(arg)),
+            }
+        }
+
+        options.check()?;
+
+        Ok(options)
+    }
+
+    fn read_value(&mut self, option: &str, value: String) -> Result<(), CliError> {
+        let bad = |expected: &str| CliError::BadValue {
+            option: option.to_string(),
+            value: value.clone(),
+            expected: expected.to_string(),
+        };
+
+        match option {
+            "--seed" => self.seed = Some(value.parse().map_err(|_| bad("a whole number"))?),
+            "--preset" => {
+                let names: Vec<&str> = Difficulty::ALL.iter().map(|preset| preset.name()).collect();
+                let preset = Difficulty::from_name(&value).ok_or_else(|| bad(&names.join(", ")))?;
+                self.preset = Some(preset);
+            }
+            "--window-size" => {
+                let size = parse_window_size(&value).ok_or_else(|| {
+                    bad(&format!(
+                        "WIDTHxHEIGHT from {}x{} to {}x{}",
+                        WINDOW_WIDTH.0, WINDOW_HEIGHT.0, WINDOW_WIDTH.1, WINDOW_HEIGHT.1
+                    ))
+                })?;
+                self.window_size = Some(size);
+            }
+            "--load" => self.load = Some(PathBuf::from(value)),
+            "--replay" => self.replay = Some(PathBuf::from(value)),
+            "--tiles" => self.tiles = Some(PathBuf::from(value)),
+            "--cards" => self.cards = Some(PathBuf::from(value)),
+            _ => return Err(CliError::UnknownOption(option.to_string())),
+        }
+
+        Ok(())
+    }
+
+    fn check(&self) -> Result<(), CliError> {
+        if self.load.is_some() && self.replay.is_some() {
+            return Err(CliError::Conflict("--load", "--replay"));
+        }
+
+        // A recorded run brings its own seed, rules and pools.
+        let recorded = if self.load.is_some() { "--load" } else { "--replay" };
+        if self.load.is_some() || self.replay.is_some() {
+            if self.seed.is_some() {
+                return Err(CliError::Conflict("--seed", recorded));
+            }
+            if self.preset.is_some() {
+                return Err(CliError::Conflict("--preset", recorded));
+            }
+            if self.tiles.is_some() {
+                return Err(CliError::Conflict("--tiles", recorded));
+            }
+            if self.cards.is_some() {
+                return Err(CliError::Conflict("--cards", recorded));
+            }
+        }
+
+        if self.headless && self.window_size.is_some() {
+            return Err(CliError::Conflict("--window-size", "--headless"));
+        }
+        if self.headless && self.fullscreen {
+            return Err(CliError::Conflict("--fullscreen", "--headless"));
+        }
+
+        Ok(())
+    }
+
+    /// The recorded run to play, if any.
+    pub fn recording(&self) -> Option<&PathBuf> {
+        self.load.as_ref().or(self.replay.as_ref())
+    }
+
+    /// Overrides the window settings for this session. They are only saved if the player changes a setting.
+    pub fn apply_window(&self, settings: &mut Settings) {
+        if let Some((width, height)) = self.window_size {
+            settings.window_width = width;
+            settings.window_height = height;
+        }
+
+        if self.fullscreen {
+            settings.display_mode = DisplayMode::Fullscreen;
+        }
+    }
+
+    /// Reads the files the options name and sets `app` up for the run they ask for.
+    /// Call before adding the game's plugins.
+    pub fn install(&self, app: &mut App) -> Result<(), String> {
+        if let Some(path) = self.recording() {
+            let report = BugReport::load(path)
+                .map_err(|err| format!("could not read the run in {}: {}", path.display(), err))?;
+            report.install_replay(app);
+
+            // A loaded run is only played back to get to where it was left.
+            if self.load.is_some() {
+                app.insert_resource(FastForward);
+            }
+        }
+
+        if let Some(seed) = self.seed {
+            app.insert_resource(RunSeed(seed));
+        }
+
+        if let Some(preset) = self.preset {
+            app.insert_resource(preset.rules());
+        }
+
+        if let Some(path) = &self.tiles {
+            let pool = TilePool::load(path)
+                .map_err(|err| format!("could not read tiles from {}: {}", path.display(), err))?;
+            let layout = app.world.get_resource::<BoardLayout>().cloned().unwrap_or_default();
+            // Picked now rather than by the game, so the board checked is the one that will be played.
+            let seed = app
+                .world
+                .get_resource_or_insert_with(|| RunSeed(rand::random()))
+                .0;
+
+            if let Err(err) = BoardGenerator::with_pool(layout, pool.clone()).generate_near(seed) {
+                return Err(format!("the tiles in {} cannot make a board: {}", path.display(), err));
+            }
+
+            app.insert_resource(pool);
+        }
+
+        if let Some(path) = &self.cards {
+            let pool = SpecialCardPool::load(path)
+                .map_err(|err| format!("could not read special cards from {}: {}", path.display(), err))?;
+            app.insert_resource(pool);
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_window_size(value: &str) -> Option<(u32, u32)> {
+    let (width, height) = value.split_once(['x', 'X'])?;
+    let width: u32 = width.trim().parse().ok()?;
+    let height: u32 = height.trim().parse().ok()?;
+
+    let fits = (WINDOW_WIDTH.0..=WINDOW_WIDTH.1).contains(&(width as i64))
+        && (WINDOW_HEIGHT.0..=WINDOW_HEIGHT.1).contains(&(height as i64));
+
+    fits.then_some((width, height))
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+/// Overrides where configuration files are kept, mostly for tests and portable installs.
+pub const CONFIG_DIR_ENV: &str = "SAFARI_CONFIG_DIR";
+const APP_DIR: &str = "treacherous_safari";
+
+/// Where the player's configuration files are read from and saved to. `None` keeps everything in memory.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ConfigDirectory(pub Option<PathBuf>);
+
+impl ConfigDirectory {
+    /// `SAFARI_CONFIG_DIR` when set, otherwise the platform's config directory.
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+
+        let dir = var(CONFIG_DIR_ENV).or_else(|| {
+            var("XDG_CONFIG_HOME")
+                .or_else(|| var("APPDATA"))
+                .or_else(|| var("HOME").map(|home| home.join(".config")))
+                .map(|base| base.join(APP_DIR))
+        });
+
+        ConfigDirectory(dir)
+    }
+
+    pub fn file(&self, name: &str) -> Option<PathBuf> {
+        self.0.as_ref().map(|dir| dir.join(name))
+    }
+}
@@ -0,0 +1,315 @@
+use bevy::{prelude::*, window::ReceivedCharacter};
+
+use crate::{
+    bindings::{ActionMap, GameAction},
+    diagnostics::RunSnapshot,
+    game_state::RunSeed,
+    ledger::Cause,
+    movement::{MovementPoints, MovementPointsUpdateEvent},
+    replay::{ActionQueue, PlayerAction},
+    special_cards::{generate_cards, SpecialCard, SpecialCardSelectable, SpecialCardSlot},
+    tiles::{Selectable, Tile, TileCover},
+    turns::{TurnsLeft, TurnsUpdateEvent},
+    tween::Flip,
+};
+
+const OUTPUT_LINES: usize = 8;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DevConsole>()
+            .add_event::<ReceivedCharacter>()
+            .add_systems(Startup, setup_console)
+            .add_systems(
+                Update,
+                (
+                    read_console_input,
+                    run_console_commands.after(read_console_input),
+                    update_console_text.after(run_console_commands),
+                ),
+            );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    /// Adds to the movement points, or sets them when `relative` is false.
+    Points { value: i32, relative: bool },
+    Turns { value: i32, relative: bool },
+    RevealAll,
+    GiveSpecial(String),
+    GotoTile(u32),
+    Seed,
+    DumpState,
+}
+
+impl ConsoleCommand {
+    pub fn parse(line: &str) -> Result<ConsoleCommand, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["points", value] => {
+                let (value, relative) = parse_amount(value)?;
+                Ok(ConsoleCommand::Points { value, relative })
+            }
+            ["turns", value] => {
+                let (value, relative) = parse_amount(value)?;
+                Ok(ConsoleCommand::Turns { value, relative })
+            }
+            ["reveal", "all"] => Ok(ConsoleCommand::RevealAll),
+            ["give", "special", name @ ..] if !name.is_empty() => {
+                Ok(ConsoleCommand::GiveSpecial(name.join(" ")))
+            }
+            ["goto", "tile", number] => number
+                .parse()
+                .map(ConsoleCommand::GotoTile)
+                .map_err(|_| format!("not a tile number: {}", number)),
+            ["seed"] => Ok(ConsoleCommand::Seed),
+            ["dump", "state"] => Ok(ConsoleCommand::DumpState),
+            _ => Err(format!("unknown command: {}", line.trim())),
+        }
+    }
+}
+
+fn parse_amount(word: &str) -> Result<(i32, bool), String> {
+    let relative = word.starts_with('+') || word.starts_with('-');
+    let value = word
+        .parse()
+        .map_err(|_| format!("not a number: {}", word))?;
+
+    Ok((value, relative))
+}
+
+#[derive(Resource, Default)]
+pub struct DevConsole {
+    pub open: bool,
+    pub input: String,
+    pub output: Vec<String>,
+    queued: Vec<ConsoleCommand>,
+}
+
+impl DevConsole {
+    /// Parses `line` and queues it to run on the next frame.
+    pub fn submit(&mut self, line: &str) {
+        self.print(format!("> {}", line));
+
+        match ConsoleCommand::parse(line) {
+            Ok(command) => self.queued.push(command),
+            Err(err) => self.print(err),
+        }
+    }
+
+    pub fn print(&mut self, line: String) {
+        info!("{}", line);
+        self.output.push(line);
+    }
+}
+
+/// Whether the console is closed, so the keys typed into it mean what they do in the game.
+pub fn console_closed(console: Option<Res<DevConsole>>) -> bool {
+    !console.is_some_and(|console| console.open)
+}
+
+#[derive(Component)]
+pub struct ConsoleText;
+
+pub fn setup_console(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    top: Val::Percent(60.0),
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(40.0),
+                    align_items: AlignItems::FlexEnd,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            ConsoleText,
+        ))
+        .with_children(|commands| {
+            commands.spawn((TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                ..default()
+            },));
+        });
+}
+
+pub fn read_console_input(
+    keys: Res<Input<KeyCode>>,
+    map: Res<ActionMap>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut console: ResMut<DevConsole>,
+) {
+    if map.just_pressed(GameAction::Console, &keys) {
+        console.open = !console.open;
+        characters.clear();
+        return;
+    }
+
+    if !console.open {
+        characters.clear();
+        return;
+    }
+
+    for ev in characters.read() {
+        if !ev.char.is_control() && ev.char != '`' {
+            console.input.push(ev.char);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+
+    if keys.just_pressed(KeyCode::Return) && !console.input.is_empty() {
+        let line = std::mem::take(&mut console.input);
+        console.submit(&line);
+    }
+}
+
+pub fn run_console_commands(world: &mut World) {
+    let queued = std::mem::take(&mut world.resource_mut::<DevConsole>().queued);
+
+    for command in queued {
+        if let Err(err) = run_command(world, command) {
+            world.resource_mut::<DevConsole>().print(err);
+        }
+    }
+}
+
+fn run_command(world: &mut World, command: ConsoleCommand) -> Result<(), String> {
+    match command {
+        ConsoleCommand::Points { value, relative } => {
+            let current = world.resource::<MovementPoints>().0;
+            let delta = if relative { value } else { value - current };
+            world.send_event(MovementPointsUpdateEvent(delta, Cause::Console));
+        }
+        ConsoleCommand::Turns { value, relative } => {
+            let current = world.resource::<TurnsLeft>().0;
+            let delta = if relative { value } else { value - current };
+            world.send_event(TurnsUpdateEvent(delta, Cause::Console));
+        }
+        ConsoleCommand::RevealAll => {
+            let covered: Vec<(Entity, Entity)> = world
+                .query_filtered::<(Entity, &Parent, &Visibility), With<TileCover>>()
+                .iter(world)
+                .filter(|(_, _, vis)| **vis != Visibility::Hidden)
+                .map(|(cover, tile, _)| (tile.get(), cover))
+                .collect();
+
+            for (tile, cover) in covered {
+                world.entity_mut(tile).insert(Flip::reveal(cover));
+            }
+        }
+        ConsoleCommand::GiveSpecial(name) => {
+            let card = generate_cards()
+                .into_iter()
+                .find(|card| {
+                    card.name.eq_ignore_ascii_case(&name)
+                        || format!("{:?}", card.card_type).eq_ignore_ascii_case(&name)
+                })
+                .ok_or_else(|| format!("no special card called {}", name))?;
+
+            // Swapped into a card the player may pick now, then picked as the player would,
+            // so the one-pick rule and the hand limit still hold.
+            let mut board_cards =
+                world.query_filtered::<(&mut SpecialCard, &SpecialCardSlot), With<SpecialCardSelectable>>();
+
+            let (mut slot_card, slot) = board_cards
+                .iter_mut(world)
+                .next()
+                .ok_or_else(|| String::from("no special card can be picked now"))?;
+            *slot_card = card;
+            let slot = slot.0;
+
+            let mut queue = world.resource_mut::<ActionQueue>();
+            queue.push(PlayerAction::SelectSpecial { slot });
+            queue.push(PlayerAction::CloseReveal);
+        }
+        ConsoleCommand::GotoTile(number) => {
+            if world.query::<&Selectable>().iter(world).next().is_none() {
+                return Err(String::from("tiles can only be gone to while choosing the next one"));
+            }
+
+            let target = world
+                .query::<(Entity, &Tile)>()
+                .iter(world)
+                .find(|(_, tile)| tile.number == number)
+                .map(|(entity, _)| entity)
+                .ok_or_else(|| format!("no tile {}", number))?;
+
+            // Offered as the only way on, then moved to as the player would.
+            let mut selectable = world.query_filtered::<Entity, With<Selectable>>();
+            for entity in selectable.iter(world).collect::<Vec<_>>() {
+                world.entity_mut(entity).remove::<Selectable>();
+            }
+            world.entity_mut(target).insert(Selectable);
+
+            let mut queue = world.resource_mut::<ActionQueue>();
+            queue.push(PlayerAction::SelectTile { tile: number });
+            queue.push(PlayerAction::Confirm);
+        }
+        ConsoleCommand::Seed => {
+            let seed = world.resource::<RunSeed>().0;
+            world
+                .resource_mut::<DevConsole>()
+                .print(format!("seed: {}", seed));
+        }
+        ConsoleCommand::DumpState => {
+            let snapshot = RunSnapshot::capture(world);
+            let mut console = world.resource_mut::<DevConsole>();
+
+            for line in snapshot.to_string().lines() {
+                console.print(line.to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn update_console_text(
+    console: Res<DevConsole>,
+    mut node: Query<(&mut Visibility, &Children), With<ConsoleText>>,
+    mut texts: Query<&mut Text>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    let Ok((mut vis, children)) = node.get_single_mut() else {
+        return;
+    };
+
+    *vis = if console.open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    let skip = console.output.len().saturating_sub(OUTPUT_LINES);
+    let mut lines: Vec<&str> = console.output.iter().skip(skip).map(String::as_str).collect();
+    let prompt = format!("> {}_", console.input);
+    lines.push(&prompt);
+
+    for child in children {
+        if let Ok(mut text) = texts.get_mut(*child) {
+            text.sections[0].value = lines.join("\n");
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+
+use bevy::{ecs::query::ReadOnlyWorldQuery, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    effects::EffectQueue,
+    game_state::RunSeed,
+    history::RunEndedEvent,
+    modal::{Modal, ModalBlocker, ModalCloseButton, ModalContent},
+    movement::{
+        MovementCard, MovementCardDiscarded, MovementCardDrawn, MovementCardsDrawnEvent,
+        MovementCardsPlayedEvent, MovementPoints, DECK_SIZE,
+    },
+    special_cards::{
+        SpecialCard, SpecialCardClosed, SpecialCardHand, SpecialCardInHand, SpecialCardPending,
+    },
+    tiles::{Player, Selectable, Tile, TileClosedEvent, VisitedTiles},
+    turns::TurnsLeft,
+};
+
+/// Checks the game's invariants after every phase change, in debug builds only.
+pub struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        app.init_resource::<InvariantViolations>().add_systems(
+            PostUpdate,
+            check_invariants.run_if(
+                on_event::<TileClosedEvent>()
+                    .or_else(on_event::<MovementCardsDrawnEvent>())
+                    .or_else(on_event::<SpecialCardClosed>())
+                    .or_else(on_event::<MovementCardsPlayedEvent>())
+                    .or_else(on_event::<RunEndedEvent>()),
+            ),
+        );
+    }
+}
+
+/// The state of a run at one moment, for reports and debugging.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    pub seed: Option<u64>,
+    pub turns_left: i32,
+    pub movement_points: i32,
+    pub visited_tiles: Vec<u32>,
+    pub current_tiles: Vec<u32>,
+    pub drawn_cards: Vec<u32>,
+    pub deck_remaining: usize,
+    pub discarded_cards: usize,
+    pub hand: Vec<String>,
+    pub pending: Vec<String>,
+    pub phase_open: bool,
+    pub pending_effects: Vec<String>,
+}
+
+impl RunSnapshot {
+    pub fn capture(world: &mut World) -> Self {
+        RunSnapshot {
+            seed: world.get_resource::<RunSeed>().map(|seed| seed.0),
+            turns_left: world.get_resource::<TurnsLeft>().map_or(0, |t| t.0),
+            movement_points: world.get_resource::<MovementPoints>().map_or(0, |m| m.0),
+            visited_tiles: world
+                .get_resource::<VisitedTiles>()
+                .map_or(vec![], |v| v.0.clone()),
+            current_tiles: world
+                .query::<&Tile>()
+                .iter(world)
+                .filter(|tile| tile.current)
+                .map(|tile| tile.number)
+                .collect(),
+            drawn_cards: world
+                .query_filtered::<&MovementCard, (With<MovementCardDrawn>, Without<MovementCardDiscarded>)>()
+                .iter(world)
+                .map(|card| card.value)
+                .collect(),
+            deck_remaining: count::<(
+                With<MovementCard>,
+                Without<MovementCardDrawn>,
+                Without<MovementCardDiscarded>,
+            )>(world),
+            discarded_cards: count::<With<MovementCardDiscarded>>(world),
+            hand: card_names::<With<SpecialCardInHand>>(world),
+            pending: card_names::<With<SpecialCardPending>>(world),
+            phase_open: world
+                .get_resource::<SpecialCardHand>()
+                .is_some_and(|hand| hand.phase_open),
+            pending_effects: world
+                .get_resource::<EffectQueue>()
+                .map_or(vec![], |queue| {
+                    queue.pending().map(|effect| format!("{:?}", effect)).collect()
+                }),
+        }
+    }
+}
+
+impl fmt::Display for RunSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "seed: {:?}", self.seed)?;
+        writeln!(f, "turns left: {}", self.turns_left)?;
+        writeln!(f, "movement points: {}", self.movement_points)?;
+        writeln!(f, "visited tiles: {:?}", self.visited_tiles)?;
+        writeln!(f, "current tiles: {:?}", self.current_tiles)?;
+        writeln!(f, "drawn cards: {:?}", self.drawn_cards)?;
+        writeln!(
+            f,
+            "deck remaining: {}, discarded: {}",
+            self.deck_remaining, self.discarded_cards
+        )?;
+        writeln!(f, "hand: {:?}, pending: {:?}", self.hand, self.pending)?;
+        writeln!(f, "special card phase open: {}", self.phase_open)?;
+        write!(f, "pending effects: {:?}", self.pending_effects)
+    }
+}
+
+/// Every invariant broken so far, oldest first.
+#[derive(Resource, Default, Debug)]
+pub struct InvariantViolations(pub Vec<String>);
+
+pub fn check_invariants(world: &mut World) {
+    let mut violations = Vec::new();
+
+    let current_tiles = world.query::<&Tile>().iter(world).filter(|t| t.current).count();
+
+    // The player is on no tile only from the end of a turn until the next tile is revealed,
+    // or once the run is over.
+    let between_tiles = count::<With<Selectable>>(world) > 0
+        || world
+            .get_resource::<Modal>()
+            .is_some_and(|modal| matches!(modal.content(), Some(ModalContent::TileDetails(_))))
+        || world
+            .get_resource::<Events<RunEndedEvent>>()
+            .is_some_and(|events| !events.is_empty());
+
+    if current_tiles > 1 || (current_tiles == 0 && !between_tiles) {
+        violations.push(format!("expected one current tile, found {}", current_tiles));
+    }
+
+    expect_single::<With<Player>>(world, "Player", &mut violations);
+    expect_single::<With<ModalBlocker>>(world, "ModalBlocker", &mut violations);
+    expect_single::<With<ModalCloseButton>>(world, "ModalCloseButton", &mut violations);
+
+    let drawn = count::<(With<MovementCard>, With<MovementCardDrawn>)>(world);
+    let discarded = count::<(With<MovementCard>, With<MovementCardDiscarded>)>(world);
+    let deck = count::<(
+        With<MovementCard>,
+        Without<MovementCardDrawn>,
+        Without<MovementCardDiscarded>,
+    )>(world);
+
+    if drawn + discarded + deck != DECK_SIZE {
+        violations.push(format!(
+            "{} drawn, {} discarded and {} in the deck from a deck of {}",
+            drawn, discarded, deck, DECK_SIZE
+        ));
+    }
+
+    let both = count::<(With<MovementCardDrawn>, With<MovementCardDiscarded>)>(world);
+
+    if both > 0 {
+        violations.push(format!("{} movement cards both drawn and discarded", both));
+    }
+
+    if violations.is_empty() {
+        return;
+    }
+
+    let snapshot = RunSnapshot::capture(world);
+
+    for violation in &violations {
+        error!("Invariant violated: {}\n{}", violation, snapshot);
+    }
+
+    if let Some(mut reported) = world.get_resource_mut::<InvariantViolations>() {
+        reported.0.extend(violations);
+    }
+}
+
+fn count<F: ReadOnlyWorldQuery>(world: &mut World) -> usize {
+    world.query_filtered::<(), F>().iter(world).count()
+}
+
+fn card_names<F: ReadOnlyWorldQuery>(world: &mut World) -> Vec<String> {
+    world
+        .query_filtered::<&SpecialCard, F>()
+        .iter(world)
+        .map(|card| card.name.clone())
+        .collect()
+}
+
+fn expect_single<F: ReadOnlyWorldQuery>(world: &mut World, name: &str, violations: &mut Vec<String>) {
+    let found = count::<F>(world);
+
+    if found != 1 {
+        violations.push(format!("expected one {}, found {}", name, found));
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    ledger::{Cause, Tally},
+    movement::MovementPointsUpdateEvent,
+    turns::TurnsUpdateEvent,
+};
+
+/// Points in a turn where pending effects resolve, listed in the order they happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingWindow {
+    /// When the reveal of a tile is closed.
+    OnReveal,
+    /// When the movement cards for a turn are drawn.
+    StartOfTurn,
+    /// When the first hand on a newly entered tile is played, after its special card.
+    OnEnter,
+    /// Right before the cost of the current tile is checked.
+    BeforeCostCheck,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EffectSource {
+    Tile(u32),
+    SpecialCard(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EffectKind {
+    MovementPoints(i32),
+    Turns(i32),
+    /// Cancels every pending effect that keeps taking movement points or turns.
+    ClearAfflictions,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingEffect {
+    pub window: TimingWindow,
+    pub source: EffectSource,
+    pub kind: EffectKind,
+    /// How many more times the effect resolves, `None` until it is cancelled.
+    pub remaining: Option<u32>,
+}
+
+impl PendingEffect {
+    pub fn once(window: TimingWindow, source: EffectSource, kind: EffectKind) -> Self {
+        PendingEffect {
+            window,
+            source,
+            kind,
+            remaining: Some(1),
+        }
+    }
+
+    /// Harmful effects that linger from turn to turn, such as curses and poison.
+    pub fn is_affliction(&self) -> bool {
+        let harmful = match self.kind {
+            EffectKind::MovementPoints(value) | EffectKind::Turns(value) => value < 0,
+            EffectKind::ClearAfflictions => false,
+        };
+
+        harmful && self.window == TimingWindow::StartOfTurn
+    }
+
+    pub fn cause(&self) -> Cause {
+        match &self.source {
+            EffectSource::Tile(number) if self.window == TimingWindow::StartOfTurn => {
+                Cause::Affliction(*number)
+            }
+            EffectSource::Tile(number) => Cause::Tile(*number),
+            EffectSource::SpecialCard(name) => Cause::SpecialCard(name.clone()),
+        }
+    }
+}
+
+/// Effects waiting for their timing window, resolved in the order they were registered.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct EffectQueue {
+    pending: Vec<PendingEffect>,
+}
+
+impl EffectQueue {
+    pub fn push(&mut self, effect: PendingEffect) {
+        self.pending.push(effect);
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &PendingEffect> {
+        self.pending.iter()
+    }
+
+    /// Removes pending effects matching `predicate`, returning how many were cancelled.
+    pub fn cancel_where(&mut self, predicate: impl Fn(&PendingEffect) -> bool) -> usize {
+        let before = self.pending.len();
+        self.pending.retain(|effect| !predicate(effect));
+        before - self.pending.len()
+    }
+
+    pub fn modify_where(
+        &mut self,
+        predicate: impl Fn(&PendingEffect) -> bool,
+        mut modify: impl FnMut(&mut PendingEffect),
+    ) {
+        for effect in self.pending.iter_mut().filter(|effect| predicate(effect)) {
+            modify(effect);
+        }
+    }
+
+    /// Resolves every effect registered for `window`, keeping the ones that repeat.
+    pub fn resolve(
+        &mut self,
+        window: TimingWindow,
+        movement_points_update: &mut EventWriter<MovementPointsUpdateEvent>,
+        turns_update: &mut EventWriter<TurnsUpdateEvent>,
+    ) {
+        self.resolve_with(window, |tally, amount, cause| match tally {
+            Tally::MovementPoints => {
+                movement_points_update.send(MovementPointsUpdateEvent(amount, cause))
+            }
+            Tally::Turns => turns_update.send(TurnsUpdateEvent(amount, cause)),
+        });
+    }
+
+    /// Like `resolve`, handing each change to `apply` instead of sending it as an event.
+    pub fn resolve_with(&mut self, window: TimingWindow, mut apply: impl FnMut(Tally, i32, Cause)) {
+        let (batch, rest): (Vec<PendingEffect>, Vec<PendingEffect>) = self
+            .pending
+            .drain(..)
+            .partition(|effect| effect.window == window);
+        self.pending = rest;
+
+        let mut batch = VecDeque::from(batch);
+        let mut repeating = Vec::new();
+
+        while let Some(mut effect) = batch.pop_front() {
+            match effect.kind {
+                EffectKind::MovementPoints(value) => {
+                    apply(Tally::MovementPoints, value, effect.cause());
+                }
+                EffectKind::Turns(value) => {
+                    apply(Tally::Turns, value, effect.cause());
+                }
+                EffectKind::ClearAfflictions => {
+                    self.cancel_where(PendingEffect::is_affliction);
+                    batch.retain(|e| !e.is_affliction());
+                    repeating.retain(|e: &PendingEffect| !e.is_affliction());
+                }
+            }
+
+            match effect.remaining {
+                None => repeating.push(effect),
+                Some(remaining) if remaining > 1 => {
+                    effect.remaining = Some(remaining - 1);
+                    repeating.push(effect);
+                }
+                Some(_) => {}
+            }
+        }
+
+        self.pending.extend(repeating);
+    }
+}
@@ -0,0 +1,257 @@
+//! A reinforcement-learning environment over `rules::Run`, in the style of Gym.
+
+use crate::{
+    board::{BoardLayout, TilePool},
+    history::MovementRules,
+    movement::DECK_SIZE,
+    rules::{Action, CardPlace, Phase, Run, RulesError},
+    special_cards::{CardType, SpecialCardPool},
+    tiles::TileType,
+};
+
+/// Drawn movement cards the observation has room for: the whole deck, as special cards can draw past the usual hand.
+pub const MAX_DRAWN_CARDS: usize = DECK_SIZE;
+/// Special cards dealt on the board.
+pub const SPECIAL_CARD_SLOTS: usize = 8;
+
+const TILE_TYPES: usize = 4;
+const CARD_TYPES: usize = 10;
+const CARD_PLACES: usize = 4;
+
+const TILE_FEATURES: usize = 5 + TILE_TYPES;
+const DRAWN_FEATURES: usize = 2;
+const SPECIAL_FEATURES: usize = CARD_PLACES + CARD_TYPES + 2;
+const RUN_FEATURES: usize = 8;
+
+/// Rough upper bounds, so every feature stays around 0..1.
+const COST_SCALE: f32 = 10.0;
+const POINTS_SCALE: f32 = 30.0;
+const TURNS_SCALE: f32 = 10.0;
+const DECK_SCALE: f32 = DECK_SIZE as f32;
+
+const WIN_REWARD: f32 = 1.0;
+const LOSS_REWARD: f32 = -1.0;
+
+/// What `step` returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub observation: Vec<f32>,
+    pub reward: f32,
+    pub done: bool,
+}
+
+pub struct SafariEnv {
+    pub layout: BoardLayout,
+    pub rules: MovementRules,
+    pub tiles: TilePool,
+    pub special_cards: SpecialCardPool,
+    run: Run,
+}
+
+impl SafariEnv {
+    pub fn new(layout: BoardLayout, rules: MovementRules) -> Result<Self, RulesError> {
+        SafariEnv::with_pools(layout, rules, TilePool::default(), SpecialCardPool::default())
+    }
+
+    /// Like `new`, with boards and special cards drawn from these pools.
+    pub fn with_pools(
+        layout: BoardLayout,
+        rules: MovementRules,
+        tiles: TilePool,
+        special_cards: SpecialCardPool,
+    ) -> Result<Self, RulesError> {
+        let run = Run::with_pools(0, layout.clone(), rules.clone(), &tiles, &special_cards)?;
+
+        Ok(SafariEnv {
+            layout,
+            rules,
+            tiles,
+            special_cards,
+            run,
+        })
+    }
+
+    /// Starts a new run on `seed` and returns its first observation.
+    pub fn reset(&mut self, seed: u64) -> Result<Vec<f32>, RulesError> {
+        self.run = Run::with_pools(
+            seed,
+            self.layout.clone(),
+            self.rules.clone(),
+            &self.tiles,
+            &self.special_cards,
+        )?;
+        Ok(self.observation())
+    }
+
+    pub fn run(&self) -> &Run {
+        &self.run
+    }
+
+    pub fn legal_actions(&self) -> Vec<Action> {
+        self.run.legal_actions()
+    }
+
+    pub fn step(&mut self, action: Action) -> Result<Step, RulesError> {
+        self.run.apply(action)?;
+
+        let reward = match self.run.phase {
+            Phase::Won => WIN_REWARD,
+            Phase::Lost => LOSS_REWARD,
+            _ => 0.0,
+        };
+
+        Ok(Step {
+            observation: self.observation(),
+            reward,
+            done: self.run.is_over(),
+        })
+    }
+
+    /// Size of every observation, fixed by the layout.
+    pub fn observation_len(&self) -> usize {
+        self.layout.tile_count() as usize * TILE_FEATURES
+            + MAX_DRAWN_CARDS * DRAWN_FEATURES
+            + SPECIAL_CARD_SLOTS * SPECIAL_FEATURES
+            + RUN_FEATURES
+    }
+
+    /// Number of action indices, fixed by the layout.
+    pub fn action_count(&self) -> usize {
+        self.layout.tile_count() as usize + SPECIAL_CARD_SLOTS * 3 + 1
+    }
+
+    /// The action's place in `0..action_count()`: moves, then picks, plays and discards by slot, then confirm.
+    pub fn action_index(&self, action: Action) -> usize {
+        let tiles = self.layout.tile_count() as usize;
+
+        match action {
+            Action::MoveTo(tile) => tile as usize,
+            Action::PickSpecial(slot) => tiles + slot,
+            Action::PlaySpecial(slot) => tiles + SPECIAL_CARD_SLOTS + slot,
+            Action::DiscardSpecial(slot) => tiles + SPECIAL_CARD_SLOTS * 2 + slot,
+            Action::ConfirmHand => tiles + SPECIAL_CARD_SLOTS * 3,
+        }
+    }
+
+    pub fn action_from_index(&self, index: usize) -> Option<Action> {
+        let tiles = self.layout.tile_count() as usize;
+
+        match index.checked_sub(tiles) {
+            None => Some(Action::MoveTo(index as u32)),
+            Some(i) if i < SPECIAL_CARD_SLOTS => Some(Action::PickSpecial(i)),
+            Some(i) if i < SPECIAL_CARD_SLOTS * 2 => Some(Action::PlaySpecial(i - SPECIAL_CARD_SLOTS)),
+            Some(i) if i < SPECIAL_CARD_SLOTS * 3 => {
+                Some(Action::DiscardSpecial(i - SPECIAL_CARD_SLOTS * 2))
+            }
+            Some(i) if i == SPECIAL_CARD_SLOTS * 3 => Some(Action::ConfirmHand),
+            Some(_) => None,
+        }
+    }
+
+    /// Legal actions as a mask over action indices.
+    pub fn action_mask(&self) -> Vec<bool> {
+        let mut mask = vec![false; self.action_count()];
+
+        for action in self.legal_actions() {
+            mask[self.action_index(action)] = true;
+        }
+
+        mask
+    }
+
+    /// What the player can see, as `observation_len()` numbers: tiles, drawn cards, special cards, then the run.
+    /// Costs and types of tiles not yet revealed, and faces of special cards still on the board, read as 0.
+    pub fn observation(&self) -> Vec<f32> {
+        let run = &self.run;
+        let mut obs = Vec::with_capacity(self.observation_len());
+
+        for (number, tile) in run.tiles.iter().enumerate() {
+            let revealed = run.revealed[number];
+            let number = number as u32;
+
+            obs.push(flag(revealed));
+            obs.push(if revealed { tile.cost as f32 / COST_SCALE } else { 0.0 });
+            obs.push(flag(run.current == number));
+            obs.push(flag(run.visited.contains(&number)));
+            obs.push(flag(run.phase == Phase::Moving && run.next_tiles.contains(&number)));
+            one_hot(&mut obs, revealed.then(|| tile_type_index(&tile.tile_type)), TILE_TYPES);
+        }
+
+        for i in 0..MAX_DRAWN_CARDS {
+            match run.drawn.get(i) {
+                Some(card) => obs.extend([1.0, card.value as f32 / COST_SCALE]),
+                None => obs.extend([0.0; DRAWN_FEATURES]),
+            }
+        }
+
+        for slot in 0..SPECIAL_CARD_SLOTS {
+            let Some((card, place)) = run.special_cards.get(slot) else {
+                obs.extend([0.0; SPECIAL_FEATURES]);
+                continue;
+            };
+
+            let face_up = matches!(place, CardPlace::Hand | CardPlace::Pending);
+
+            one_hot(&mut obs, Some(place_index(*place)), CARD_PLACES);
+            one_hot(&mut obs, face_up.then(|| card_type_index(&card.card_type)), CARD_TYPES);
+            obs.push(if face_up { card.value as f32 / COST_SCALE } else { 0.0 });
+            obs.push(flag(*place != CardPlace::Discarded && run.is_playable(slot)));
+        }
+
+        obs.push(run.movement_points as f32 / POINTS_SCALE);
+        obs.push(run.turns_left as f32 / TURNS_SCALE);
+        obs.push(run.deck.len() as f32 / DECK_SCALE);
+        obs.push(flag(run.phase == Phase::Moving));
+        obs.push(flag(run.phase == Phase::SpecialCards));
+        obs.push(flag(run.picked_this_turn));
+        obs.push(flag(run.discarding));
+        obs.push(flag(run.is_over()));
+
+        obs
+    }
+}
+
+fn flag(value: bool) -> f32 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn one_hot(obs: &mut Vec<f32>, index: Option<usize>, len: usize) {
+    obs.extend((0..len).map(|i| flag(Some(i) == index)));
+}
+
+fn tile_type_index(tile_type: &TileType) -> usize {
+    match tile_type {
+        TileType::Plain => 0,
+        TileType::MovementPointsUpdate => 1,
+        TileType::TurnUpdate => 2,
+        TileType::Blessing => 3,
+    }
+}
+
+fn card_type_index(card_type: &CardType) -> usize {
+    match card_type {
+        CardType::DrawMovementCard => 0,
+        CardType::MovementPointsUpdate => 1,
+        CardType::TurnUpdate => 2,
+        CardType::MovementPointsSubHighest => 3,
+        CardType::CurrentTileCostDirectChange => 4,
+        CardType::CurrentTileCostIndirectChange => 5,
+        CardType::MovementPointsMultiplyLeastCard => 6,
+        CardType::MovementPointsReductionAllCards => 7,
+        CardType::Erase => 8,
+        CardType::CancelNaughty => 9,
+    }
+}
+
+fn place_index(place: CardPlace) -> usize {
+    match place {
+        CardPlace::Board => 0,
+        CardPlace::Hand => 1,
+        CardPlace::Pending => 2,
+        CardPlace::Discarded => 3,
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_state::{GameStates, RunSeed},
+    ledger::Ledger,
+};
+
+/// Base directory for run directories, read when no `RunDirectory` is set up front.
+pub const RUN_DIR_ENV: &str = "SAFARI_RUN_DIR";
+const EVENT_LOG_FILE: &str = "events.jsonl";
+/// Events kept in memory for bug reports.
+const RECENT_EVENTS: usize = 50;
+
+/// Writes every `GameplayEvent` as a JSON line to `events.jsonl` in the run directory, when there is one.
+pub struct EventLogPlugin;
+
+impl Plugin for EventLogPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<RunDirectory>() {
+            let base = std::env::var_os(RUN_DIR_ENV).map(PathBuf::from);
+            app.insert_resource(RunDirectory(base));
+        }
+
+        app.init_resource::<GameplayLog>()
+            .add_event::<GameplayEvent>()
+            .add_systems(Startup, open_gameplay_log)
+            .add_systems(Last, write_gameplay_log);
+    }
+}
+
+/// Where files about the current run are written. `None` keeps everything in memory.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RunDirectory(pub Option<PathBuf>);
+
+impl RunDirectory {
+    /// A fresh directory for a run under `base`, named after the seed and start time.
+    pub fn for_run(base: PathBuf, seed: u64) -> PathBuf {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        base.join(format!("run-{}-{}", seed, started))
+    }
+}
+
+#[derive(Event, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GameplayEvent {
+    TileRevealed {
+        tile: u32,
+        cost: u32,
+        tile_type: String,
+        description: String,
+    },
+    Moved {
+        from: Option<u32>,
+        to: u32,
+    },
+    CardDrawn {
+        name: String,
+        value: u32,
+    },
+    SpecialCardPicked {
+        name: String,
+    },
+    SpecialCardPlayed {
+        name: String,
+        card_type: String,
+    },
+    CostCheck {
+        tile: u32,
+        cost: u32,
+        movement_points: i32,
+        met: bool,
+    },
+    GameEnded {
+        won: bool,
+        turns_left: i32,
+        movement_points: i32,
+    },
+}
+
+impl GameplayEvent {
+    pub fn phase(&self) -> GameStates {
+        match self {
+            GameplayEvent::TileRevealed { .. } => GameStates::TileReveal,
+            GameplayEvent::Moved { .. } => GameStates::PlayerMovement,
+            GameplayEvent::CardDrawn { .. } => GameStates::MovementCardsPlay,
+            GameplayEvent::SpecialCardPicked { .. } => GameStates::SpecialCardSelection,
+            GameplayEvent::SpecialCardPlayed { .. } => GameStates::SpecialCardReveal,
+            GameplayEvent::CostCheck { .. } | GameplayEvent::GameEnded { .. } => {
+                GameStates::TurnEnd
+            }
+        }
+    }
+}
+
+/// One line of the log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LoggedEvent {
+    pub turn: u32,
+    pub phase: String,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub event: GameplayEvent,
+}
+
+#[derive(Resource, Default)]
+pub struct GameplayLog {
+    pub path: Option<PathBuf>,
+    /// The last few events logged, oldest first, whether or not they went to a file.
+    pub recent: VecDeque<LoggedEvent>,
+    writer: Option<BufWriter<File>>,
+}
+
+pub fn open_gameplay_log(
+    mut run_directory: ResMut<RunDirectory>,
+    mut log: ResMut<GameplayLog>,
+    seed: Res<RunSeed>,
+) {
+    let Some(base) = run_directory.0.clone() else {
+        return;
+    };
+
+    let dir = RunDirectory::for_run(base, seed.0);
+
+    if let Err(err) = fs::create_dir_all(&dir) {
+        warn!("Could not create run directory {:?}: {}", dir, err);
+        run_directory.0 = None;
+        return;
+    }
+
+    let path = dir.join(EVENT_LOG_FILE);
+
+    match File::create(&path) {
+        Ok(file) => {
+            log.writer = Some(BufWriter::new(file));
+            log.path = Some(path);
+        }
+        Err(err) => warn!("Could not create event log {:?}: {}", path, err),
+    }
+
+    run_directory.0 = Some(dir);
+}
+
+pub fn write_gameplay_log(
+    mut events: EventReader<GameplayEvent>,
+    mut log: ResMut<GameplayLog>,
+    seed: Res<RunSeed>,
+    ledger: Res<Ledger>,
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    for ev in events.read() {
+        let line = LoggedEvent {
+            turn: ledger.turn(),
+            phase: format!("{:?}", ev.phase()),
+            seed: seed.0,
+            event: ev.clone(),
+        };
+
+        let Ok(json) = serde_json::to_string(&line) else {
+            continue;
+        };
+
+        debug!("{}", json);
+
+        if let Some(writer) = log.writer.as_mut() {
+            if let Err(err) = writeln!(writer, "{}", json) {
+                warn!("Could not write event log: {}", err);
+            }
+        }
+
+        if log.recent.len() == RECENT_EVENTS {
+            log.recent.pop_front();
+        }
+        log.recent.push_back(line);
+    }
+
+    if let Some(writer) = log.writer.as_mut() {
+        let _ = writer.flush();
+    }
+}
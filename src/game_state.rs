@@ -1,5 +1,7 @@
 use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GameStates  {
     TileReveal,
     SpecialCardSelection,
@@ -12,4 +14,30 @@ pub enum GameStates  {
 }
 
 #[derive(Resource)]
-pub struct GameState(pub GameStates);
\ No newline at end of file
+pub struct GameState(pub GameStates);
+
+/// Seed the board of the current run is generated from.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RunSeed(pub u64);
+
+impl RunSeed {
+    /// A generator of its own for one part of the run, so setup systems roll the same whatever order they run in.
+    pub fn rng(&self, stream: u64) -> StdRng {
+        StdRng::seed_from_u64(self.0 ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+}
+
+/// Randomness used while the run is played, seeded from `RunSeed` so a replay rolls the same.
+#[derive(Resource)]
+pub struct RunRng(pub StdRng);
+
+impl FromWorld for RunRng {
+    fn from_world(world: &mut World) -> Self {
+        let seed = world
+            .get_resource::<RunSeed>()
+            .copied()
+            .unwrap_or_else(|| RunSeed(rand::random()));
+
+        RunRng(seed.rng(0))
+    }
+}
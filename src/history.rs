@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::BoardLayout,
+    ledger::{Ledger, LedgerEntry, Tally},
+    tiles::{Tile, VisitedTiles},
+    ui::{GameOverText, RunSummaryText, WonText},
+};
+
+/// In board units, as the trail is drawn under the tiles' parent and moves and scales with the board.
+const TRAIL_WIDTH: f32 = 20.0;
+const TRAIL_Z: f32 = -0.5;
+
+/// Which tiles can be moved to after a cost is met, on top of the board's forward neighbours.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MovementRules {
+    pub allow_revisit: bool,
+    /// Moving to the previous column.
+    pub allow_backward: bool,
+    /// Movement points lost when entering a tile that was already visited.
+    pub revisit_penalty: i32,
+    /// Movement points lost when moving to the previous column.
+    pub backward_penalty: i32,
+}
+
+impl Default for MovementRules {
+    fn default() -> Self {
+        MovementRules {
+            allow_revisit: true,
+            allow_backward: false,
+            revisit_penalty: 0,
+            backward_penalty: 0,
+        }
+    }
+}
+
+impl MovementRules {
+    pub fn next_tiles(&self, layout: &BoardLayout, visited: &[u32], from: u32) -> Vec<u32> {
+        let mut next = layout.neighbours(from);
+
+        if self.allow_backward {
+            next.extend(layout.backward_neighbours(from));
+        }
+
+        if !self.allow_revisit {
+            next.retain(|n| !visited.contains(n));
+        }
+
+        next.sort();
+        next.dedup();
+        next
+    }
+
+    /// Movement points lost for moving from `from` to `to`, given the tiles visited so far.
+    pub fn penalty(&self, layout: &BoardLayout, visited: &[u32], from: u32, to: u32) -> i32 {
+        let mut penalty = 0;
+
+        if visited.contains(&to) {
+            penalty += self.revisit_penalty;
+        }
+
+        if layout.backward_neighbours(from).contains(&to) {
+            penalty += self.backward_penalty;
+        }
+
+        penalty
+    }
+}
+
+#[derive(Component)]
+pub struct TrailSegment;
+
+/// Sent by the final cost check, with the turns and points the run ended on.
+#[derive(Event)]
+pub struct RunEndedEvent {
+    pub won: bool,
+    pub turns_left: i32,
+    pub movement_points: i32,
+}
+
+/// What the player did during a run, filled in when the run ends.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RunSummary {
+    pub won: bool,
+    pub path: Vec<u32>,
+    pub turns_left: i32,
+    pub movement_points: i32,
+    pub ledger: Vec<LedgerEntry>,
+}
+
+impl RunSummary {
+    pub fn revisits(&self) -> usize {
+        self.path
+            .iter()
+            .enumerate()
+            .filter(|(i, n)| self.path[..*i].contains(n))
+            .count()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let path: Vec<String> = self.path.iter().map(|n| n.to_string()).collect();
+
+        let mut lines = vec![
+            format!("Path: {}", path.join(" → ")),
+            format!(
+                "Tiles visited: {} ({} revisits)",
+                self.path.len(),
+                self.revisits()
+            ),
+            format!("Turns left: {}", self.turns_left),
+            format!("Movement points: {}", self.movement_points),
+        ];
+
+        let ledger = Ledger {
+            entries: self.ledger.clone(),
+        };
+
+        for (category, total) in ledger.totals(Tally::MovementPoints) {
+            lines.push(format!("  {}: {:+}", category, total));
+        }
+
+        lines
+    }
+}
+
+pub fn draw_trail(
+    mut commands: Commands,
+    visited_tiles: Res<VisitedTiles>,
+    tiles: Query<(&Tile, &Transform, &Parent)>,
+    segments: Query<Entity, With<TrailSegment>>,
+) {
+    for entity in &segments {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(board) = tiles.iter().next().map(|(_, _, parent)| parent.get()) else {
+        return;
+    };
+
+    let position = |number: u32| {
+        tiles
+            .iter()
+            .find(|(tile, _, _)| tile.number == number)
+            .map(|(_, transform, _)| transform.translation.truncate())
+    };
+
+    for pair in visited_tiles.0.windows(2) {
+        let (Some(start), Some(end)) = (position(pair[0]), position(pair[1])) else {
+            continue;
+        };
+
+        let diff = end - start;
+        let middle = start + diff / 2.0;
+
+        let segment = commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::new(diff.length(), TRAIL_WIDTH)),
+                        color: Color::rgba(1.0, 0.85, 0.2, 0.8),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(middle.x, middle.y, TRAIL_Z)
+                        .with_rotation(Quat::from_rotation_z(diff.y.atan2(diff.x))),
+                    ..default()
+                },
+                TrailSegment,
+            ))
+            .id();
+
+        commands.entity(board).add_child(segment);
+    }
+}
+
+pub fn show_run_summary(
+    mut events: EventReader<RunEndedEvent>,
+    mut summary: ResMut<RunSummary>,
+    visited_tiles: Res<VisitedTiles>,
+    ledger: Res<Ledger>,
+    mut game_over: Query<&mut Visibility, (With<GameOverText>, Without<WonText>)>,
+    mut game_won: Query<&mut Visibility, (With<WonText>, Without<GameOverText>)>,
+    mut summary_node: Query<
+        (&mut Visibility, &Children),
+        (With<RunSummaryText>, Without<GameOverText>, Without<WonText>),
+    >,
+    mut texts: Query<&mut Text>,
+) {
+    let Some(ev) = events.read().last() else {
+        return;
+    };
+
+    *summary = RunSummary {
+        won: ev.won,
+        path: visited_tiles.0.clone(),
+        turns_left: ev.turns_left,
+        movement_points: ev.movement_points,
+        ledger: ledger.entries.clone(),
+    };
+
+    if ev.won {
+        *game_won.single_mut() = Visibility::Visible;
+    } else {
+        *game_over.single_mut() = Visibility::Visible;
+    }
+
+    let (mut vis, children) = summary_node.single_mut();
+    *vis = Visibility::Visible;
+
+    for child in children {
+        if let Ok(mut text) = texts.get_mut(*child) {
+            text.sections[0].value = summary.lines().join("\n");
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::settings::Settings;
+
+/// The window size the HUD's fonts and spacing were made for.
+pub const REFERENCE_SIZE: Vec2 = Vec2::new(1280.0, 720.0);
+/// Share of the window height kept free at the top for the HUD.
+const HUD_SHARE: f32 = 0.2;
+/// Shares of the play area's width, left to right.
+const SPECIAL_CARDS_SHARE: f32 = 0.3;
+const BOARD_SHARE: f32 = 0.4;
+/// Space around every zone, in logical pixels at the reference size.
+const MARGIN: f32 = 12.0;
+
+/// Places the board, the special cards and the movement cards in zones worked out from the window size,
+/// and scales the HUD along with them, again whenever the window is resized.
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Layout>()
+            .init_resource::<UiScale>()
+            .add_event::<WindowResized>()
+            .add_systems(Startup, read_window_size)
+            .add_systems(
+                PreUpdate,
+                (
+                    update_layout.run_if(on_event::<WindowResized>()),
+                    place_layout_roots,
+                    scale_ui.run_if(resource_changed::<Layout>().or_else(resource_exists_and_changed::<Settings>())),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Board,
+    /// The special cards dealt on the board, the hand, the waiting Naughty card and their discard pile.
+    SpecialCards,
+    /// The movement deck, the cards drawn from it and their discard pile.
+    MovementCards,
+}
+
+/// Where everything goes for the current window, in world units with the origin at the window's centre.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Layout {
+    pub window: Vec2,
+    /// How much larger than the reference size the window is, by its tighter side.
+    pub scale: f32,
+    pub hud: Rect,
+    pub board: Rect,
+    pub special_cards: Rect,
+    pub movement_cards: Rect,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::for_window(REFERENCE_SIZE)
+    }
+}
+
+impl Layout {
+    pub fn for_window(window: Vec2) -> Self {
+        let window = window.max(Vec2::ONE);
+        let scale = (window.x / REFERENCE_SIZE.x).min(window.y / REFERENCE_SIZE.y);
+        let half = window / 2.0;
+
+        let hud_bottom = half.y - window.y * HUD_SHARE;
+        let hud = Rect::new(-half.x, hud_bottom, half.x, half.y);
+
+        let margin = MARGIN * scale;
+        let play = Rect::new(-half.x, -half.y, half.x, hud_bottom);
+        let column = |from: f32, share: f32| {
+            let left = play.min.x + play.width() * from;
+            let right = left + play.width() * share;
+            Rect::new(left + margin, play.min.y + margin, right - margin, play.max.y - margin)
+        };
+
+        Layout {
+            window,
+            scale,
+            hud,
+            special_cards: column(0.0, SPECIAL_CARDS_SHARE),
+            board: column(SPECIAL_CARDS_SHARE, BOARD_SHARE),
+            movement_cards: column(SPECIAL_CARDS_SHARE + BOARD_SHARE, 1.0 - SPECIAL_CARDS_SHARE - BOARD_SHARE),
+        }
+    }
+
+    pub fn zone(&self, zone: Zone) -> Rect {
+        match zone {
+            Zone::Board => self.board,
+            Zone::SpecialCards => self.special_cards,
+            Zone::MovementCards => self.movement_cards,
+        }
+    }
+
+    /// Where a point of the window, such as the cursor, is in world units.
+    pub fn window_to_world(&self, point: Vec2) -> Vec2 {
+        Vec2::new(point.x - self.window.x / 2.0, self.window.y / 2.0 - point.y)
+    }
+
+    /// The scale and offset that fit `design`, a rect in a root's own units, centred into `zone`.
+    pub fn fit(&self, zone: Zone, design: Rect) -> (f32, Vec2) {
+        let zone = self.zone(zone);
+        let scale = (zone.width() / design.width()).min(zone.height() / design.height()).max(0.0);
+
+        (scale, zone.center() - design.center() * scale)
+    }
+}
+
+/// The parent of a group of sprites laid out in `design` units, which is moved and scaled to fit its zone.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct LayoutRoot {
+    pub zone: Zone,
+    /// The area the group's sprites take up, in the parent's own units.
+    pub design: Rect,
+}
+
+pub fn read_window_size(windows: Query<&Window, With<PrimaryWindow>>, mut layout: ResMut<Layout>) {
+    if let Ok(window) = windows.get_single() {
+        *layout = Layout::for_window(Vec2::new(window.width(), window.height()));
+    }
+}
+
+pub fn update_layout(
+    mut resized: EventReader<WindowResized>,
+    windows: Query<(), With<PrimaryWindow>>,
+    mut layout: ResMut<Layout>,
+) {
+    let Some(event) = resized.read().filter(|event| windows.contains(event.window)).last() else {
+        return;
+    };
+
+    let next = Layout::for_window(Vec2::new(event.width, event.height));
+    if *layout != next {
+        *layout = next;
+    }
+}
+
+pub fn place_layout_roots(layout: Res<Layout>, mut roots: Query<(Ref<LayoutRoot>, &mut Transform)>) {
+    for (root, mut transform) in &mut roots {
+        if !layout.is_changed() && !root.is_changed() {
+            continue;
+        }
+
+        let (scale, offset) = layout.fit(root.zone, root.design);
+        transform.scale = Vec3::new(scale, scale, transform.scale.z);
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+    }
+}
+
+/// The HUD grows with the window, on top of the player's own scale.
+pub fn scale_ui(layout: Res<Layout>, settings: Option<Res<Settings>>, mut ui_scale: ResMut<UiScale>) {
+    let chosen = settings.map_or(1.0, |settings| settings.ui_scale);
+    ui_scale.0 = chosen * layout.scale as f64;
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::{
+    bindings::{ActionMap, GameAction},
+    ui::LedgerPanel,
+};
+
+const PANEL_ENTRIES: usize = 10;
+
+/// Why movement points or turns changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cause {
+    /// The values a run starts with.
+    Start,
+    Tile(u32),
+    /// A lasting effect of a tile, resolved at the start of each turn.
+    Affliction(u32),
+    SpecialCard(String),
+    MovementCard(String),
+    /// The turn spent at the end of every turn.
+    TurnTick,
+    Console,
+}
+
+impl Cause {
+    /// Broad group the cause is totalled under in the run summary.
+    pub fn category(&self) -> &'static str {
+        match self {
+            Cause::Start => "Start",
+            Cause::Tile(_) => "Tiles",
+            Cause::Affliction(_) => "Afflictions",
+            Cause::SpecialCard(_) => "Special cards",
+            Cause::MovementCard(_) => "Movement cards",
+            Cause::TurnTick => "Turns spent",
+            Cause::Console => "Console",
+        }
+    }
+}
+
+impl fmt::Display for Cause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cause::Start => write!(f, "start of the run"),
+            Cause::Tile(number) => write!(f, "tile {}", number),
+            Cause::Affliction(number) => write!(f, "affliction from tile {}", number),
+            Cause::SpecialCard(name) => write!(f, "special card {}", name),
+            Cause::MovementCard(name) => write!(f, "movement card {}", name),
+            Cause::TurnTick => write!(f, "end of turn"),
+            Cause::Console => write!(f, "console"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tally {
+    MovementPoints,
+    Turns,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub turn: u32,
+    pub tally: Tally,
+    pub amount: i32,
+    pub cause: Cause,
+}
+
+impl fmt::Display for LedgerEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = match self.tally {
+            Tally::MovementPoints => "points",
+            Tally::Turns => "turns",
+        };
+
+        write!(
+            f,
+            "Turn {}: {:+} {} from {}",
+            self.turn, self.amount, unit, self.cause
+        )
+    }
+}
+
+/// Every change to movement points and turns, in the order it happened.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct Ledger {
+    pub entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    /// The turn being played, counting from 1.
+    pub fn turn(&self) -> u32 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.cause == Cause::TurnTick)
+            .count() as u32
+            + 1
+    }
+
+    pub fn record(&mut self, tally: Tally, amount: i32, cause: Cause) {
+        if amount == 0 {
+            return;
+        }
+
+        let turn = self.turn();
+
+        self.entries.push(LedgerEntry {
+            turn,
+            tally,
+            amount,
+            cause,
+        });
+    }
+
+    pub fn entries_for_turn(&self, turn: u32) -> impl Iterator<Item = &LedgerEntry> {
+        self.entries.iter().filter(move |entry| entry.turn == turn)
+    }
+
+    /// Net change of `tally` per cause category, in the order the categories first appear.
+    pub fn totals(&self, tally: Tally) -> Vec<(&'static str, i32)> {
+        let mut totals: Vec<(&'static str, i32)> = Vec::new();
+
+        for entry in self.entries.iter().filter(|entry| entry.tally == tally) {
+            let category = entry.cause.category();
+
+            match totals.iter_mut().find(|(name, _)| *name == category) {
+                Some((_, total)) => *total += entry.amount,
+                None => totals.push((category, entry.amount)),
+            }
+        }
+
+        totals
+    }
+}
+
+pub fn toggle_ledger_panel(
+    keys: Res<Input<KeyCode>>,
+    map: Res<ActionMap>,
+    mut panel: Query<&mut Visibility, With<LedgerPanel>>,
+) {
+    if !map.just_pressed(GameAction::Ledger, &keys) {
+        return;
+    }
+
+    for mut vis in &mut panel {
+        *vis = match *vis {
+            Visibility::Visible => Visibility::Hidden,
+            _ => Visibility::Visible,
+        };
+    }
+}
+
+pub fn update_ledger_panel(
+    ledger: Res<Ledger>,
+    panel: Query<&Children, With<LedgerPanel>>,
+    mut texts: Query<&mut Text>,
+) {
+    let skip = ledger.entries.len().saturating_sub(PANEL_ENTRIES);
+    let lines: Vec<String> = ledger
+        .entries
+        .iter()
+        .skip(skip)
+        .map(|entry| entry.to_string())
+        .collect();
+
+    for children in &panel {
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = format!("History\n{}", lines.join("\n"));
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use bindings::BindingsPlugin;
+use camera::CameraControlPlugin;
+use diagnostics::DiagnosticsPlugin;
+use event_log::EventLogPlugin;
+use game_state::{GameState, GameStates};
+use layout::LayoutPlugin;
+use modal::ModalPlugin;
+use movement::MovementPlugin;
+use navigation::NavigationPlugin;
+use replay::ReplayPlugin;
+use settings::SettingsPlugin;
+use special_cards::SpecialCardsPlugin;
+use tiles::TilesPlugin;
+use tween::TweenPlugin;
+use ui::HudPlugin;
+
+pub mod arena;
+pub mod bindings;
+pub mod board;
+pub mod bot;
+pub mod bug_report;
+pub mod camera;
+pub mod cli;
+pub mod config;
+#[cfg(feature = "dev")]
+pub mod console;
+pub mod diagnostics;
+pub mod effects;
+pub mod env;
+pub mod event_log;
+pub mod game_state;
+pub mod history;
+pub mod layout;
+pub mod ledger;
+pub mod modal;
+pub mod movement;
+pub mod navigation;
+pub mod replay;
+pub mod rules;
+pub mod settings;
+pub mod special_cards;
+pub mod tiles;
+pub mod turns;
+pub mod tween;
+pub mod ui;
+
+/// The whole game: board, movement and turns, special cards and the HUD.
+/// Expects a camera and picking to be set up by the app embedding it.
+pub struct TreacherousSafariPlugin;
+
+impl Plugin for TreacherousSafariPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameState(GameStates::TileReveal))
+            .add_plugins((
+                BindingsPlugin,
+                SettingsPlugin,
+                LayoutPlugin,
+                CameraControlPlugin,
+                TweenPlugin,
+                ModalPlugin,
+                TilesPlugin,
+                MovementPlugin,
+                SpecialCardsPlugin,
+                HudPlugin,
+                DiagnosticsPlugin,
+                EventLogPlugin,
+                ReplayPlugin,
+                NavigationPlugin,
+            ));
+
+        #[cfg(feature = "dev")]
+        app.add_plugins(console::ConsolePlugin);
+    }
+}
+
+/// Panics unless the resource `R` that `plugin` sets up is there, naming the plugin `by` that needs it.
+/// Called from `Plugin::finish`, once every plugin has been built in whatever order.
+pub(crate) fn require<R: Resource>(app: &App, by: &str, plugin: &str) {
+    assert!(app.world.contains_resource::<R>(), "{by} needs {plugin}");
+}
@@ -1,91 +1,122 @@
-use bevy::{prelude::*, input::common_conditions::input_toggle_active};
+use std::{process, time::Duration};
+
+use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_mod_picking::prelude::*;
-use bevy::{app::AppExit, log::LogPlugin};
-use tiles::{setup_tiles, on_tile_selected, tile_selected_close, on_tile_setup_complete,
-    TileSelected, TileSelectedBlockerClose, TileSetupComplete, VisitedTiles, TileClosedEvent, OverTile, 
-    on_over_tile, on_turn_ended, OffTile, on_off_tile};
-use movement::{MovementPoints, update_movement_points, MovementPointsUpdateEvent, 
-    on_tile_closed_event, setup_movement_cards, DrawCardEvent, on_draw_card, MovementCardsDrawnEvent, on_special_card_closed_event, MovementCardsPlayedEvent};
-use game_state::{GameState, GameStates};
-use turns::{TurnsLeft, TurnsUpdateEvent, update_turns_left};
-use ui::setup_game_ui;
-use special_cards::{setup_special_cards, on_movement_cards_drawn, SpecialCardPlayedEvent, SpecialCardSelected, 
-    OverSpecialCard, OffSpecialCard, on_over_special_card, on_off_special_card, on_special_card_selected, 
-    SpecialCardSelectedBlockerClose, selected_special_card_close, SpecialCardClosed};
-
-
-mod game_state;
-mod movement;
-mod tiles;
-mod turns;
-mod ui;
-mod special_cards;
+use bevy::{app::{AppExit, ScheduleRunnerPlugin}, input::InputPlugin, log::LogPlugin, window::WindowResolution};
+use treacherous_safari::{
+    bindings::{action_toggle_active, bindings_screen_closed, GameAction},
+    bot::BotPlugin,
+    bug_report::{BugReportPlugin, CrashContext},
+    cli::{CliOptions, USAGE},
+    config::ConfigDirectory,
+    replay::ActionQueue,
+    settings::{Settings, SettingsWarnings, SETTINGS_FILE},
+    TreacherousSafariPlugin,
+};
 
 
 fn main() {
-    App::new()
-        .add_plugins((
+    let options = CliOptions::parse(std::env::args().skip(1))
+        .unwrap_or_else(|err| fail(&format!("{}\n\nRun with --help to see every option.", err)));
+
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    // Read before the window and the logger are made, as both take their setup from it.
+    let config = ConfigDirectory::from_env();
+    let (mut settings, warnings) = Settings::load_or_default(config.file(SETTINGS_FILE).as_deref());
+    options.apply_window(&mut settings);
+
+    // Nobody is watching, so nothing is worth waiting for.
+    if options.headless {
+        settings.skip_animations = true;
+    }
+
+    let log = LogPlugin {
+        level: settings.log_level.level(),
+        ..default()
+    };
+
+    let mut app = App::new();
+
+    if options.headless {
+        // No window or renderer, just the game's systems at a steady rate.
+        app.add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / 60.0))),
+            log,
+            AssetPlugin::default(),
+            InputPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+        ))
+        .init_asset::<Image>();
+    } else {
+        let mut window = low_latency_window_plugin();
+
+        if let Some(primary) = window.primary_window.as_mut() {
+            primary.mode = settings.display_mode.window_mode();
+            primary.resolution = WindowResolution::new(settings.window_width as f32, settings.window_height as f32);
+        }
+
+        app.add_plugins((
             DefaultPlugins
             .set(ImagePlugin::default_linear())
-            .set(low_latency_window_plugin()),
+            .set(window)
+            .set(log),
             
             DefaultPickingPlugins
                 .build()
                 .disable::<DefaultHighlightingPlugin>(),
             ))
         .add_plugins(
-            WorldInspectorPlugin::default().run_if(input_toggle_active(false, KeyCode::Escape)),
+            // Not toggled by the key being bound on the rebinding screen.
+            WorldInspectorPlugin::default()
+                .run_if(bindings_screen_closed.and_then(action_toggle_active(false, GameAction::Inspector))),
         )
-        .insert_resource(GameState(GameStates::TileReveal))
-        .insert_resource(TurnsLeft(0))
-        .insert_resource(MovementPoints(0))
-        .insert_resource(VisitedTiles(vec![0]))
-        .add_systems(Startup, (
-            setup,
-            setup_game_ui,
-            setup_special_cards,
-            setup_movement_cards,
-            setup_tiles,))
-        .add_event::<MovementPointsUpdateEvent>()
-        .add_event::<TileSelected>()
-        .add_event::<TileSelectedBlockerClose>()
-        .add_event::<TileSetupComplete>()
-        .add_event::<TurnsUpdateEvent>()
-        .add_event::<TileClosedEvent>()
-        .add_event::<DrawCardEvent>()
-        .add_event::<MovementCardsDrawnEvent>()
-        .add_event::<SpecialCardSelected>()
-        .add_event::<OverTile>()
-        .add_event::<OffTile>()
-        .add_event::<OverSpecialCard>()
-        .add_event::<OffSpecialCard>()
-        .add_event::<SpecialCardSelectedBlockerClose>()
-        .add_event::<SpecialCardClosed>()
-        .add_event::<MovementCardsPlayedEvent>()
-        .add_systems(
-            Update,
-            (
-                update_movement_points.run_if(on_event::<MovementPointsUpdateEvent>()),
-                update_turns_left.run_if(on_event::<TurnsUpdateEvent>()),
-                on_tile_selected.run_if(on_event::<TileSelected>()),
-                tile_selected_close.run_if(on_event::<TileSelectedBlockerClose>()),
-                on_tile_setup_complete.run_if(on_event::<TileSetupComplete>()),
-                on_tile_closed_event.run_if(on_event::<TileClosedEvent>()),
-                on_draw_card.run_if(on_event::<DrawCardEvent>()),
-                on_over_tile.run_if(on_event::<OverTile>()),
-                on_off_tile.run_if(on_event::<OffTile>()),
-                on_movement_cards_drawn.run_if(on_event::<MovementCardsDrawnEvent>()),
-                on_over_special_card.run_if(on_event::<OverSpecialCard>()),
-                on_off_special_card.run_if(on_event::<OffSpecialCard>()),
-                on_special_card_selected.run_if(on_event::<SpecialCardSelected>()),
-                selected_special_card_close.run_if(on_event::<SpecialCardSelectedBlockerClose>()),
-                on_special_card_closed_event.run_if(on_event::<SpecialCardClosed>()),
-                on_turn_ended.run_if(on_event::<MovementCardsPlayedEvent>()),
-            ),
+        .add_systems(Startup, setup);
+    }
 
-        )
-        .run()
+    app.insert_resource(config)
+        .insert_resource(settings)
+        .insert_resource(SettingsWarnings(warnings));
+
+    if let Err(err) = options.install(&mut app) {
+        fail(&err);
+    }
+
+    // One hook for the whole process, writing from the context the plugin keeps up to date.
+    let crash_context = CrashContext::default();
+    crash_context.install_panic_hook();
+
+    app.insert_resource(crash_context)
+        .add_plugins((TreacherousSafariPlugin, BugReportPlugin));
+
+    // Nothing to watch without a window, so a replay is done once it has been played.
+    if options.headless && options.replay.is_some() {
+        app.add_systems(Last, exit_when_replayed);
+    }
+
+    // Commands on stdin, state on stdout; see `BotPlugin`.
+    if options.bot {
+        app.add_plugins(BotPlugin);
+    }
+
+    app.run();
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(2);
+}
+
+fn exit_when_replayed(queue: Res<ActionQueue>, mut exit: EventWriter<AppExit>) {
+    if queue.is_idle() {
+        info!("Replay finished");
+        exit.send(AppExit);
+    }
 }
 
 pub fn setup(
@@ -0,0 +1,460 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::replay::InputHandled;
+
+/// Added to the scale of a tile shown in a modal.
+pub const TILE_ZOOM: f32 = 2.0;
+/// Added to the scale of a special card shown in a modal.
+pub const CARD_ZOOM: f32 = 1.4;
+const BLOCKER_COLOR_VALUE: f32 = 0.1;
+
+/// Shows one thing at a time over the game, a tile or card drawn larger or a question for the player,
+/// and keeps the pointer off everything underneath until it is closed with `CloseModal`.
+pub struct ModalPlugin;
+
+impl Plugin for ModalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Modal>()
+            .add_event::<OpenModal>()
+            .add_event::<CloseModal>()
+            .add_event::<ModalClosed>()
+            .add_event::<ModalButtonPressed>()
+            .add_event::<InputHandled>()
+            .add_systems(Startup, setup_modal)
+            .add_systems(
+                Update,
+                (
+                    open_modal.run_if(on_event::<OpenModal>()),
+                    press_modal_button.run_if(on_event::<ModalButtonPressed>()),
+                    close_modal.run_if(on_event::<CloseModal>()),
+                    (show_modal, fill_modal_panel).run_if(resource_changed::<Modal>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// What a modal shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModalContent {
+    /// A tile on the board, drawn larger where it is.
+    TileDetails(Entity),
+    /// A special card, drawn larger where it is.
+    CardDetails(Entity),
+    /// A question the player confirms or cancels.
+    Confirmation(String),
+    /// A question answered by picking one of `choices`.
+    Choice { prompt: String, choices: Vec<String> },
+}
+
+impl ModalContent {
+    /// The tile or card being shown, if any.
+    pub fn subject(&self) -> Option<Entity> {
+        match self {
+            ModalContent::TileDetails(entity) | ModalContent::CardDetails(entity) => Some(*entity),
+            ModalContent::Confirmation(_) | ModalContent::Choice { .. } => None,
+        }
+    }
+
+    fn zoom(&self) -> f32 {
+        match self {
+            ModalContent::TileDetails(_) => TILE_ZOOM,
+            ModalContent::CardDetails(_) => CARD_ZOOM,
+            ModalContent::Confirmation(_) | ModalContent::Choice { .. } => 0.0,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            ModalContent::Confirmation(message) => message,
+            ModalContent::Choice { prompt, .. } => prompt,
+            ModalContent::TileDetails(_) | ModalContent::CardDetails(_) => "",
+        }
+    }
+
+    fn answers(&self) -> Vec<(String, ModalAnswer)> {
+        match self {
+            ModalContent::Confirmation(_) => vec![
+                (String::from("Confirm"), ModalAnswer::Confirm),
+                (String::from("Cancel"), ModalAnswer::Cancel),
+            ],
+            ModalContent::Choice { choices, .. } => choices
+                .iter()
+                .enumerate()
+                .map(|(i, choice)| (choice.clone(), ModalAnswer::Choose(i)))
+                .collect(),
+            ModalContent::TileDetails(_) | ModalContent::CardDetails(_) => Vec::new(),
+        }
+    }
+}
+
+/// How the player closed a modal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModalAnswer {
+    /// Closed without answering, e.g. with the close button of a tile or card.
+    Dismiss,
+    Confirm,
+    Cancel,
+    /// Picked the choice at this index.
+    Choose(usize),
+}
+
+/// The modal open now, if any.
+#[derive(Resource, Default, Debug)]
+pub struct Modal {
+    content: Option<ModalContent>,
+    /// The subject's scale and depth from before it was drawn larger.
+    raised_from: Option<(Vec3, f32)>,
+}
+
+impl Modal {
+    pub fn is_open(&self) -> bool {
+        self.content.is_some()
+    }
+
+    pub fn content(&self) -> Option<&ModalContent> {
+        self.content.as_ref()
+    }
+
+    /// Whether `entity` is the tile or card being shown.
+    pub fn shows(&self, entity: Entity) -> bool {
+        self.content
+            .as_ref()
+            .and_then(ModalContent::subject)
+            .is_some_and(|subject| subject == entity)
+    }
+}
+
+/// Opens a modal, unless one is open already.
+#[derive(Event, Debug, Clone)]
+pub struct OpenModal(pub ModalContent);
+
+/// Closes the open modal with an answer.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CloseModal(pub ModalAnswer);
+
+impl From<ListenerInput<Pointer<Click>>> for CloseModal {
+    fn from(_event: ListenerInput<Pointer<Click>>) -> Self {
+        CloseModal(ModalAnswer::Dismiss)
+    }
+}
+
+/// Sent once a modal has closed, for whoever opened it to act on the answer.
+#[derive(Event, Debug, Clone)]
+pub struct ModalClosed {
+    pub content: ModalContent,
+    pub answer: ModalAnswer,
+}
+
+#[derive(Event)]
+pub struct ModalButtonPressed(pub Entity);
+
+impl From<ListenerInput<Pointer<Click>>> for ModalButtonPressed {
+    fn from(event: ListenerInput<Pointer<Click>>) -> Self {
+        ModalButtonPressed(event.listener())
+    }
+}
+
+/// Dims the board and catches the pointer while a modal is open.
+#[derive(Component)]
+pub struct ModalBlocker;
+
+/// Holds the modal's buttons and panel, and keeps the pointer off the rest of the interface.
+#[derive(Component)]
+pub struct ModalRoot;
+
+#[derive(Component)]
+pub struct ModalCloseButton;
+
+/// The message and answers of a confirmation or choice.
+#[derive(Component)]
+pub struct ModalPanel;
+
+#[derive(Component)]
+pub struct ModalText;
+
+#[derive(Component)]
+pub struct ModalAnswers;
+
+#[derive(Component, Debug)]
+pub struct ModalButton(pub ModalAnswer);
+
+pub fn setup_modal(mut commands: Commands) {
+    commands
+        .spawn((
+            SpatialBundle {
+                transform: Transform::from_scale(Vec3::splat(100.0)),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            ModalBlocker,
+            PickableBundle::default(),
+        ))
+        .with_children(|commands| {
+            commands.spawn((SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(1000.0)),
+                    color: Color::rgba(
+                        BLOCKER_COLOR_VALUE,
+                        BLOCKER_COLOR_VALUE,
+                        BLOCKER_COLOR_VALUE,
+                        0.2,
+                    ),
+                    ..default()
+                },
+                ..default()
+            },));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(5),
+                ..default()
+            },
+            ModalRoot,
+        ))
+        .with_children(|commands| {
+            commands
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            width: Val::Percent(10.0),
+                            height: Val::Px(42.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            left: Val::Percent(85.0),
+                            top: Val::Percent(10.0),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(Color::RED),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    On::<Pointer<Click>>::send_event::<CloseModal>(),
+                    NoDeselect,
+                    ModalCloseButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle {
+                            text: Text::from_section(
+                                "X",
+                                TextStyle {
+                                    font_size: 40.0,
+                                    color: Color::rgb(0.9, 0.9, 0.9),
+                                    ..default()
+                                },
+                            ),
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                    ));
+                });
+
+            commands
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(40.0),
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(16.0)),
+                            row_gap: Val::Px(16.0),
+                            ..default()
+                        },
+                        background_color: Color::rgba(0.0, 0.0, 0.0, 0.9).into(),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    ModalPanel,
+                ))
+                .with_children(|commands| {
+                    commands.spawn((
+                        TextBundle {
+                            text: Text::from_section(
+                                "",
+                                TextStyle {
+                                    font_size: 24.0,
+                                    color: Color::rgb(0.9, 0.9, 0.9),
+                                    ..default()
+                                },
+                            ),
+                            ..default()
+                        },
+                        ModalText,
+                        Pickable::IGNORE,
+                    ));
+
+                    commands.spawn((
+                        NodeBundle {
+                            style: Style {
+                                column_gap: Val::Px(16.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        ModalAnswers,
+                        Pickable::IGNORE,
+                    ));
+                });
+        });
+}
+
+pub fn open_modal(
+    mut events: EventReader<OpenModal>,
+    mut modal: ResMut<Modal>,
+    mut transforms: Query<&mut Transform>,
+) {
+    for ev in events.read() {
+        if modal.is_open() {
+            warn!("Modal {:?} asked for while {:?} is open", ev.0, modal.content);
+            continue;
+        }
+
+        modal.raised_from = None;
+
+        if let Some(mut transform) = ev.0.subject().and_then(|e| transforms.get_mut(e).ok()) {
+            modal.raised_from = Some((transform.scale, transform.translation.z));
+
+            let zoom = ev.0.zoom();
+            transform.scale.x += zoom;
+            transform.scale.y += zoom;
+            transform.translation.z = 1.0;
+        }
+
+        modal.content = Some(ev.0.clone());
+    }
+}
+
+pub fn press_modal_button(
+    mut events: EventReader<ModalButtonPressed>,
+    buttons: Query<&ModalButton>,
+    mut close: EventWriter<CloseModal>,
+) {
+    for ev in events.read() {
+        if let Ok(button) = buttons.get(ev.0) {
+            close.send(CloseModal(button.0));
+        }
+    }
+}
+
+pub fn close_modal(
+    mut events: EventReader<CloseModal>,
+    mut modal: ResMut<Modal>,
+    mut transforms: Query<&mut Transform>,
+    mut closed: EventWriter<ModalClosed>,
+    mut handled: EventWriter<InputHandled>,
+) {
+    for ev in events.read() {
+        let Some(content) = modal.content.take() else {
+            continue;
+        };
+
+        handled.send(InputHandled::Answer {
+            question: content.subject().is_none(),
+            answer: ev.0,
+        });
+
+        if let Some((scale, z)) = modal.raised_from.take() {
+            if let Some(mut transform) = content.subject().and_then(|e| transforms.get_mut(e).ok()) {
+                transform.scale = scale;
+                transform.translation.z = z;
+            }
+        }
+
+        closed.send(ModalClosed {
+            content,
+            answer: ev.0,
+        });
+    }
+}
+
+type CloseButtonFilter = (With<ModalCloseButton>, Without<ModalRoot>, Without<ModalBlocker>);
+
+type PanelFilter = (
+    With<ModalPanel>,
+    Without<ModalCloseButton>,
+    Without<ModalRoot>,
+    Without<ModalBlocker>,
+);
+
+pub fn show_modal(
+    modal: Res<Modal>,
+    mut blocker: Query<&mut Visibility, With<ModalBlocker>>,
+    mut root: Query<&mut Visibility, (With<ModalRoot>, Without<ModalBlocker>)>,
+    mut close_button: Query<&mut Visibility, CloseButtonFilter>,
+    mut panel: Query<&mut Visibility, PanelFilter>,
+) {
+    let shown = |shown: bool| if shown { Visibility::Visible } else { Visibility::Hidden };
+    let content = modal.content();
+
+    *blocker.single_mut() = shown(content.is_some());
+    *root.single_mut() = shown(content.is_some());
+    *close_button.single_mut() = shown(content.is_some_and(|c| c.subject().is_some()));
+    *panel.single_mut() = shown(content.is_some_and(|c| c.subject().is_none()));
+}
+
+/// Writes the message of a confirmation or choice and gives it a button for each answer.
+pub fn fill_modal_panel(
+    mut commands: Commands,
+    modal: Res<Modal>,
+    mut text: Query<&mut Text, With<ModalText>>,
+    answers: Query<Entity, With<ModalAnswers>>,
+) {
+    let content = modal.content();
+
+    text.single_mut().sections[0].value = content.map_or("", ModalContent::message).to_string();
+
+    let answers = answers.single();
+    commands.entity(answers).despawn_descendants();
+
+    for (label, answer) in content.map_or_else(Vec::new, ModalContent::answers) {
+        let button = commands
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(24.0), Val::Px(8.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::SEA_GREEN),
+                    ..default()
+                },
+                On::<Pointer<Click>>::send_event::<ModalButtonPressed>(),
+                NoDeselect,
+                ModalButton(answer),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle {
+                        text: Text::from_section(
+                            label,
+                            TextStyle {
+                                font_size: 32.0,
+                                color: Color::rgb(0.9, 0.9, 0.9),
+                                ..default()
+                            },
+                        ),
+                        ..default()
+                    },
+                    Pickable::IGNORE,
+                ));
+            })
+            .id();
+
+        commands.entity(answers).add_child(button);
+    }
+}
@@ -5,20 +5,84 @@ use bevy::{
 };
 use rand::Rng;
 
-use crate::{tiles::TileClosedEvent, ui::MovementPointsText, special_cards::SpecialCardClosed};
+use crate::{
+    effects::{EffectQueue, TimingWindow},
+    event_log::GameplayEvent,
+    game_state::RunSeed,
+    layout::{LayoutRoot, Zone},
+    ledger::{Cause, Ledger, Tally},
+    special_cards::SpecialCardClosed,
+    tiles::{on_turn_ended, TileClosedEvent},
+    turns::{update_turns_left, TurnsLeft, TurnsUpdateEvent},
+    tween::{lean, AnimationGate, Ease, Flip, Tween},
+    ui::MovementPointsText,
+};
 
-const CARDS_TO_DRAW: u32 = 2;
+pub const CARDS_TO_DRAW: u32 = 2;
+pub const DECK_SIZE: usize = 15;
 const DRAWN_CARDS_START: f32 = 1400.0;
 const DRAWN_CARDS_SPACE: f32 = 400.0;
+/// How long a card takes to come off the deck, before it is turned over, in seconds.
+const DRAW_SECONDS: f32 = 0.35;
+const DISCARD_SECONDS: f32 = 0.4;
 
 const X_FINAL: f32 = 1400.0;
 const Y_FINAL: f32 = 0.0;
+/// The deck, the drawn cards and the discard pile, in the units of the positions above.
+const DESIGN: Rect = Rect {
+    min: Vec2::new(-150.0, -830.0),
+    max: Vec2::new(1550.0, 230.0),
+};
+
+pub(crate) const DECK_RNG_STREAM: u64 = 1;
+
+/// The movement deck, the points and turns it feeds, and the ledger of where they came from.
+/// Needs [`TweenPlugin`](crate::tween::TweenPlugin); the HUD and the event log are optional.
+pub struct MovementPlugin;
+
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MovementPoints(0))
+            .insert_resource(TurnsLeft(0))
+            .init_resource::<EffectQueue>()
+            .init_resource::<Ledger>()
+            .add_event::<MovementPointsUpdateEvent>()
+            .add_event::<GameplayEvent>()
+            .add_event::<TurnsUpdateEvent>()
+            .add_event::<DrawCardEvent>()
+            .add_event::<MovementCardsDrawnEvent>()
+            .add_event::<MovementCardsPlayedEvent>()
+            .add_event::<TileClosedEvent>()
+            .add_event::<SpecialCardClosed>()
+            .add_systems(Startup, setup_movement_cards)
+            .add_systems(
+                Update,
+                (
+                    update_movement_points
+                        .run_if(on_event::<MovementPointsUpdateEvent>())
+                        .after(on_special_card_closed_event)
+                        .before(on_turn_ended),
+                    update_turns_left
+                        .run_if(on_event::<TurnsUpdateEvent>())
+                        .after(on_special_card_closed_event)
+                        .before(on_turn_ended),
+                    on_tile_closed_event.run_if(on_event::<TileClosedEvent>()),
+                    on_draw_card.run_if(on_event::<DrawCardEvent>()),
+                    on_special_card_closed_event.run_if(on_event::<SpecialCardClosed>()),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        crate::require::<AnimationGate>(app, "MovementPlugin", "TweenPlugin");
+    }
+}
 
 #[derive(Resource)]
 pub struct MovementPoints(pub i32);
 
 #[derive(Event)]
-pub struct MovementPointsUpdateEvent(pub i32);
+pub struct MovementPointsUpdateEvent(pub i32, pub Cause);
 
 #[derive(Event)]
 pub struct DrawCardEvent(pub u32);
@@ -32,15 +96,21 @@ pub struct MovementCardsPlayedEvent;
 #[derive(Component)]
 pub struct MovementCardCover(pub Entity);
 
+/// On the table, until it is discarded.
 #[derive(Component)]
 pub struct MovementCardDrawn;
 
+/// On the discard pile; it no longer counts as drawn.
 #[derive(Component)]
 pub struct MovementCardDiscarded;
 
 #[derive(Component)]
 pub struct CardPointsText;
 
+/// Where a movement card lies in the deck it was dealt in, counting from the top. Cards are drawn in this order.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct DeckPosition(pub usize);
+
 #[derive(Component, Debug, Clone, Default)]
 pub struct MovementCard {
     pub name: String,
@@ -52,56 +122,121 @@ pub fn update_movement_points(
     mut movement_points: ResMut<MovementPoints>,
     mut movement_points_update: EventReader<MovementPointsUpdateEvent>,
     mut texts: Query<&mut Text, With<MovementPointsText>>,
+    mut ledger: ResMut<Ledger>,
 ) {
-    let mut text = texts.single_mut();
-
     for ev in movement_points_update.read() {
         movement_points.0 += ev.0;
+        ledger.record(Tally::MovementPoints, ev.0, ev.1.clone());
+    }
+
+    // The HUD is optional, so there may be no text to show the count on.
+    if let Ok(mut text) = texts.get_single_mut() {
+        text.sections[0].value = format!("Points: {:?}", movement_points.0);
+    }
+}
+
+/// The chance that `draws` cards off `deck`, in any order, add up to at least `needed`.
+/// Draws the whole deck when it has fewer cards left.
+pub fn draw_odds(deck: &[u32], draws: usize, needed: i32) -> f32 {
+    if needed <= 0 {
+        return 1.0;
+    }
+
+    let mut covering = 0;
+    let mut hands = 0;
+    count_hands(deck, draws.min(deck.len()), 0, needed, &mut covering, &mut hands);
+
+    covering as f32 / hands as f32
+}
+
+/// Counts every hand of `draws` cards from `deck`, and those that bring `sum` up to `needed`.
+fn count_hands(deck: &[u32], draws: usize, sum: i32, needed: i32, covering: &mut u64, hands: &mut u64) {
+    if draws == 0 {
+        *hands += 1;
+
+        if sum >= needed {
+            *covering += 1;
+        }
+
+        return;
     }
 
-    text.sections[0].value = format!("Points: {:?}", movement_points.0);
+    for (i, value) in deck.iter().enumerate().take(deck.len() + 1 - draws) {
+        count_hands(&deck[i + 1..], draws - 1, sum + *value as i32, needed, covering, hands);
+    }
 }
 
 pub fn on_tile_closed_event(
     mut commands: Commands, 
     mut events: EventReader<TileClosedEvent>,
-    mut playable_cards_query: Query<(Entity, &mut Transform , &mut MovementCard, &Children), 
+    mut playable_cards_query: Query<(Entity, &mut Transform , &mut MovementCard, &Children, &DeckPosition), 
     (Without<MovementCardDrawn>, Without<MovementCardDiscarded>)>,
-    mut cards_drawn: EventWriter<MovementCardsDrawnEvent>,
-    mut card_cover: Query<&mut Visibility, With<MovementCardCover>>,
+    mut gate: ResMut<AnimationGate>,
+    card_cover: Query<(), With<MovementCardCover>>,
+    mut effect_queue: ResMut<EffectQueue>,
+    mut movement_points_update: EventWriter<MovementPointsUpdateEvent>,
+    mut turns_update: EventWriter<TurnsUpdateEvent>,
+    mut gameplay_log: EventWriter<GameplayEvent>,
 ) {
+    effect_queue.resolve(
+        TimingWindow::StartOfTurn,
+        &mut movement_points_update,
+        &mut turns_update,
+    );
+
     let mut count = 0;
+    let mut deck: Vec<_> = playable_cards_query.iter_mut().collect();
+    deck.sort_by_key(|(.., position)| position.0);
 
-    for (entity, mut transform, mut card, mut children) in &mut playable_cards_query{
+    for (entity, mut transform, mut card, mut children, _) in deck {
         if count == CARDS_TO_DRAW{
             break;
         }
 
         for child in children {
-            if let Ok(mut vis) = card_cover.get_mut(*child) {
-                *vis = Visibility::Hidden;
+            if card_cover.contains(*child) {
+                commands.entity(entity).insert(Flip::reveal(*child).after(DRAW_SECONDS));
             }
         }
 
-        transform.translation.x -= (DRAWN_CARDS_START - (DRAWN_CARDS_SPACE * count as f32));
-
-
-        commands.entity(entity).insert(MovementCardDrawn);
+        let offset = DRAWN_CARDS_START - (DRAWN_CARDS_SPACE * count as f32);
+        commands.entity(entity).insert((MovementCardDrawn, draw_tween(&transform, offset)));
+        gameplay_log.send(GameplayEvent::CardDrawn {
+            name: card.name.clone(),
+            value: card.value,
+        });
 
         count += 1;
     }
 
-    cards_drawn.send(MovementCardsDrawnEvent);
+    // Special cards are picked once the hand is on the table.
+    gate.send(MovementCardsDrawnEvent);
+}
+
+/// Slides a card from the deck `offset` to the left.
+fn draw_tween(transform: &Transform, offset: f32) -> Tween {
+    Tween::new(DRAW_SECONDS, Ease::CubicOut).translate_to(transform.translation - Vec3::X * offset)
+}
+
+/// Sends a card to the discard pile at its usual size, turning it a little further from however it leans.
+fn discard_tween(transform: &Transform) -> Tween {
+    let mut rng = rand::thread_rng();
+
+    Tween::new(DISCARD_SECONDS, Ease::QuadInOut)
+        .translate_to(Vec3::new(X_FINAL, Y_FINAL, transform.translation.z))
+        .rotate_to(Quat::from_rotation_z(lean(transform.rotation) + rng.gen_range(-0.1..=0.1)))
+        .scale_to(Vec3::ONE)
 }
 
 pub fn on_draw_card(
     mut commands: Commands, 
     mut events: EventReader<DrawCardEvent>,
-    mut playable_cards_query: Query<(Entity, &mut Transform , &mut MovementCard, &Children), 
+    mut playable_cards_query: Query<(Entity, &mut Transform , &mut MovementCard, &Children, &DeckPosition), 
     (Without<MovementCardDrawn>, Without<MovementCardDiscarded>)>,
     mut drawn_cards_query: Query<(Entity, &mut Transform , &mut MovementCard, &Children), 
     (With<MovementCardDrawn>, Without<MovementCardDiscarded>)>,
-    mut card_cover: Query<&mut Visibility, With<MovementCardCover>>,
+    card_cover: Query<(), With<MovementCardCover>>,
+    mut gameplay_log: EventWriter<GameplayEvent>,
 ) {
 
     let mut count = 0;
@@ -111,44 +246,40 @@ pub fn on_draw_card(
     }
 
     if count > 1 {
-        info!("Count is > 1 and found {}", drawn_cards_query.iter().len());
         for (entity,mut transform, mut card, mut children) in &mut drawn_cards_query{
-            commands.entity(entity).insert(MovementCardDiscarded);
-
-
-            let diff_x = X_FINAL - transform.translation.x;
-            let diff_y = Y_FINAL - transform.translation.y;
-
-            transform.translation.x += diff_x;
-            transform.translation.y+= diff_y;
-
-            let mut rng = rand::thread_rng();
-
-            transform.rotate_z(rng.gen_range(-0.1..=0.1));
+            commands
+                .entity(entity)
+                .remove::<MovementCardDrawn>()
+                .insert((MovementCardDiscarded, discard_tween(&transform)));
         }
     }
 
     let mut counting = 0;
 
     let mut spacing_count = 0;
+    let mut deck: Vec<_> = playable_cards_query.iter_mut().collect();
+    deck.sort_by_key(|(.., position)| position.0);
 
+    for (entity, mut transform, mut card, mut children, _) in deck {
 
-    for (entity,mut transform, mut card, mut children) in &mut playable_cards_query{
-
-        if count == 1{
-            transform.translation.x -= (DRAWN_CARDS_START - (DRAWN_CARDS_SPACE * 2.0));
+        let offset = if count == 1{
+            DRAWN_CARDS_START - (DRAWN_CARDS_SPACE * 2.0)
         }
         else{
-            transform.translation.x -= (DRAWN_CARDS_START - (DRAWN_CARDS_SPACE * spacing_count as f32));
-        }
+            DRAWN_CARDS_START - (DRAWN_CARDS_SPACE * spacing_count as f32)
+        };
 
         for child in children {
-            if let Ok(mut vis) = card_cover.get_mut(*child) {
-                *vis = Visibility::Hidden;
+            if card_cover.contains(*child) {
+                commands.entity(entity).insert(Flip::reveal(*child).after(DRAW_SECONDS));
             }
         }
 
-        commands.entity(entity).insert(MovementCardDrawn);
+        commands.entity(entity).insert((MovementCardDrawn, draw_tween(&transform, offset)));
+        gameplay_log.send(GameplayEvent::CardDrawn {
+            name: card.name.clone(),
+            value: card.value,
+        });
 
         counting += 1;
         spacing_count += 1;
@@ -165,60 +296,63 @@ pub fn on_special_card_closed_event(
     mut drawn_cards_query: Query<(Entity, &mut Transform , &mut MovementCard, &Children), 
     (With<MovementCardDrawn>, Without<MovementCardDiscarded>)>,
     mut movement_points_update: EventWriter<MovementPointsUpdateEvent>,
-    mut movement_cards_played: EventWriter<MovementCardsPlayedEvent>,
+    mut gate: ResMut<AnimationGate>,
+    mut effect_queue: ResMut<EffectQueue>,
+    mut turns_update: EventWriter<TurnsUpdateEvent>,
 )
 {
     for (entity,mut transform, mut card, mut children) in &mut drawn_cards_query{
-        commands.entity(entity).insert(MovementCardDiscarded);
-
-
-        let diff_x = X_FINAL - transform.translation.x;
-        let diff_y = Y_FINAL - transform.translation.y;
-
-        transform.translation.x += diff_x;
-        transform.translation.y+= diff_y;
-
-        let mut rng = rand::thread_rng();
-
-        transform.rotate_z(rng.gen_range(-0.1..=0.1));
-
-        movement_points_update.send(MovementPointsUpdateEvent(card.value as i32));
+        commands
+            .entity(entity)
+            .remove::<MovementCardDrawn>()
+            .insert((MovementCardDiscarded, discard_tween(&transform)));
+
+        movement_points_update.send(MovementPointsUpdateEvent(
+            card.value as i32,
+            Cause::MovementCard(card.name.clone()),
+        ));
     }
 
-    movement_cards_played.send(MovementCardsPlayedEvent);
+    effect_queue.resolve(
+        TimingWindow::OnEnter,
+        &mut movement_points_update,
+        &mut turns_update,
+    );
+    effect_queue.resolve(
+        TimingWindow::BeforeCostCheck,
+        &mut movement_points_update,
+        &mut turns_update,
+    );
+
+    // The cost is checked once the played cards are on the discard pile.
+    gate.send(MovementCardsPlayedEvent);
 
 }
 
 
-pub fn setup_movement_cards(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn setup_movement_cards(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    seed: Res<RunSeed>,
+) {
     let len = 300.0;
     let height = 450.0;
     let sprite_size = Some(Vec2::new(len, height));
-    let mut card_res = generate_cards();
+    let deck = deal_deck(&mut seed.rng(DECK_RNG_STREAM));
 
     commands
-        .spawn((SpatialBundle {
-            transform: Transform::from_scale(Vec3 {
-                x: 0.4,
-                y: 0.4,
-                z: 1.0,
-            }),
-            ..default()
-        },))
+        .spawn((
+            SpatialBundle::default(),
+            LayoutRoot {
+                zone: Zone::MovementCards,
+                design: DESIGN,
+            },
+        ))
         .with_children(|commands| {
             const X_START: f32 = 1400.0;
             const Y_START: f32 = -600.0;
 
-            let mut card_res_index: usize = 30;
-
-            
-
-            for _ in 0..15 {
-                let mut rng = rand::thread_rng();
-
-                card_res_index = rng.gen_range(0..card_res.len());
-                let card = &mut card_res[card_res_index];
-
+            for (position, card) in deck.iter().enumerate() {
                 commands
                     .spawn((
                         SpriteBundle {
@@ -232,6 +366,7 @@ pub fn setup_movement_cards(mut commands: Commands, asset_server: Res<AssetServe
                             ..default()
                         },
                         card.clone(),
+                        DeckPosition(position),
                     ))
                     .with_children(|parent: &mut ChildBuilder<'_, '_, '_>| {
                         parent.spawn(Text2dBundle {
@@ -284,13 +419,18 @@ pub fn setup_movement_cards(mut commands: Commands, asset_server: Res<AssetServe
                         ));
                     });
             }
-
-            if card_res_index < card_res.len() {
-                card_res.remove(card_res_index);
-            }
         });
 }
 
+/// The movement cards of a run, in the order they are laid out.
+pub fn deal_deck(rng: &mut impl Rng) -> Vec<MovementCard> {
+    let card_res = generate_cards();
+
+    (0..DECK_SIZE)
+        .map(|_| card_res[rng.gen_range(0..card_res.len())].clone())
+        .collect()
+}
+
 pub fn generate_cards() -> Vec<MovementCard> {
     let mut card_res = Vec::with_capacity(2);
 
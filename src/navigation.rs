@@ -0,0 +1,322 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    bindings::{bindings_screen_closed, ActionMap, GameAction},
+    modal::{CloseModal, Modal, ModalAnswer},
+    settings::settings_screen_closed,
+    special_cards::{
+        OffSpecialCard, OverSpecialCard, SpecialCard, SpecialCardDiscarded, SpecialCardHand,
+        SpecialCardInHand, SpecialCardSelectable, SpecialCardSelected, SpecialCardUnplayable,
+        SpecialHandConfirmButton, SpecialHandConfirmed,
+    },
+    tiles::{OffTile, OverTile, Selectable, Tile, TileSelected},
+};
+
+/// How far off the pressed direction a target may lie and still be moved to.
+const MIN_ALIGNMENT: f32 = 0.5;
+/// How much being off the pressed direction counts against a target, next to its distance.
+const SIDEWAYS_WEIGHT: f32 = 2.0;
+
+/// Moves a focus between the tiles and special cards that can be picked with arrow keys or a D-pad,
+/// showing the same highlight as the pointer, and confirms or cancels with keys or gamepad buttons.
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        let read_input = read_navigation_input
+            .run_if(bindings_screen_closed)
+            .run_if(settings_screen_closed);
+        // Typing in the console must not move the focus or pick what it is on.
+        #[cfg(feature = "dev")]
+        let read_input = read_input.run_if(crate::console::console_closed);
+
+        app.init_resource::<Focus>()
+            .add_event::<NavigationInput>()
+            .add_event::<OverTile>()
+            .add_event::<OffTile>()
+            .add_event::<TileSelected>()
+            .add_event::<OverSpecialCard>()
+            .add_event::<OffSpecialCard>()
+            .add_event::<SpecialCardSelected>()
+            .add_event::<CloseModal>()
+            .add_event::<SpecialHandConfirmed>()
+            .add_systems(
+                Update,
+                (
+                    read_input,
+                    drop_lost_focus,
+                    navigate.run_if(on_event::<NavigationInput>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The tile or special card keyboard and gamepad input acts on.
+#[derive(Resource, Default, Debug)]
+pub struct Focus(pub Option<Entity>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn vector(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::Y,
+            Direction::Down => Vec2::NEG_Y,
+            Direction::Left => Vec2::NEG_X,
+            Direction::Right => Vec2::X,
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum NavigationInput {
+    Move(Direction),
+    /// Picks the focused target, or closes the open reveal, or plays the hand when nothing is focused.
+    Confirm,
+    /// Drops the focus.
+    Cancel,
+}
+
+impl NavigationInput {
+    fn from_action(action: GameAction) -> Option<Self> {
+        match action {
+            GameAction::Up => Some(NavigationInput::Move(Direction::Up)),
+            GameAction::Down => Some(NavigationInput::Move(Direction::Down)),
+            GameAction::Left => Some(NavigationInput::Move(Direction::Left)),
+            GameAction::Right => Some(NavigationInput::Move(Direction::Right)),
+            GameAction::Confirm => Some(NavigationInput::Confirm),
+            GameAction::Cancel => Some(NavigationInput::Cancel),
+            _ => None,
+        }
+    }
+
+    fn from_button(button: GamepadButtonType) -> Option<Self> {
+        match button {
+            GamepadButtonType::DPadUp => Some(NavigationInput::Move(Direction::Up)),
+            GamepadButtonType::DPadDown => Some(NavigationInput::Move(Direction::Down)),
+            GamepadButtonType::DPadLeft => Some(NavigationInput::Move(Direction::Left)),
+            GamepadButtonType::DPadRight => Some(NavigationInput::Move(Direction::Right)),
+            GamepadButtonType::South => Some(NavigationInput::Confirm),
+            GamepadButtonType::East => Some(NavigationInput::Cancel),
+            _ => None,
+        }
+    }
+}
+
+type TileTargetFilter = (With<Tile>, With<Selectable>);
+
+type CardTarget = (
+    Entity,
+    &'static GlobalTransform,
+    Has<SpecialCardInHand>,
+    Has<SpecialCardUnplayable>,
+);
+
+type CardTargetFilter = (
+    With<SpecialCard>,
+    Or<(With<SpecialCardSelectable>, With<SpecialCardInHand>)>,
+    Without<SpecialCardDiscarded>,
+);
+
+/// What the focus can be moved to.
+#[derive(SystemParam)]
+pub struct NavigationTargets<'w, 's> {
+    hand: Res<'w, SpecialCardHand>,
+    tiles: Query<'w, 's, (Entity, &'static GlobalTransform), TileTargetFilter>,
+    cards: Query<'w, 's, CardTarget, CardTargetFilter>,
+}
+
+impl NavigationTargets<'_, '_> {
+    fn positions(&self) -> Vec<(Entity, Vec2)> {
+        let mut targets: Vec<(Entity, Vec2)> = self
+            .tiles
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation().truncate()))
+            .collect();
+
+        // While the hand is full only its cards can be picked, to be discarded.
+        targets.extend(
+            self.cards
+                .iter()
+                .filter(|(_, _, in_hand, unplayable)| {
+                    if self.hand.discarding {
+                        *in_hand
+                    } else {
+                        !unplayable
+                    }
+                })
+                .map(|(entity, transform, _, _)| (entity, transform.translation().truncate())),
+        );
+
+        targets
+    }
+}
+
+/// What navigating sends on, the same events the pointer sends.
+#[derive(SystemParam)]
+pub struct NavigationEvents<'w> {
+    over_tile: EventWriter<'w, OverTile>,
+    off_tile: EventWriter<'w, OffTile>,
+    tile_selected: EventWriter<'w, TileSelected>,
+    over_card: EventWriter<'w, OverSpecialCard>,
+    off_card: EventWriter<'w, OffSpecialCard>,
+    card_selected: EventWriter<'w, SpecialCardSelected>,
+    close_modal: EventWriter<'w, CloseModal>,
+    hand_confirmed: EventWriter<'w, SpecialHandConfirmed>,
+}
+
+pub fn read_navigation_input(
+    keys: Option<Res<Input<KeyCode>>>,
+    map: Res<ActionMap>,
+    buttons: Option<Res<Input<GamepadButton>>>,
+    mut navigation: EventWriter<NavigationInput>,
+) {
+    if let Some(keys) = keys {
+        for key in keys.get_just_pressed() {
+            if let Some(input) = map.action(*key).and_then(NavigationInput::from_action) {
+                navigation.send(input);
+            }
+        }
+    }
+
+    if let Some(buttons) = buttons {
+        for button in buttons.get_just_pressed() {
+            if let Some(input) = NavigationInput::from_button(button.button_type) {
+                navigation.send(input);
+            }
+        }
+    }
+}
+
+pub fn navigate(
+    mut inputs: EventReader<NavigationInput>,
+    mut focus: ResMut<Focus>,
+    targets: NavigationTargets,
+    modal: Res<Modal>,
+    confirm_button: Query<&Visibility, With<SpecialHandConfirmButton>>,
+    mut events: NavigationEvents,
+) {
+    let is_tile = |entity: Entity| targets.tiles.contains(entity);
+    let targets = targets.positions();
+    let visible = |vis: &Visibility| *vis == Visibility::Visible;
+
+    for input in inputs.read() {
+        match input {
+            NavigationInput::Move(direction) => {
+                let next = match focus.0.and_then(|f| targets.iter().find(|(e, _)| *e == f)) {
+                    Some((_, from)) => closest_towards(&targets, *from, *direction),
+                    None => first_target(&targets),
+                };
+
+                let Some(next) = next else {
+                    continue;
+                };
+
+                if let Some(previous) = focus.0 {
+                    if is_tile(previous) {
+                        events.off_tile.send(OffTile(previous));
+                    } else {
+                        events.off_card.send(OffSpecialCard(previous));
+                    }
+                }
+
+                if is_tile(next) {
+                    events.over_tile.send(OverTile(next));
+                } else {
+                    events.over_card.send(OverSpecialCard(next));
+                }
+
+                focus.0 = Some(next);
+            }
+            NavigationInput::Confirm => {
+                if modal.is_open() {
+                    events.close_modal.send(CloseModal(ModalAnswer::Confirm));
+                } else if let Some(entity) = focus.0.filter(|f| targets.iter().any(|(e, _)| e == f)) {
+                    // The pick takes over the highlight, so there is nothing to undo afterwards.
+                    focus.0 = None;
+
+                    if is_tile(entity) {
+                        events.tile_selected.send(TileSelected(entity));
+                    } else {
+                        events.card_selected.send(SpecialCardSelected(entity));
+                    }
+                } else if confirm_button.iter().any(visible) {
+                    events.hand_confirmed.send(SpecialHandConfirmed);
+                }
+            }
+            NavigationInput::Cancel => {
+                // A tile or card shown larger is only put back by confirming it.
+                if modal.content().is_some_and(|content| content.subject().is_none()) {
+                    events.close_modal.send(CloseModal(ModalAnswer::Cancel));
+                } else if let Some(previous) = focus.0.take() {
+                    if is_tile(previous) {
+                        events.off_tile.send(OffTile(previous));
+                    } else {
+                        events.off_card.send(OffSpecialCard(previous));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Takes the focus off a tile or card that can no longer be picked, putting back its highlight.
+pub fn drop_lost_focus(
+    mut focus: ResMut<Focus>,
+    targets: NavigationTargets,
+    tiles: Query<(), With<Tile>>,
+    mut off_tile: EventWriter<OffTile>,
+    mut off_card: EventWriter<OffSpecialCard>,
+) {
+    let Some(focused) = focus.0 else {
+        return;
+    };
+
+    if targets.positions().iter().any(|(entity, _)| *entity == focused) {
+        return;
+    }
+
+    focus.0 = None;
+
+    if tiles.contains(focused) {
+        off_tile.send(OffTile(focused));
+    } else {
+        off_card.send(OffSpecialCard(focused));
+    }
+}
+
+/// The top-left target, where focus starts.
+fn first_target(targets: &[(Entity, Vec2)]) -> Option<Entity> {
+    targets
+        .iter()
+        .min_by(|(_, a), (_, b)| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)))
+        .map(|(entity, _)| *entity)
+}
+
+/// The nearest target lying roughly in `direction` from `from`.
+fn closest_towards(targets: &[(Entity, Vec2)], from: Vec2, direction: Direction) -> Option<Entity> {
+    let towards = direction.vector();
+
+    targets
+        .iter()
+        .filter_map(|(entity, position)| {
+            let offset = *position - from;
+            let along = offset.dot(towards);
+
+            if along <= 0.0 || along < offset.length() * MIN_ALIGNMENT {
+                return None;
+            }
+
+            let sideways = (offset - towards * along).length();
+            Some((*entity, along + sideways * SIDEWAYS_WEIGHT))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
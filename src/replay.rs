@@ -0,0 +1,320 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    modal::{CloseModal, Modal, ModalAnswer, ModalContent},
+    special_cards::{
+        OverSpecialCard, SpecialCardDiscarded, SpecialCardHand, SpecialCardInHand,
+        SpecialCardSelectable, SpecialCardSelected, SpecialCardSlot, SpecialCardUnplayable,
+        SpecialHandConfirmed,
+    },
+    tiles::{OverTile, Selectable, Tile, TileSelected},
+    tween::{AnimationGate, FastForward},
+};
+
+/// Frames given to each input of a queued action for its events to run through.
+const SETTLE_FRAMES: u32 = 5;
+/// Frames an action that cannot be played yet is retried for, e.g. while the board is still being set up.
+const RETRY_FRAMES: u32 = 30;
+
+/// Records what the player does and plays queued actions back through the same events pointer input sends.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionLog>()
+            .init_resource::<ActionQueue>()
+            .add_event::<TileSelected>()
+            .add_event::<CloseModal>()
+            .add_event::<SpecialCardSelected>()
+            .add_event::<SpecialHandConfirmed>()
+            .add_event::<InputHandled>()
+            .add_systems(PreUpdate, run_queued_actions)
+            .add_systems(Last, end_fast_forward.run_if(resource_exists::<FastForward>()))
+            .add_systems(Update, record_player_actions.run_if(on_event::<InputHandled>()));
+    }
+}
+
+/// Something the player does, in terms that stay the same for a seed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlayerAction {
+    SelectTile { tile: u32 },
+    /// Closes the reveal of a tile or special card.
+    CloseReveal,
+    /// Answers yes to the open question, such as whether to move to a tile.
+    Confirm,
+    Cancel,
+    /// Picks one of the answers to the open question.
+    Choose { choice: usize },
+    SelectSpecial { slot: usize },
+    ConfirmHand,
+}
+
+impl PlayerAction {
+    fn inputs(&self, world: &mut World) -> Result<Vec<Input>, String> {
+        match self {
+            PlayerAction::SelectTile { tile } => {
+                let entity = world
+                    .query::<(Entity, &Tile)>()
+                    .iter(world)
+                    .find(|(_, t)| t.number == *tile)
+                    .map(|(entity, _)| entity)
+                    .ok_or_else(|| format!("no tile {}", tile))?;
+
+                Ok(vec![Input::OverTile(entity), Input::SelectTile(entity)])
+            }
+            PlayerAction::CloseReveal => match modal_content(world) {
+                Some(content) if content.subject().is_some() => {
+                    Ok(vec![Input::Answer(ModalAnswer::Dismiss)])
+                }
+                _ => Err(String::from("no reveal is open")),
+            },
+            PlayerAction::Confirm | PlayerAction::Cancel => {
+                let answer = if *self == PlayerAction::Confirm {
+                    ModalAnswer::Confirm
+                } else {
+                    ModalAnswer::Cancel
+                };
+
+                match modal_content(world) {
+                    Some(ModalContent::Confirmation(_)) => Ok(vec![Input::Answer(answer)]),
+                    _ => Err(String::from("nothing to confirm")),
+                }
+            }
+            PlayerAction::Choose { choice } => match modal_content(world) {
+                Some(ModalContent::Choice { choices, .. }) if *choice < choices.len() => {
+                    Ok(vec![Input::Answer(ModalAnswer::Choose(*choice))])
+                }
+                _ => Err(format!("no choice {}", choice)),
+            },
+            PlayerAction::SelectSpecial { slot } => {
+                let entity = world
+                    .query::<(Entity, &SpecialCardSlot)>()
+                    .iter(world)
+                    .find(|(_, s)| s.0 == *slot)
+                    .map(|(entity, _)| entity)
+                    .ok_or_else(|| format!("no special card in slot {}", slot))?;
+
+                Ok(vec![Input::OverSpecial(entity), Input::SelectSpecial(entity)])
+            }
+            PlayerAction::ConfirmHand => Ok(vec![Input::ConfirmHand]),
+        }
+    }
+}
+
+/// One pointer event an action is made of.
+#[derive(Debug, Clone, Copy)]
+enum Input {
+    OverTile(Entity),
+    SelectTile(Entity),
+    Answer(ModalAnswer),
+    OverSpecial(Entity),
+    SelectSpecial(Entity),
+    ConfirmHand,
+}
+
+impl Input {
+    fn send(self, world: &mut World) {
+        match self {
+            Input::OverTile(entity) => world.send_event(OverTile(entity)),
+            Input::SelectTile(entity) => world.send_event(TileSelected(entity)),
+            Input::Answer(answer) => world.send_event(CloseModal(answer)),
+            Input::OverSpecial(entity) => world.send_event(OverSpecialCard(entity)),
+            Input::SelectSpecial(entity) => world.send_event(SpecialCardSelected(entity)),
+            Input::ConfirmHand => world.send_event(SpecialHandConfirmed),
+        };
+    }
+}
+
+/// Sent by the system that acts on a player's input, so inputs of every kind are logged in the order they were handled.
+/// Inputs the game ignores, such as picks while a modal is open, are never sent.
+#[derive(Event, Debug, Clone, Copy)]
+pub enum InputHandled {
+    /// A tile the player asked to move to. The game picks the first tile itself, which is not sent.
+    SelectTile(Entity),
+    SelectSpecial(Entity),
+    /// A modal closed, with whether it asked a question or only showed a tile or card.
+    Answer { question: bool, answer: ModalAnswer },
+    ConfirmHand,
+}
+
+/// Every action the player has taken this run, oldest first.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ActionLog(pub Vec<PlayerAction>);
+
+/// Actions waiting to be played, one input every few frames.
+#[derive(Resource, Default, Debug)]
+pub struct ActionQueue {
+    pub actions: VecDeque<PlayerAction>,
+    /// Why the last action that could not be played was skipped.
+    pub last_error: Option<String>,
+    inputs: VecDeque<Input>,
+    wait: u32,
+    retries: u32,
+}
+
+impl ActionQueue {
+    pub fn push(&mut self, action: PlayerAction) {
+        self.actions.push_back(action);
+    }
+
+    /// Whether everything queued has been played and has had time to settle.
+    pub fn is_idle(&self) -> bool {
+        self.actions.is_empty() && self.inputs.is_empty() && self.wait == 0
+    }
+}
+
+/// Lets animations play again once a loaded run has caught up and its last turn has played out.
+pub fn end_fast_forward(mut commands: Commands, queue: Res<ActionQueue>, gate: Res<AnimationGate>) {
+    if queue.is_idle() && gate.is_idle() {
+        commands.remove_resource::<FastForward>();
+    }
+}
+
+pub fn run_queued_actions(world: &mut World) {
+    // The turn plays out before the next input, as it would for a player watching it.
+    if world.get_resource::<AnimationGate>().is_some_and(|gate| !gate.is_idle()) {
+        return;
+    }
+
+    let mut queue = world.resource_mut::<ActionQueue>();
+
+    if queue.wait > 0 {
+        queue.wait -= 1;
+        return;
+    }
+
+    if queue.inputs.is_empty() {
+        let Some(action) = queue.actions.front().cloned() else {
+            return;
+        };
+
+        let inputs = action.inputs(world);
+        let mut queue = world.resource_mut::<ActionQueue>();
+
+        match inputs {
+            Ok(inputs) => {
+                queue.inputs = inputs.into();
+                queue.retries = 0;
+            }
+            Err(_) if queue.retries < RETRY_FRAMES => {
+                queue.retries += 1;
+                return;
+            }
+            Err(err) => {
+                warn!("Skipped {:?}: {}", action, err);
+                queue.last_error = Some(err);
+                queue.retries = 0;
+            }
+        }
+
+        queue.actions.pop_front();
+    }
+
+    let mut queue = world.resource_mut::<ActionQueue>();
+
+    if let Some(input) = queue.inputs.pop_front() {
+        queue.wait = SETTLE_FRAMES;
+        input.send(world);
+    }
+}
+
+pub fn record_player_actions(
+    mut events: EventReader<InputHandled>,
+    tiles: Query<&Tile>,
+    slots: Query<&SpecialCardSlot>,
+    mut log: ResMut<ActionLog>,
+) {
+    for ev in events.read() {
+        let action = match *ev {
+            InputHandled::SelectTile(entity) => tiles
+                .get(entity)
+                .map(|tile| PlayerAction::SelectTile { tile: tile.number })
+                .ok(),
+            InputHandled::SelectSpecial(entity) => slots
+                .get(entity)
+                .map(|slot| PlayerAction::SelectSpecial { slot: slot.0 })
+                .ok(),
+            InputHandled::Answer { question: false, .. } => Some(PlayerAction::CloseReveal),
+            InputHandled::Answer { answer, .. } => Some(match answer {
+                ModalAnswer::Confirm => PlayerAction::Confirm,
+                ModalAnswer::Choose(choice) => PlayerAction::Choose { choice },
+                ModalAnswer::Cancel | ModalAnswer::Dismiss => PlayerAction::Cancel,
+            }),
+            InputHandled::ConfirmHand => Some(PlayerAction::ConfirmHand),
+        };
+
+        log.0.extend(action);
+    }
+}
+
+/// What the player can do right now, the same way pointer input would find it.
+pub fn legal_actions(world: &mut World) -> Vec<PlayerAction> {
+    match modal_content(world) {
+        Some(ModalContent::Confirmation(_)) => {
+            return vec![PlayerAction::Confirm, PlayerAction::Cancel];
+        }
+        Some(ModalContent::Choice { choices, .. }) => {
+            return (0..choices.len())
+                .map(|choice| PlayerAction::Choose { choice })
+                .collect();
+        }
+        Some(_) => return vec![PlayerAction::CloseReveal],
+        None => {}
+    }
+
+    let (phase_open, picked, discarding) = world
+        .get_resource::<SpecialCardHand>()
+        .map_or((false, false, false), |hand| {
+            (hand.phase_open, hand.picked_this_turn, hand.discarding)
+        });
+
+    if !phase_open {
+        let mut tiles: Vec<u32> = world
+            .query_filtered::<&Tile, With<Selectable>>()
+            .iter(world)
+            .map(|tile| tile.number)
+            .collect();
+        tiles.sort();
+
+        return tiles
+            .into_iter()
+            .map(|tile| PlayerAction::SelectTile { tile })
+            .collect();
+    }
+
+    let mut slots: Vec<usize> = world
+        .query_filtered::<(&SpecialCardSlot, Has<SpecialCardInHand>, Has<SpecialCardUnplayable>), (
+            Or<(With<SpecialCardSelectable>, With<SpecialCardInHand>)>,
+            Without<SpecialCardDiscarded>,
+        )>()
+        .iter(world)
+        .filter(|(_, in_hand, unplayable)| {
+            if discarding {
+                *in_hand
+            } else {
+                !unplayable
+            }
+        })
+        .map(|(slot, _, _)| slot.0)
+        .collect();
+    slots.sort();
+
+    let mut actions: Vec<PlayerAction> = slots
+        .into_iter()
+        .map(|slot| PlayerAction::SelectSpecial { slot })
+        .collect();
+
+    if picked && !discarding {
+        actions.push(PlayerAction::ConfirmHand);
+    }
+
+    actions
+}
+
+fn modal_content(world: &World) -> Option<&ModalContent> {
+    world.get_resource::<Modal>().and_then(Modal::content)
+}
@@ -0,0 +1,430 @@
+//! The rules of a run as plain data, without Bevy, for simulations and agents that need many runs a second.
+//! Follows the same steps as the game's systems, from entering a tile to the cost check.
+
+use std::fmt;
+
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{BoardError, BoardGenerator, BoardLayout, TilePool},
+    effects::{EffectKind, EffectQueue, EffectSource, PendingEffect, TimingWindow},
+    game_state::RunSeed,
+    history::MovementRules,
+    ledger::{Cause, Ledger, Tally},
+    movement::{deal_deck, MovementCard, CARDS_TO_DRAW, DECK_RNG_STREAM},
+    special_cards::{
+        deal_special_cards, CardContext, CardType, SpecialCard, SpecialCardPool, DEAL_RNG_STREAM, HAND_LIMIT,
+        MUTATED_COST_MAX,
+    },
+    tiles::{Tile, TileType, MOVEMENT_POINTS_INIT_VALUE, TURNS_INIT_VALUE},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Choosing the next tile after meeting a cost.
+    Moving,
+    /// Picking and playing special cards before the hand is played.
+    SpecialCards,
+    Won,
+    Lost,
+}
+
+/// Where one of the special cards dealt on the board is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardPlace {
+    Board,
+    Hand,
+    /// A Naughty card picked this turn, resolved when the hand is played.
+    Pending,
+    Discarded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "action", content = "target", rename_all = "snake_case")]
+pub enum Action {
+    MoveTo(u32),
+    PickSpecial(usize),
+    PlaySpecial(usize),
+    /// Discards a card from a full hand.
+    DiscardSpecial(usize),
+    ConfirmHand,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RulesError {
+    Board(BoardError),
+    Illegal(Action),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::Board(err) => write!(f, "no board: {}", err),
+            RulesError::Illegal(action) => write!(f, "{:?} is not allowed now", action),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub seed: u64,
+    pub layout: BoardLayout,
+    pub movement_rules: MovementRules,
+    pub tiles: Vec<Tile>,
+    pub revealed: Vec<bool>,
+    pub visited: Vec<u32>,
+    pub current: u32,
+    /// Tiles that can be moved to while `phase` is `Moving`.
+    pub next_tiles: Vec<u32>,
+    pub movement_points: i32,
+    pub turns_left: i32,
+    /// Movement cards left to draw, top first.
+    pub deck: Vec<MovementCard>,
+    pub drawn: Vec<MovementCard>,
+    pub special_cards: Vec<(SpecialCard, CardPlace)>,
+    pub phase: Phase,
+    pub picked_this_turn: bool,
+    pub discarding: bool,
+    pub effects: EffectQueue,
+    pub ledger: Ledger,
+    rng: StdRng,
+}
+
+impl Run {
+    /// A run on `seed`, standing on the first tile with the first hand drawn.
+    pub fn new(seed: u64, layout: BoardLayout, movement_rules: MovementRules) -> Result<Run, RulesError> {
+        Run::with_pools(
+            seed,
+            layout,
+            movement_rules,
+            &TilePool::default(),
+            &SpecialCardPool::default(),
+        )
+    }
+
+    /// Like `new`, with the board and special cards drawn from these pools, as `--tiles` and `--cards` do for the game.
+    pub fn with_pools(
+        seed: u64,
+        layout: BoardLayout,
+        movement_rules: MovementRules,
+        tiles: &TilePool,
+        special_cards: &SpecialCardPool,
+    ) -> Result<Run, RulesError> {
+        let generator = BoardGenerator::with_pool(layout.clone(), tiles.clone());
+        // Played on the seed the board was built from, as the game does.
+        let (seed, tiles) = generator.generate_near(seed).map_err(RulesError::Board)?;
+        let run_seed = RunSeed(seed);
+
+        let mut run = Run {
+            seed,
+            revealed: vec![false; tiles.len()],
+            tiles,
+            layout,
+            movement_rules,
+            visited: vec![],
+            current: 0,
+            next_tiles: vec![],
+            movement_points: 0,
+            turns_left: 0,
+            deck: deal_deck(&mut run_seed.rng(DECK_RNG_STREAM)),
+            drawn: vec![],
+            special_cards: deal_special_cards(&special_cards.0, &mut run_seed.rng(DEAL_RNG_STREAM))
+                .into_iter()
+                .map(|card| (card, CardPlace::Board))
+                .collect(),
+            phase: Phase::Moving,
+            picked_this_turn: false,
+            discarding: false,
+            effects: EffectQueue::default(),
+            ledger: Ledger::default(),
+            rng: run_seed.rng(0),
+        };
+
+        run.change(Tally::MovementPoints, MOVEMENT_POINTS_INIT_VALUE, Cause::Start);
+        run.change(Tally::Turns, TURNS_INIT_VALUE, Cause::Start);
+        run.enter(0);
+
+        Ok(run)
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self.phase, Phase::Won | Phase::Lost)
+    }
+
+    pub fn current_tile(&self) -> &Tile {
+        &self.tiles[self.current as usize]
+    }
+
+    pub fn hand(&self) -> impl Iterator<Item = usize> + '_ {
+        self.cards_in(CardPlace::Hand)
+    }
+
+    pub fn cards_in(&self, place: CardPlace) -> impl Iterator<Item = usize> + '_ {
+        self.special_cards
+            .iter()
+            .enumerate()
+            .filter(move |(_, (_, p))| *p == place)
+            .map(|(slot, _)| slot)
+    }
+
+    pub fn context(&self) -> CardContext<'_> {
+        CardContext {
+            hand: &self.drawn,
+            deck_remaining: self.deck.len(),
+            current_tile: Some(self.current_tile()),
+            pending: self
+                .cards_in(CardPlace::Pending)
+                .next()
+                .map(|slot| &self.special_cards[slot].0),
+            movement_points: self.movement_points,
+            turns_left: self.turns_left,
+        }
+    }
+
+    /// Whether the card in `slot` can be picked from the board or played from the hand.
+    pub fn is_playable(&self, slot: usize) -> bool {
+        let Some((card, place)) = self.special_cards.get(slot) else {
+            return false;
+        };
+
+        card.can_be_picked(&self.context(), *place == CardPlace::Board)
+    }
+
+    pub fn legal_actions(&self) -> Vec<Action> {
+        match self.phase {
+            Phase::Moving => self.next_tiles.iter().map(|tile| Action::MoveTo(*tile)).collect(),
+            Phase::SpecialCards if self.discarding => {
+                self.hand().map(Action::DiscardSpecial).collect()
+            }
+            Phase::SpecialCards => {
+                let mut actions = vec![];
+
+                if !self.picked_this_turn {
+                    actions.extend(
+                        self.cards_in(CardPlace::Board)
+                            .filter(|slot| self.is_playable(*slot))
+                            .map(Action::PickSpecial),
+                    );
+                }
+
+                actions.extend(
+                    self.hand()
+                        .filter(|slot| self.is_playable(*slot))
+                        .map(Action::PlaySpecial),
+                );
+
+                if self.picked_this_turn {
+                    actions.push(Action::ConfirmHand);
+                }
+
+                actions
+            }
+            Phase::Won | Phase::Lost => vec![],
+        }
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<(), RulesError> {
+        if !self.legal_actions().contains(&action) {
+            return Err(RulesError::Illegal(action));
+        }
+
+        match action {
+            Action::MoveTo(tile) => self.enter(tile),
+            Action::PickSpecial(slot) => {
+                let hand_size = self.hand().count();
+                let forced = self.special_cards[slot].0.is_forced();
+
+                self.special_cards[slot].1 = if forced {
+                    CardPlace::Pending
+                } else {
+                    CardPlace::Hand
+                };
+                self.picked_this_turn = true;
+                self.discarding = !forced && hand_size >= HAND_LIMIT;
+            }
+            Action::PlaySpecial(slot) => self.play(slot),
+            Action::DiscardSpecial(slot) => {
+                self.special_cards[slot].1 = CardPlace::Discarded;
+                self.discarding = false;
+            }
+            Action::ConfirmHand => self.play_hand(),
+        }
+
+        Ok(())
+    }
+
+    fn change(&mut self, tally: Tally, amount: i32, cause: Cause) {
+        match tally {
+            Tally::MovementPoints => self.movement_points += amount,
+            Tally::Turns => self.turns_left += amount,
+        }
+
+        self.ledger.record(tally, amount, cause);
+    }
+
+    fn resolve(&mut self, window: TimingWindow) {
+        let mut changes = vec![];
+        self.effects
+            .resolve_with(window, |tally, amount, cause| changes.push((tally, amount, cause)));
+
+        for (tally, amount, cause) in changes {
+            self.change(tally, amount, cause);
+        }
+    }
+
+    fn enter(&mut self, number: u32) {
+        if let Some(from) = self.visited.last() {
+            let penalty = self
+                .movement_rules
+                .penalty(&self.layout, &self.visited, *from, number);
+
+            if penalty != 0 {
+                self.effects.push(PendingEffect::once(
+                    TimingWindow::OnReveal,
+                    EffectSource::Tile(number),
+                    EffectKind::MovementPoints(-penalty),
+                ));
+            }
+        }
+
+        let first_visit = !self.visited.contains(&number);
+
+        self.current = number;
+        self.revealed[number as usize] = true;
+        self.visited.push(number);
+
+        if first_visit {
+            for effect in self.current_tile().effects() {
+                self.effects.push(effect);
+            }
+        }
+
+        self.resolve(TimingWindow::OnReveal);
+        self.start_turn();
+    }
+
+    fn start_turn(&mut self) {
+        self.resolve(TimingWindow::StartOfTurn);
+        self.draw(CARDS_TO_DRAW as usize);
+
+        self.phase = Phase::SpecialCards;
+        // A board of only unplayable Naughty cards counts as empty, or there would be nothing to do.
+        let pickable = self.cards_in(CardPlace::Board).any(|slot| self.is_playable(slot));
+        self.picked_this_turn = !pickable;
+    }
+
+    fn draw(&mut self, count: usize) {
+        let count = count.min(self.deck.len());
+        self.drawn.extend(self.deck.drain(..count));
+    }
+
+    fn play(&mut self, slot: usize) {
+        self.special_cards[slot].1 = CardPlace::Discarded;
+        let card = self.special_cards[slot].0.clone();
+        let source = EffectSource::SpecialCard(card.name.clone());
+
+        match card.card_type {
+            CardType::DrawMovementCard => {
+                if card.value > 1 {
+                    self.drawn.clear();
+                }
+                self.draw(card.value as usize);
+            }
+            CardType::MovementPointsUpdate => self.effects.push(PendingEffect::once(
+                TimingWindow::BeforeCostCheck,
+                source,
+                EffectKind::MovementPoints(card.value),
+            )),
+            CardType::TurnUpdate => self.effects.push(PendingEffect::once(
+                TimingWindow::BeforeCostCheck,
+                source,
+                EffectKind::Turns(card.value),
+            )),
+            CardType::MovementPointsSubHighest
+            | CardType::MovementPointsMultiplyLeastCard
+            | CardType::MovementPointsReductionAllCards => {
+                let mut values: Vec<u32> = self.drawn.iter().map(|c| c.value).collect();
+                card.apply_to_hand(&mut values);
+
+                for (drawn, value) in self.drawn.iter_mut().zip(values) {
+                    drawn.value = value;
+                }
+            }
+            CardType::CurrentTileCostDirectChange | CardType::CurrentTileCostIndirectChange => {
+                let cost = self.current_tile().cost;
+                let cost = card
+                    .tile_cost_after(cost)
+                    .unwrap_or_else(|| self.rng.gen_range(0..=MUTATED_COST_MAX));
+                self.tiles[self.current as usize].cost = cost;
+            }
+            CardType::Erase => {
+                let tile = &mut self.tiles[self.current as usize];
+                tile.cost = card.tile_cost_after(tile.cost).unwrap_or(tile.cost);
+                tile.tile_type = TileType::Plain;
+
+                let source = EffectSource::Tile(tile.number);
+                self.effects.cancel_where(|effect| effect.source == source);
+            }
+            CardType::CancelNaughty => {
+                for (_, place) in &mut self.special_cards {
+                    if *place == CardPlace::Pending {
+                        *place = CardPlace::Discarded;
+                    }
+                }
+            }
+        }
+    }
+
+    fn play_hand(&mut self) {
+        let pending: Vec<usize> = self.cards_in(CardPlace::Pending).collect();
+
+        for slot in pending {
+            self.play(slot);
+        }
+
+        for card in std::mem::take(&mut self.drawn) {
+            self.change(
+                Tally::MovementPoints,
+                card.value as i32,
+                Cause::MovementCard(card.name),
+            );
+        }
+
+        self.resolve(TimingWindow::OnEnter);
+        self.resolve(TimingWindow::BeforeCostCheck);
+        self.end_turn();
+    }
+
+    fn end_turn(&mut self) {
+        let turns_left = self.turns_left;
+        self.change(Tally::Turns, -1, Cause::TurnTick);
+
+        if turns_left <= 0 {
+            self.phase = Phase::Lost;
+            return;
+        }
+
+        if self.current_tile().cost as i32 > self.movement_points {
+            self.start_turn();
+            return;
+        }
+
+        if self.current == self.layout.last_tile() {
+            self.phase = Phase::Won;
+            return;
+        }
+
+        self.next_tiles = self
+            .movement_rules
+            .next_tiles(&self.layout, &self.visited, self.current);
+        // Nowhere left to go, which the game leaves the player stuck on.
+        self.phase = if self.next_tiles.is_empty() {
+            Phase::Lost
+        } else {
+            Phase::Moving
+        };
+    }
+}
@@ -0,0 +1,679 @@
+use std::{fs, io, path::Path};
+
+use bevy::{log::Level, prelude::*, window::{PrimaryWindow, WindowMode}};
+use toml_edit::{value, Document, Item};
+
+use crate::{
+    bindings::{bindings_screen_closed, ActionMap, GameAction},
+    config::ConfigDirectory,
+    history::MovementRules,
+};
+
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// Window sizes the settings screen steps through.
+const WINDOW_SIZES: [(u32, u32); 4] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+pub(crate) const WINDOW_WIDTH: (i64, i64) = (640, 7680);
+pub(crate) const WINDOW_HEIGHT: (i64, i64) = (360, 4320);
+const UI_SCALE: (f64, f64) = (0.5, 3.0);
+const ANIMATION_SPEED: (f64, f64) = (0.25, 4.0);
+const VOLUME: (f64, f64) = (0.0, 1.0);
+
+/// Keeps the player's `Settings`, loaded from `settings.toml` in the config directory,
+/// applies them to the window and the colours, and offers a screen to change them.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<ConfigDirectory>() {
+            app.insert_resource(ConfigDirectory::from_env());
+        }
+
+        if !app.world.contains_resource::<Settings>() {
+            let path = app.world.resource::<ConfigDirectory>().file(SETTINGS_FILE);
+            let (settings, warnings) = Settings::load_or_default(path.as_deref());
+            app.insert_resource(settings).insert_resource(SettingsWarnings(warnings));
+        }
+
+        // A run set up with rules of its own, such as a replay, keeps them.
+        if !app.world.contains_resource::<MovementRules>() {
+            let rules = app.world.resource::<Settings>().difficulty.rules();
+            app.insert_resource(rules);
+        }
+
+        app.init_resource::<SettingsWarnings>()
+            .init_resource::<SettingsScreen>()
+            .init_resource::<ClearColor>()
+            .add_systems(Startup, (report_settings_warnings, setup_settings_screen))
+            .add_systems(
+                Update,
+                (
+                    settings_screen_input.run_if(bindings_screen_closed),
+                    apply_settings.run_if(resource_changed::<Settings>()),
+                    update_settings_screen.run_if(
+                        resource_changed::<SettingsScreen>().or_else(resource_changed::<Settings>()),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// A setting with a fixed set of values, written to the file by name.
+pub trait Choice: Copy + PartialEq + 'static {
+    const ALL: &'static [Self];
+
+    fn name(&self) -> &'static str;
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|choice| choice.name() == name)
+    }
+
+    /// The next value, wrapping around, or the previous one when `step` is negative.
+    fn cycle(&self, step: i32) -> Self {
+        let index = Self::ALL.iter().position(|choice| choice == self).unwrap_or(0) as i32;
+        let len = Self::ALL.len() as i32;
+        Self::ALL[(index + step).rem_euclid(len) as usize]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl Choice for DisplayMode {
+    const ALL: &'static [Self] = &[DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+
+    fn name(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "windowed",
+            DisplayMode::Borderless => "borderless",
+            DisplayMode::Fullscreen => "fullscreen",
+        }
+    }
+}
+
+impl DisplayMode {
+    pub fn window_mode(self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Theme {
+    #[default]
+    Savanna,
+    Night,
+    HighContrast,
+}
+
+impl Choice for Theme {
+    const ALL: &'static [Self] = &[Theme::Savanna, Theme::Night, Theme::HighContrast];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Theme::Savanna => "savanna",
+            Theme::Night => "night",
+            Theme::HighContrast => "high_contrast",
+        }
+    }
+}
+
+impl Theme {
+    pub fn background(self) -> Color {
+        match self {
+            Theme::Savanna => Color::rgb(0.4, 0.4, 0.4),
+            Theme::Night => Color::rgb(0.08, 0.09, 0.14),
+            Theme::HighContrast => Color::BLACK,
+        }
+    }
+}
+
+/// Rule presets for new runs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Difficulty {
+    /// Stepping back a column is allowed.
+    Easy,
+    #[default]
+    Normal,
+    /// Going over old ground costs a movement point.
+    Hard,
+}
+
+impl Choice for Difficulty {
+    const ALL: &'static [Self] = &[Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
+
+impl Difficulty {
+    pub fn rules(self) -> MovementRules {
+        match self {
+            Difficulty::Easy => MovementRules {
+                allow_backward: true,
+                ..MovementRules::default()
+            },
+            Difficulty::Normal => MovementRules::default(),
+            Difficulty::Hard => MovementRules {
+                revisit_penalty: 1,
+                ..MovementRules::default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Choice for LogLevel {
+    const ALL: &'static [Self] = &[
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl LogLevel {
+    pub fn level(self) -> Level {
+        match self {
+            LogLevel::Error => Level::ERROR,
+            LogLevel::Warn => Level::WARN,
+            LogLevel::Info => Level::INFO,
+            LogLevel::Debug => Level::DEBUG,
+            LogLevel::Trace => Level::TRACE,
+        }
+    }
+}
+
+/// What the player has chosen, kept between sessions.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub display_mode: DisplayMode,
+    pub window_width: u32,
+    pub window_height: u32,
+    /// On top of the scale the layout picks for the window size.
+    pub ui_scale: f64,
+    pub theme: Theme,
+    /// How fast animations play, 1 being their normal speed.
+    pub animation_speed: f64,
+    /// Puts everything where it is going at once instead.
+    pub skip_animations: bool,
+    pub master_volume: f64,
+    pub music_volume: f64,
+    pub effects_volume: f64,
+    /// Rules used from the next run on.
+    pub difficulty: Difficulty,
+    /// Read when the game starts.
+    pub log_level: LogLevel,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            display_mode: DisplayMode::Windowed,
+            window_width: 1280,
+            window_height: 720,
+            ui_scale: 1.0,
+            theme: Theme::Savanna,
+            animation_speed: 1.0,
+            skip_animations: false,
+            master_volume: 1.0,
+            music_volume: 0.8,
+            effects_volume: 0.8,
+            difficulty: Difficulty::Normal,
+            log_level: LogLevel::Info,
+        }
+    }
+}
+
+/// Problems found while loading the settings, logged once the game has started.
+#[derive(Resource, Default, Debug)]
+pub struct SettingsWarnings(pub Vec<String>);
+
+impl Settings {
+    /// Reads settings written by `to_toml`. Missing values keep their defaults; values that cannot be used
+    /// keep them too, with a warning for each.
+    pub fn from_toml(text: &str) -> (Settings, Vec<String>) {
+        let mut settings = Settings::default();
+        let mut warnings = vec![];
+
+        let doc = match text.parse::<Document>() {
+            Ok(doc) => doc,
+            Err(err) => {
+                warnings.push(format!("not a settings file, using the defaults: {}", err));
+                return (settings, warnings);
+            }
+        };
+
+        let mut read = Reader {
+            doc: &doc,
+            warnings: &mut warnings,
+        };
+
+        read.choice("window", "mode", &mut settings.display_mode);
+        read.integer("window", "width", WINDOW_WIDTH, &mut settings.window_width);
+        read.integer("window", "height", WINDOW_HEIGHT, &mut settings.window_height);
+        read.number("interface", "ui_scale", UI_SCALE, &mut settings.ui_scale);
+        read.choice("interface", "theme", &mut settings.theme);
+        read.number("interface", "animation_speed", ANIMATION_SPEED, &mut settings.animation_speed);
+        read.boolean("interface", "skip_animations", &mut settings.skip_animations);
+        read.number("audio", "master_volume", VOLUME, &mut settings.master_volume);
+        read.number("audio", "music_volume", VOLUME, &mut settings.music_volume);
+        read.number("audio", "effects_volume", VOLUME, &mut settings.effects_volume);
+        read.choice("game", "difficulty", &mut settings.difficulty);
+        read.choice("log", "level", &mut settings.log_level);
+
+        (settings, warnings)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut doc = Document::new();
+
+        doc["window"]["mode"] = value(self.display_mode.name());
+        doc["window"]["width"] = value(self.window_width as i64);
+        doc["window"]["height"] = value(self.window_height as i64);
+        doc["interface"]["ui_scale"] = value(self.ui_scale);
+        doc["interface"]["theme"] = value(self.theme.name());
+        doc["interface"]["animation_speed"] = value(self.animation_speed);
+        doc["interface"]["skip_animations"] = value(self.skip_animations);
+        doc["audio"]["master_volume"] = value(self.master_volume);
+        doc["audio"]["music_volume"] = value(self.music_volume);
+        doc["audio"]["effects_volume"] = value(self.effects_volume);
+        doc["game"]["difficulty"] = value(self.difficulty.name());
+        doc["log"]["level"] = value(self.log_level.name());
+
+        doc.to_string()
+    }
+
+    /// The saved settings, or the defaults when there are none, with what was wrong with the file.
+    pub fn load_or_default(path: Option<&Path>) -> (Settings, Vec<String>) {
+        let Some(path) = path.filter(|path| path.exists()) else {
+            return (Settings::default(), vec![]);
+        };
+
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                let (settings, warnings) = Settings::from_toml(&text);
+                let warnings = warnings
+                    .into_iter()
+                    .map(|warning| format!("{}: {}", path.display(), warning))
+                    .collect();
+
+                (settings, warnings)
+            }
+            Err(err) => (
+                Settings::default(),
+                vec![format!("could not read {}: {}", path.display(), err)],
+            ),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_toml())
+    }
+}
+
+/// Reads values out of a settings file, keeping the default for anything missing or unusable.
+struct Reader<'a> {
+    doc: &'a Document,
+    warnings: &'a mut Vec<String>,
+}
+
+impl Reader<'_> {
+    fn get(&self, table: &str, key: &str) -> Option<&Item> {
+        self.doc.get(table)?.get(key).filter(|item| !item.is_none())
+    }
+
+    fn warn(&mut self, table: &str, key: &str, problem: String) {
+        self.warnings
+            .push(format!("{}.{}: {}, using the default", table, key, problem));
+    }
+
+    fn choice<T: Choice>(&mut self, table: &str, key: &str, setting: &mut T) {
+        let Some(item) = self.get(table, key) else {
+            return;
+        };
+
+        match item.as_str().map(|name| (name, T::from_name(name))) {
+            Some((_, Some(choice))) => *setting = choice,
+            Some((name, None)) => {
+                let names: Vec<&str> = T::ALL.iter().map(|choice| choice.name()).collect();
+                self.warn(table, key, format!("{:?} is not one of {}", name, names.join(", ")));
+            }
+            None => self.warn(table, key, String::from("expected a name")),
+        }
+    }
+
+    fn number(&mut self, table: &str, key: &str, (min, max): (f64, f64), setting: &mut f64) {
+        let Some(item) = self.get(table, key) else {
+            return;
+        };
+
+        match item.as_float().or_else(|| item.as_integer().map(|i| i as f64)) {
+            Some(number) if (min..=max).contains(&number) => *setting = number,
+            Some(number) => self.warn(table, key, format!("{} is not between {} and {}", number, min, max)),
+            None => self.warn(table, key, String::from("expected a number")),
+        }
+    }
+
+    fn boolean(&mut self, table: &str, key: &str, setting: &mut bool) {
+        let Some(item) = self.get(table, key) else {
+            return;
+        };
+
+        match item.as_bool() {
+            Some(flag) => *setting = flag,
+            None => self.warn(table, key, String::from("expected true or false")),
+        }
+    }
+
+    fn integer(&mut self, table: &str, key: &str, (min, max): (i64, i64), setting: &mut u32) {
+        let Some(item) = self.get(table, key) else {
+            return;
+        };
+
+        match item.as_integer() {
+            Some(number) if (min..=max).contains(&number) => *setting = number as u32,
+            Some(number) => self.warn(table, key, format!("{} is not between {} and {}", number, min, max)),
+            None => self.warn(table, key, String::from("expected a whole number")),
+        }
+    }
+}
+
+pub fn report_settings_warnings(warnings: Res<SettingsWarnings>) {
+    for warning in &warnings.0 {
+        warn!("Settings: {}", warning);
+    }
+}
+
+/// Settings are applied whenever any of them changes, so the window is only touched when its own
+/// settings did. Otherwise turning the volume down would undo a window the player resized.
+pub fn apply_settings(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut clear_color: ResMut<ClearColor>,
+    mut applied: Local<Option<(DisplayMode, u32, u32)>>,
+) {
+    let window_settings = (settings.display_mode, settings.window_width, settings.window_height);
+
+    if *applied != Some(window_settings) {
+        for mut window in &mut windows {
+            window.mode = settings.display_mode.window_mode();
+            window
+                .resolution
+                .set(settings.window_width as f32, settings.window_height as f32);
+        }
+        *applied = Some(window_settings);
+    }
+
+    clear_color.0 = settings.theme.background();
+}
+
+/// One line of the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingsRow {
+    DisplayMode,
+    WindowSize,
+    UiScale,
+    Theme,
+    AnimationSpeed,
+    SkipAnimations,
+    MasterVolume,
+    MusicVolume,
+    EffectsVolume,
+    Difficulty,
+    LogLevel,
+    Reset,
+}
+
+impl SettingsRow {
+    pub const ALL: [SettingsRow; 12] = [
+        SettingsRow::DisplayMode,
+        SettingsRow::WindowSize,
+        SettingsRow::UiScale,
+        SettingsRow::Theme,
+        SettingsRow::AnimationSpeed,
+        SettingsRow::SkipAnimations,
+        SettingsRow::MasterVolume,
+        SettingsRow::MusicVolume,
+        SettingsRow::EffectsVolume,
+        SettingsRow::Difficulty,
+        SettingsRow::LogLevel,
+        SettingsRow::Reset,
+    ];
+
+    fn label(self, settings: &Settings) -> String {
+        let percent = |volume: f64| format!("{:.0}%", volume * 100.0);
+
+        match self {
+            SettingsRow::DisplayMode => format!("Window mode: {}", settings.display_mode.name()),
+            SettingsRow::WindowSize => {
+                format!("Window size: {}x{}", settings.window_width, settings.window_height)
+            }
+            SettingsRow::UiScale => format!("UI scale: {:.1}", settings.ui_scale),
+            SettingsRow::Theme => format!("Theme: {}", settings.theme.name()),
+            SettingsRow::AnimationSpeed => format!("Animation speed: {:.2}x", settings.animation_speed),
+            SettingsRow::SkipAnimations => {
+                format!("Skip animations: {}", if settings.skip_animations { "on" } else { "off" })
+            }
+            SettingsRow::MasterVolume => format!("Master volume: {}", percent(settings.master_volume)),
+            SettingsRow::MusicVolume => format!("Music volume: {}", percent(settings.music_volume)),
+            SettingsRow::EffectsVolume => format!("Effects volume: {}", percent(settings.effects_volume)),
+            SettingsRow::Difficulty => {
+                format!("Difficulty (on restart): {}", settings.difficulty.name())
+            }
+            SettingsRow::LogLevel => format!("Log level (on restart): {}", settings.log_level.name()),
+            SettingsRow::Reset => String::from("Reset to defaults"),
+        }
+    }
+
+    /// Changes the row's setting one step up or down.
+    fn change(self, settings: &mut Settings, step: i32) {
+        let nudge = |value: &mut f64, by: f64, (min, max): (f64, f64)| {
+            *value = ((*value + by * step as f64) * 100.0).round() / 100.0;
+            *value = value.clamp(min, max);
+        };
+
+        match self {
+            SettingsRow::DisplayMode => settings.display_mode = settings.display_mode.cycle(step),
+            SettingsRow::WindowSize => {
+                let current = (settings.window_width, settings.window_height);
+                let index = WINDOW_SIZES.iter().position(|size| *size == current);
+                let next = match index {
+                    Some(index) => (index as i32 + step).rem_euclid(WINDOW_SIZES.len() as i32) as usize,
+                    None => 0,
+                };
+
+                (settings.window_width, settings.window_height) = WINDOW_SIZES[next];
+            }
+            SettingsRow::UiScale => nudge(&mut settings.ui_scale, 0.1, UI_SCALE),
+            SettingsRow::Theme => settings.theme = settings.theme.cycle(step),
+            SettingsRow::AnimationSpeed => nudge(&mut settings.animation_speed, 0.25, ANIMATION_SPEED),
+            SettingsRow::SkipAnimations => settings.skip_animations = !settings.skip_animations,
+            SettingsRow::MasterVolume => nudge(&mut settings.master_volume, 0.1, VOLUME),
+            SettingsRow::MusicVolume => nudge(&mut settings.music_volume, 0.1, VOLUME),
+            SettingsRow::EffectsVolume => nudge(&mut settings.effects_volume, 0.1, VOLUME),
+            SettingsRow::Difficulty => settings.difficulty = settings.difficulty.cycle(step),
+            SettingsRow::LogLevel => settings.log_level = settings.log_level.cycle(step),
+            SettingsRow::Reset => {}
+        }
+    }
+}
+
+/// The settings screen, opened with the `Settings` key.
+#[derive(Resource, Default, Debug)]
+pub struct SettingsScreen {
+    pub open: bool,
+    pub selected: usize,
+    pub message: Option<String>,
+}
+
+/// Whether the settings screen is closed, so the keys it uses mean what they do in the game.
+pub fn settings_screen_closed(screen: Option<Res<SettingsScreen>>) -> bool {
+    !screen.is_some_and(|screen| screen.open)
+}
+
+#[derive(Component)]
+pub struct SettingsScreenText;
+
+pub fn setup_settings_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    left: Val::Percent(25.0),
+                    top: Val::Percent(15.0),
+                    width: Val::Percent(50.0),
+                    height: Val::Percent(70.0),
+                    padding: UiRect::all(Val::Px(16.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.9).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            SettingsScreenText,
+        ))
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                ..default()
+            });
+        });
+}
+
+pub fn settings_screen_input(
+    keys: Res<Input<KeyCode>>,
+    map: Res<ActionMap>,
+    mut screen: ResMut<SettingsScreen>,
+    mut settings: ResMut<Settings>,
+    config: Res<ConfigDirectory>,
+) {
+    if !screen.open {
+        if map.just_pressed(GameAction::Settings, &keys) {
+            *screen = SettingsScreen {
+                open: true,
+                ..default()
+            };
+        }
+
+        return;
+    }
+
+    let rows = SettingsRow::ALL.len();
+    let row = SettingsRow::ALL[screen.selected];
+    let step = if map.just_pressed(GameAction::Right, &keys) {
+        1
+    } else if map.just_pressed(GameAction::Left, &keys) {
+        -1
+    } else {
+        0
+    };
+
+    if map.just_pressed(GameAction::Up, &keys) {
+        screen.selected = (screen.selected + rows - 1) % rows;
+    } else if map.just_pressed(GameAction::Down, &keys) {
+        screen.selected = (screen.selected + 1) % rows;
+    } else if step != 0 && row != SettingsRow::Reset {
+        row.change(&mut settings, step);
+        screen.message = Some(save_settings(&settings, &config));
+    } else if map.just_pressed(GameAction::Confirm, &keys) && row == SettingsRow::Reset {
+        *settings = Settings::default();
+        screen.message = Some(save_settings(&settings, &config));
+    } else if map.just_pressed(GameAction::Cancel, &keys)
+        || map.just_pressed(GameAction::Settings, &keys)
+    {
+        screen.open = false;
+    }
+}
+
+/// Saves the settings, returning what to tell the player.
+fn save_settings(settings: &Settings, config: &ConfigDirectory) -> String {
+    let Some(path) = config.file(SETTINGS_FILE) else {
+        return String::from("Settings changed for this session");
+    };
+
+    match settings.save(&path) {
+        Ok(()) => String::from("Settings saved"),
+        Err(err) => {
+            warn!("Could not save settings to {}: {}", path.display(), err);
+            format!("Could not save settings: {}", err)
+        }
+    }
+}
+
+pub fn update_settings_screen(
+    screen: Res<SettingsScreen>,
+    settings: Res<Settings>,
+    mut panel: Query<(&mut Visibility, &Children), With<SettingsScreenText>>,
+    mut texts: Query<&mut Text>,
+) {
+    let mut lines = vec![String::from("Settings"), String::new()];
+
+    for (index, row) in SettingsRow::ALL.iter().enumerate() {
+        let cursor = if index == screen.selected { ">" } else { " " };
+        lines.push(format!("{} {}", cursor, row.label(&settings)));
+    }
+
+    lines.push(String::new());
+    lines.push(screen.message.clone().unwrap_or_default());
+
+    for (mut vis, children) in &mut panel {
+        *vis = if screen.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = lines.join("\n");
+            }
+        }
+    }
+}
@@ -1,25 +1,34 @@
+use std::{fs, io, path::Path};
+
 use bevy::{
+    ecs::system::SystemParam,
     prelude::*,
     sprite::Anchor,
     text::{BreakLineOn, Text2dBounds, TextLayoutInfo},
 };
 use bevy_mod_picking::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    effects::{EffectKind, EffectQueue, EffectSource, PendingEffect, TimingWindow},
+    event_log::GameplayEvent,
+    game_state::{RunRng, RunSeed},
+    layout::{LayoutRoot, Zone},
+    modal::{open_modal, Modal, ModalClosed, ModalContent, OpenModal},
     movement::{
-        CardPointsText, DrawCardEvent, MovementCard, MovementCardDiscarded, MovementCardDrawn,
-        MovementCardsDrawnEvent, MovementPointsUpdateEvent,
+        on_special_card_closed_event, CardPointsText, DrawCardEvent, MovementCard,
+        MovementCardDiscarded, MovementCardDrawn, MovementCardsDrawnEvent, MovementPoints,
     },
-    tiles::{Tile, TileClosedEvent, TileCostText, TileDescriptionText, TileType},
-    turns::TurnsUpdateEvent,
-    ui::MovementPointsText,
+    replay::InputHandled,
+    tiles::{Focused, Tile, TileClosedEvent, TileCostText, TileDescriptionText, TileType},
+    turns::TurnsLeft,
+    tween::{lean, AnimationGate, Ease, Flip, Tween},
+    ui::{MovementPointsText, SpecialCardPreviewText, SpecialHandPromptText},
 };
 
 const CARDS_TO_DRAW: u32 = 8;
 const FOCUS_SCALE: f32 = 0.1;
-const SELECTED_SCALE: f32 = 1.4;
-const BLOCKER_COLOR_VALUE: f32 = 0.1;
 
 const X_START: f32 = -1700.0;
 const X_STEP: f32 = 300.0;
@@ -31,14 +40,86 @@ const X_FINAL: f32 = -1300.0;
 const Y_FINAL: f32 = -800.0;
 const FINAL_SCALE: f32 = 1.3;
 
-#[derive(Component)]
-pub struct SpecialCardRevealBlocker;
+pub const HAND_LIMIT: usize = 3;
+const HAND_X_START: f32 = -900.0;
+const HAND_X_STEP: f32 = 350.0;
+const HAND_Y: f32 = -800.0;
+const HAND_SCALE: f32 = 1.0;
+const PENDING_X: f32 = -1700.0;
+/// How long a card takes to move into the hand or onto the discard pile, in seconds.
+const HAND_SECONDS: f32 = 0.3;
+const DISCARD_SECONDS: f32 = 0.4;
+/// The cards on the board, the hand, the waiting card and the discard pile, in the units of the positions above.
+const DESIGN: Rect = Rect {
+    min: Vec2::new(-1850.0, -1100.0),
+    max: Vec2::new(-50.0, 550.0),
+};
 
-#[derive(Component)]
-pub struct SpecialCardRevealBlockerCloseButton;
+pub(crate) const MUTATED_COST_MAX: u32 = 10;
+const BOARD_COLUMNS: usize = 4;
+const BOARD_ROWS: usize = 2;
+pub(crate) const DEAL_RNG_STREAM: u64 = 2;
+const UNPLAYABLE_TINT: f32 = 0.4;
+
+/// The special cards dealt on the board and the hand they are picked into.
+/// Needs [`TweenPlugin`](crate::tween::TweenPlugin), [`ModalPlugin`](crate::modal::ModalPlugin)
+/// and [`MovementPlugin`](crate::movement::MovementPlugin); the HUD and the event log are optional.
+pub struct SpecialCardsPlugin;
+
+impl Plugin for SpecialCardsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpecialCardHand>()
+            .init_resource::<SpecialCardPool>()
+            .init_resource::<RunRng>()
+            .add_event::<SpecialCardSelected>()
+            .add_event::<GameplayEvent>()
+            .add_event::<OverSpecialCard>()
+            .add_event::<OffSpecialCard>()
+            .add_event::<SpecialCardClosed>()
+            .add_event::<SpecialCardPlayedEvent>()
+            .add_event::<SpecialHandConfirmed>()
+            .add_event::<SpecialCardDiscardEvent>()
+            .add_event::<MovementCardsDrawnEvent>()
+            .add_event::<InputHandled>()
+            .add_systems(Startup, setup_special_cards)
+            .add_systems(
+                Update,
+                (
+                    on_movement_cards_drawn.run_if(on_event::<MovementCardsDrawnEvent>()),
+                    on_over_special_card.run_if(on_event::<OverSpecialCard>()),
+                    on_off_special_card.run_if(on_event::<OffSpecialCard>()),
+                    on_special_card_selected
+                        .run_if(on_event::<SpecialCardSelected>())
+                        .before(open_modal),
+                    selected_special_card_close.run_if(on_event::<ModalClosed>()),
+                    refresh_special_card_playability,
+                    on_special_hand_confirmed.run_if(on_event::<SpecialHandConfirmed>()),
+                    on_special_card_played
+                        .run_if(
+                            on_event::<SpecialCardPlayedEvent>()
+                                .or_else(on_event::<SpecialCardDiscardEvent>()),
+                        )
+                        .after(on_special_hand_confirmed)
+                        .before(on_special_card_closed_event),
+                    // Before, so a card leaving the hand goes to the discard pile rather than back to its slot.
+                    arrange_special_card_hand.before(on_special_card_played),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        crate::require::<AnimationGate>(app, "SpecialCardsPlugin", "TweenPlugin");
+        crate::require::<Modal>(app, "SpecialCardsPlugin", "ModalPlugin");
+        crate::require::<MovementPoints>(app, "SpecialCardsPlugin", "MovementPlugin");
+    }
+}
+
+#[derive(Event)]
+pub struct SpecialCardPlayedEvent(pub Entity);
 
+/// Sent when a card leaves the hand without resolving.
 #[derive(Event)]
-pub struct SpecialCardPlayedEvent;
+pub struct SpecialCardDiscardEvent(pub Entity);
 
 #[derive(Component)]
 pub struct SpecialCardCover;
@@ -49,36 +130,264 @@ pub struct SpecialCardDiscarded;
 #[derive(Component)]
 pub struct SpecialCardSelectable;
 
+/// Where a special card was dealt on the board, counting from 0. Stays the same for a seed.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct SpecialCardSlot(pub usize);
+
 #[derive(Event)]
 pub struct SpecialCardClosed;
 
 #[derive(Component)]
 pub struct SpecialCardHighlight(pub Entity);
 
-#[derive(Component, Debug, Clone, Default)]
+#[derive(Component)]
+pub struct SpecialCardUnplayable;
+
+#[derive(Component)]
+pub struct SpecialCardInHand;
+
+/// A Naughty card that has been picked and resolves when the hand is played, unless countered.
+#[derive(Component)]
+pub struct SpecialCardPending;
+
+#[derive(Component)]
+pub struct SpecialHandConfirmButton;
+
+/// Tracks the special-card part of a turn: one pick from the board, then any cards from the hand.
+#[derive(Resource)]
+pub struct SpecialCardHand {
+    pub limit: usize,
+    pub phase_open: bool,
+    pub picked_this_turn: bool,
+    pub discarding: bool,
+}
+
+impl Default for SpecialCardHand {
+    fn default() -> Self {
+        SpecialCardHand {
+            limit: HAND_LIMIT,
+            phase_open: false,
+            picked_this_turn: false,
+            discarding: false,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SpecialCard {
     pub name: String,
+    #[serde(default)]
     pub tag: String,
     pub description: String,
     pub value: i32,
     pub card_type: CardType,
 }
 
-pub fn setup_special_cards(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// What a special card acts on, used to decide whether it can be played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardTarget {
+    Hand,
+    Deck,
+    CurrentTile,
+    Player,
+    PendingCard,
+}
+
+/// Snapshot of the run state a special card is checked and previewed against.
+pub struct CardContext<'a> {
+    pub hand: &'a [MovementCard],
+    pub deck_remaining: usize,
+    pub current_tile: Option<&'a Tile>,
+    pub pending: Option<&'a SpecialCard>,
+    pub movement_points: i32,
+    pub turns_left: i32,
+}
+
+type DrawnFilter = (With<MovementCardDrawn>, Without<MovementCardDiscarded>);
+type DeckFilter = (Without<MovementCardDrawn>, Without<MovementCardDiscarded>);
+type FocusedCardFilter = (With<SpecialCard>, With<Focused>);
+
+/// The run state a `CardContext` is taken from.
+#[derive(SystemParam)]
+pub struct CardSituation<'w, 's> {
+    drawn: Query<'w, 's, &'static MovementCard, DrawnFilter>,
+    deck: Query<'w, 's, &'static MovementCard, DeckFilter>,
+    tiles: Query<'w, 's, &'static Tile>,
+    pending: Query<'w, 's, &'static SpecialCard, With<SpecialCardPending>>,
+    movement_points: Res<'w, MovementPoints>,
+    turns_left: Res<'w, TurnsLeft>,
+}
+
+impl CardSituation<'_, '_> {
+    /// Calls `f` with the run as special cards see it right now.
+    pub fn with_context<R>(&self, f: impl FnOnce(&CardContext) -> R) -> R {
+        let hand: Vec<MovementCard> = self.drawn.iter().cloned().collect();
+
+        f(&CardContext {
+            hand: &hand,
+            deck_remaining: self.deck.iter().len(),
+            current_tile: self.tiles.iter().find(|t| t.current),
+            pending: self.pending.iter().next(),
+            movement_points: self.movement_points.0,
+            turns_left: self.turns_left.0,
+        })
+    }
+}
+
+impl CardType {
+    pub fn target(&self) -> CardTarget {
+        match self {
+            CardType::DrawMovementCard => CardTarget::Deck,
+            CardType::MovementPointsUpdate | CardType::TurnUpdate => CardTarget::Player,
+            CardType::MovementPointsSubHighest
+            | CardType::MovementPointsMultiplyLeastCard
+            | CardType::MovementPointsReductionAllCards => CardTarget::Hand,
+            CardType::CurrentTileCostDirectChange
+            | CardType::CurrentTileCostIndirectChange
+            | CardType::Erase => CardTarget::CurrentTile,
+            CardType::CancelNaughty => CardTarget::PendingCard,
+        }
+    }
+}
+
+impl SpecialCard {
+    pub fn is_playable(&self, context: &CardContext) -> bool {
+        match self.card_type.target() {
+            CardTarget::Hand => !context.hand.is_empty(),
+            CardTarget::Deck => context.deck_remaining > 0,
+            CardTarget::CurrentTile => context.current_tile.is_some(),
+            CardTarget::Player => true,
+            CardTarget::PendingCard => context.pending.is_some(),
+        }
+    }
+
+    /// Naughty cards resolve on their own; everything else can be kept in the hand.
+    pub fn is_forced(&self) -> bool {
+        self.tag == "<Naughty>"
+    }
+
+    /// Whether the card can be picked where it is. Picking one from the board only plays it
+    /// straight away when it is forced, so anything else there can be picked into the hand.
+    pub fn can_be_picked(&self, context: &CardContext, on_board: bool) -> bool {
+        self.is_playable(context) || (on_board && !self.is_forced())
+    }
+
+    /// Applies a hand-targeting effect to the drawn card values, in the same order as the hand.
+    pub fn apply_to_hand(&self, values: &mut [u32]) {
+        match self.card_type {
+            CardType::MovementPointsSubHighest => {
+                if let Some(highest) = values.iter_mut().max_by_key(|v| **v) {
+                    *highest = highest.saturating_sub(self.value as u32);
+                }
+            }
+            CardType::MovementPointsMultiplyLeastCard => {
+                if let Some(least) = values.iter_mut().min_by_key(|v| **v) {
+                    *least *= self.value as u32;
+                }
+            }
+            CardType::MovementPointsReductionAllCards => {
+                for v in values.iter_mut() {
+                    *v = self.value as u32;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// New cost of the current tile, `None` when the card mutates it randomly.
+    pub fn tile_cost_after(&self, cost: u32) -> Option<u32> {
+        match self.card_type {
+            CardType::CurrentTileCostDirectChange | CardType::Erase if self.value < 0 => None,
+            CardType::CurrentTileCostDirectChange | CardType::Erase => Some(self.value as u32),
+            CardType::CurrentTileCostIndirectChange => {
+                Some(cost.saturating_add_signed(self.value))
+            }
+            _ => Some(cost),
+        }
+    }
+
+    pub fn preview(&self, context: &CardContext) -> String {
+        if !self.is_playable(context) {
+            return format!("{}: nothing to target", self.name);
+        }
+
+        match self.card_type.target() {
+            CardTarget::Hand => {
+                let mut values: Vec<u32> = context.hand.iter().map(|c| c.value).collect();
+                self.apply_to_hand(&mut values);
+
+                let changes: Vec<String> = context
+                    .hand
+                    .iter()
+                    .zip(values)
+                    .filter(|(card, value)| card.value != *value)
+                    .map(|(card, value)| format!("{} +{} \u{2192} +{}", card.name, card.value, value))
+                    .collect();
+
+                if changes.is_empty() {
+                    String::from("No change to your hand")
+                } else {
+                    changes.join(", ")
+                }
+            }
+            CardTarget::Deck => {
+                if self.value > 1 {
+                    format!(
+                        "Discard {} cards, draw {}",
+                        context.hand.len(),
+                        (self.value as usize).min(context.deck_remaining)
+                    )
+                } else {
+                    String::from("Draw 1 movement card")
+                }
+            }
+            CardTarget::CurrentTile => {
+                let tile = context.current_tile.unwrap();
+                let cost = match self.tile_cost_after(tile.cost) {
+                    Some(cost) => format!("Tile cost {} \u{2192} {}", tile.cost, cost),
+                    None => format!("Tile cost {} \u{2192} ?", tile.cost),
+                };
+
+                if let CardType::Erase = self.card_type {
+                    format!("{}, tile effect erased", cost)
+                } else {
+                    cost
+                }
+            }
+            CardTarget::Player => match self.card_type {
+                CardType::TurnUpdate => format!(
+                    "Turns {} \u{2192} {}",
+                    context.turns_left,
+                    context.turns_left + self.value
+                ),
+                _ => format!(
+                    "Points {} \u{2192} {}",
+                    context.movement_points,
+                    context.movement_points + self.value
+                ),
+            },
+            CardTarget::PendingCard => format!("Cancel {}", context.pending.unwrap().name),
+        }
+    }
+}
+
+pub fn setup_special_cards(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    seed: Res<RunSeed>,
+    pool: Res<SpecialCardPool>,
+) {
     let len = 300.0;
     let height = 450.0;
     let sprite_size = Some(Vec2::new(len, height));
-    let mut card_res = generate_cards();
+    let dealt = deal_special_cards(&pool.0, &mut seed.rng(DEAL_RNG_STREAM));
 
     commands
         .spawn((
-            SpatialBundle {
-                transform: Transform::from_scale(Vec3 {
-                    x: 0.3,
-                    y: 0.3,
-                    z: 1.0,
-                }),
-                ..default()
+            SpatialBundle::default(),
+            LayoutRoot {
+                zone: Zone::SpecialCards,
+                design: DESIGN,
             },
             PickableBundle::default(),
             On::<Pointer<Down>>::send_event::<SpecialCardSelected>(),
@@ -87,18 +396,12 @@ pub fn setup_special_cards(mut commands: Commands, asset_server: Res<AssetServer
         ))
         .with_children(|commands| {
             let mut counter = 0;
-            let mut card_res_index: usize = 30;
 
-            for x in 0..4 {
-                for y in 0..2 {
-                    if card_res.len() == 0 {
+            for x in 0..BOARD_COLUMNS {
+                for y in 0..BOARD_ROWS {
+                    let Some(card) = dealt.get(counter) else {
                         continue;
-                    }
-
-                    let mut rng = rand::thread_rng();
-
-                    card_res_index = rng.gen_range(0..card_res.len());
-                    let card = &mut card_res[card_res_index];
+                    };
 
                     commands
                         .spawn((
@@ -117,6 +420,7 @@ pub fn setup_special_cards(mut commands: Commands, asset_server: Res<AssetServer
                                 ..default()
                             },
                             card.clone(),
+                            SpecialCardSlot(counter),
                         ))
                         .with_children(|parent: &mut ChildBuilder<'_, '_, '_>| {
                             parent.spawn(Text2dBundle {
@@ -221,69 +525,36 @@ pub fn setup_special_cards(mut commands: Commands, asset_server: Res<AssetServer
 
                     counter += 1;
                 }
-
-                if card_res_index < card_res.len() {
-                    card_res.remove(card_res_index);
-                }
             }
         });
 
-    commands
-        .spawn((
-            SpatialBundle {
-                transform: Transform::from_scale(Vec3::splat(100.0)),
-                visibility: Visibility::Hidden,
-                ..Default::default()
-            },
-            SpecialCardRevealBlocker,
-            PickableBundle::default(),
-        ))
-        .with_children(|commands| {
-            commands.spawn((SpriteBundle {
-                sprite: Sprite {
-                    custom_size: sprite_size,
-                    color: Color::rgba(
-                        BLOCKER_COLOR_VALUE,
-                        BLOCKER_COLOR_VALUE,
-                        BLOCKER_COLOR_VALUE,
-                        0.2,
-                    ),
-                    ..default()
-                },
-                // texture: asset_server.load("images/boovy.png"),
-                ..default()
-            },));
-        });
-
     commands
         .spawn((
             ButtonBundle {
                 style: Style {
-                    width: Val::Percent(10.0),
+                    width: Val::Percent(15.0),
                     height: Val::Px(42.0),
-                    margin: UiRect::top(Val::Percent(2.0)),
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
-                    left: Val::Percent(85.0),
-                    top: Val::Percent(10.0),
+                    left: Val::Percent(42.5),
+                    top: Val::Percent(88.0),
                     ..default()
                 },
-                background_color: BackgroundColor(Color::RED),
-                transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                background_color: BackgroundColor(Color::SEA_GREEN),
                 visibility: Visibility::Hidden,
                 ..default()
             },
-            On::<Pointer<Click>>::send_event::<SpecialCardSelectedBlockerClose>(),
+            On::<Pointer<Click>>::send_event::<SpecialHandConfirmed>(),
             NoDeselect,
-            SpecialCardRevealBlockerCloseButton,
+            SpecialHandConfirmButton,
         ))
         .with_children(|parent| {
             parent.spawn((
                 TextBundle {
                     text: Text::from_section(
-                        "X",
+                        "Play hand",
                         TextStyle {
-                            font_size: 40.0,
+                            font_size: 32.0,
                             color: Color::rgb(0.9, 0.9, 0.9),
                             ..default()
                         },
@@ -296,7 +567,7 @@ pub fn setup_special_cards(mut commands: Commands, asset_server: Res<AssetServer
 }
 
 #[derive(Event)]
-pub struct SpecialCardSelected(Entity);
+pub struct SpecialCardSelected(pub Entity);
 
 impl From<ListenerInput<Pointer<Down>>> for SpecialCardSelected {
     fn from(event: ListenerInput<Pointer<Down>>) -> Self {
@@ -307,61 +578,60 @@ impl From<ListenerInput<Pointer<Down>>> for SpecialCardSelected {
 pub fn on_special_card_selected(
     mut commands: Commands,
     mut events: EventReader<SpecialCardSelected>,
-    mut cards: Query<(Entity, &mut Transform, &mut SpecialCard, &Children), Without<SpecialCardDiscarded>>,
-    mut card_cover_query: Query<
-        &mut Visibility,
-        (
-            With<SpecialCardCover>,
-            Without<SpecialCardRevealBlocker>,
-            Without<SpecialCardRevealBlockerCloseButton>,
-        ),
-    >,
-    mut blocker: Query<&mut Visibility, With<SpecialCardRevealBlocker>>,
-    mut close_button: Query<(
-        &mut Visibility,
-        &SpecialCardRevealBlockerCloseButton,
-        Without<SpecialCardRevealBlocker>,
-    )>,
-    mut highlightables: Query<
-        (&mut Visibility, &mut SpecialCardHighlight),
-        (
-            Without<SpecialCardRevealBlocker>,
-            Without<SpecialCardRevealBlockerCloseButton>,
-            Without<SpecialCardCover>,
-        ),
+    mut hand: ResMut<SpecialCardHand>,
+    modal: Res<Modal>,
+    mut open_modal: EventWriter<OpenModal>,
+    cards: Query<
+        (Entity, &Children, Has<SpecialCardInHand>, Has<Focused>),
+        (Without<SpecialCardDiscarded>, Without<SpecialCardUnplayable>),
     >,
+    hand_cards: Query<Entity, With<SpecialCardInHand>>,
+    card_cover_query: Query<&Visibility, With<SpecialCardCover>>,
+    mut highlightables: Query<(&mut Visibility, &SpecialCardHighlight), Without<SpecialCardCover>>,
+    mut special_card_discard: EventWriter<SpecialCardDiscardEvent>,
+    mut handled: EventWriter<InputHandled>,
 ) {
-    for (entity, mut transform, mut tile, mut children) in &mut cards {
-        if transform.scale.x > 1.0 {
-            if transform.scale.x < FOCUS_SCALE + SELECTED_SCALE {
-                transform.scale.x += SELECTED_SCALE;
-                transform.scale.y += SELECTED_SCALE;
-                transform.translation.z = 1.0;
-
-                let mut blocker = blocker.single_mut();
-                *blocker = Visibility::Visible;
-
-                let mut close_button = close_button.single_mut();
-                *close_button.0 = Visibility::Visible;
-
-                for (mut vis, mut highlight) in &mut highlightables {
-                    if highlight.0 == entity {
-                        *vis = Visibility::Hidden;
-                    }
-                }
+    if modal.is_open() {
+        return;
+    }
 
-                for child in children {
-                    if let Ok(mut vis) = card_cover_query.get_mut(*child) {
-                        *vis = Visibility::Hidden;
-                    }
-                }
+    for ev in events.read() {
+        if hand.discarding && hand_cards.contains(ev.0) {
+            handled.send(InputHandled::SelectSpecial(ev.0));
+            special_card_discard.send(SpecialCardDiscardEvent(ev.0));
+            hand.discarding = false;
+            continue;
+        }
+
+        let Ok((entity, children, in_hand, focused)) = cards.get(ev.0) else {
+            continue;
+        };
+
+        if (in_hand && !hand.phase_open) || !focused {
+            continue;
+        }
+
+        handled.send(InputHandled::SelectSpecial(entity));
+        open_modal.send(OpenModal(ModalContent::CardDetails(entity)));
+
+        for (mut vis, highlight) in &mut highlightables {
+            if highlight.0 == entity {
+                *vis = Visibility::Hidden;
             }
         }
+
+        for child in children {
+            if card_cover_query.get(*child).is_ok_and(|vis| *vis != Visibility::Hidden) {
+                commands.entity(entity).insert(Flip::reveal(*child));
+            }
+        }
+
+        break;
     }
 }
 
 #[derive(Event)]
-pub struct OverSpecialCard(Entity);
+pub struct OverSpecialCard(pub Entity);
 
 impl From<ListenerInput<Pointer<Over>>> for OverSpecialCard {
     fn from(event: ListenerInput<Pointer<Over>>) -> Self {
@@ -372,22 +642,48 @@ impl From<ListenerInput<Pointer<Over>>> for OverSpecialCard {
 pub fn on_over_special_card(
     mut commands: Commands,
     mut events: EventReader<OverSpecialCard>,
-    mut tiles: Query<(Entity, &mut Transform), With<SpecialCardSelectable>>,
+    modal: Res<Modal>,
+    mut tiles: Query<
+        (Entity, &mut Transform, &SpecialCard, Has<Focused>),
+        (
+            Or<(With<SpecialCardSelectable>, With<SpecialCardInHand>)>,
+            Without<SpecialCardUnplayable>,
+        ),
+    >,
+    situation: CardSituation,
+    mut preview: Query<(&mut Visibility, &Children), With<SpecialCardPreviewText>>,
+    mut preview_texts: Query<&mut Text>,
 ) {
-    for ev in events.read() {
-        for (entity, mut transform) in &mut tiles {
-            if entity == ev.0 {
-                if transform.scale.x < FOCUS_SCALE + SELECTED_SCALE {
-                    transform.scale.x += FOCUS_SCALE;
-                    transform.scale.y += FOCUS_SCALE;
-                }
+    let targets: Vec<Entity> = events.read().map(|ev| ev.0).collect();
+
+    for (entity, mut transform, card, focused) in &mut tiles {
+        if !targets.contains(&entity) {
+            continue;
+        }
+
+        // The pointer and the keyboard may both be on it, but it is only drawn larger once.
+        if !focused && !modal.shows(entity) {
+            transform.scale.x += FOCUS_SCALE;
+            transform.scale.y += FOCUS_SCALE;
+            commands.entity(entity).insert(Focused);
+        }
+
+        // The HUD is optional, so there may be no preview to fill in.
+        let Ok((mut vis, children)) = preview.get_single_mut() else {
+            continue;
+        };
+        *vis = Visibility::Visible;
+
+        for child in children {
+            if let Ok(mut text) = preview_texts.get_mut(*child) {
+                text.sections[0].value = situation.with_context(|context| card.preview(context));
             }
         }
     }
 }
 
 #[derive(Event)]
-pub struct OffSpecialCard(Entity);
+pub struct OffSpecialCard(pub Entity);
 
 impl From<ListenerInput<Pointer<Out>>> for OffSpecialCard {
     fn from(event: ListenerInput<Pointer<Out>>) -> Self {
@@ -398,15 +694,21 @@ impl From<ListenerInput<Pointer<Out>>> for OffSpecialCard {
 pub fn on_off_special_card(
     mut commands: Commands,
     mut events: EventReader<OffSpecialCard>,
-    mut tiles: Query<(Entity, &mut Transform), With<SpecialCardSelectable>>,
+    modal: Res<Modal>,
+    mut tiles: Query<(Entity, &mut Transform), FocusedCardFilter>,
+    mut preview: Query<&mut Visibility, With<SpecialCardPreviewText>>,
 ) {
-    for ev in events.read() {
-        for (entity, mut transform) in &mut tiles {
-            if entity == ev.0 {
-                if transform.scale.x < FOCUS_SCALE + SELECTED_SCALE {
-                    transform.scale.x -= FOCUS_SCALE;
-                    transform.scale.y -= FOCUS_SCALE;
-                }
+    let targets: Vec<Entity> = events.read().map(|ev| ev.0).collect();
+
+    // Whether or not it can still be picked, so a card that stops being one does not stay larger.
+    for (entity, mut transform) in &mut tiles {
+        if targets.contains(&entity) && !modal.shows(entity) {
+            transform.scale.x -= FOCUS_SCALE;
+            transform.scale.y -= FOCUS_SCALE;
+            commands.entity(entity).remove::<Focused>();
+
+            if let Ok(mut vis) = preview.get_single_mut() {
+                *vis = Visibility::Hidden;
             }
         }
     }
@@ -414,53 +716,178 @@ pub fn on_off_special_card(
 
 pub fn on_movement_cards_drawn(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut SpecialCard), Without<SpecialCardDiscarded>>,
+    mut query: Query<
+        (Entity, &mut SpecialCard, Has<SpecialCardUnplayable>),
+        (
+            Without<SpecialCardDiscarded>,
+            Without<SpecialCardInHand>,
+            Without<SpecialCardPending>,
+        ),
+    >,
     mut highlightables: Query<(&mut Visibility, &mut SpecialCardHighlight)>,
     mut events: EventReader<MovementCardsDrawnEvent>, //should listen to turn ended event
+    mut hand: ResMut<SpecialCardHand>,
+    situation: CardSituation,
 ) {
     let mut tile_number = 0;
 
-    for (entity, mut card) in &mut query {
+    // A board of only unplayable Naughty cards counts as empty, or the hand could never be confirmed.
+    let pickable = situation.with_context(|context| {
+        query.iter().any(|(_, card, _)| card.can_be_picked(context, true))
+    });
+
+    hand.phase_open = true;
+    hand.picked_this_turn = !pickable;
+
+    for (entity, mut card, unplayable) in &mut query {
         commands.entity(entity).insert(SpecialCardSelectable);
 
         for (mut vis, mut highlight) in &mut highlightables {
-            if highlight.0 == entity {
+            if highlight.0 == entity && !unplayable {
                 *vis = Visibility::Visible;
             }
         }
     }
 }
 
-#[derive(Clone, Event)]
-pub struct SpecialCardSelectedBlockerClose(Entity);
+/// Greys out special cards with nothing to target and keeps them from being picked.
+pub fn refresh_special_card_playability(
+    mut commands: Commands,
+    mut cards: Query<
+        (
+            Entity,
+            &SpecialCard,
+            &mut Sprite,
+            &Children,
+            Has<SpecialCardUnplayable>,
+            Has<SpecialCardSelectable>,
+            Has<SpecialCardInHand>,
+        ),
+        Without<SpecialCardDiscarded>,
+    >,
+    mut card_covers: Query<&mut Sprite, (With<SpecialCardCover>, Without<SpecialCard>)>,
+    mut highlightables: Query<(&mut Visibility, &SpecialCardHighlight)>,
+    situation: CardSituation,
+) {
+    situation.with_context(|context| {
+        for (entity, card, mut sprite, children, unplayable, selectable, in_hand) in &mut cards {
+            let playable = card.can_be_picked(context, !in_hand);
 
-impl From<ListenerInput<Pointer<Click>>> for SpecialCardSelectedBlockerClose {
-    fn from(event: ListenerInput<Pointer<Click>>) -> Self {
-        SpecialCardSelectedBlockerClose(event.target)
-    }
+            if playable != unplayable {
+                continue;
+            }
+
+            let tint = if playable {
+                commands.entity(entity).remove::<SpecialCardUnplayable>();
+                Color::WHITE
+            } else {
+                commands.entity(entity).insert(SpecialCardUnplayable);
+                Color::rgb(UNPLAYABLE_TINT, UNPLAYABLE_TINT, UNPLAYABLE_TINT)
+            };
+
+            sprite.color = tint;
+
+            for child in children {
+                if let Ok(mut cover) = card_covers.get_mut(*child) {
+                    cover.color = tint;
+                }
+            }
+
+            for (mut vis, highlight) in &mut highlightables {
+                if highlight.0 == entity {
+                    *vis = if playable && selectable {
+                        Visibility::Visible
+                    } else {
+                        Visibility::Hidden
+                    };
+                }
+            }
+        }
+    });
 }
 
 pub fn selected_special_card_close(
     mut commands: Commands,
-    mut events: EventReader<SpecialCardSelectedBlockerClose>,
-    mut cards: Query<(Entity, &mut Transform, &mut SpecialCard), Without<MovementCard>>,
-    mut blocker: Query<&mut Visibility, With<SpecialCardRevealBlocker>>,
-    mut close_button: Query<(
+    mut events: EventReader<ModalClosed>,
+    mut cards: Query<
+        (Entity, &mut Transform, &mut SpecialCard, Has<SpecialCardInHand>),
+        Without<MovementCard>,
+    >,
+    hand_cards: Query<Entity, With<SpecialCardInHand>>,
+    mut hand: ResMut<SpecialCardHand>,
+    mut preview: Query<
         &mut Visibility,
-        &SpecialCardRevealBlockerCloseButton,
-        Without<SpecialCardRevealBlocker>,
-    )>,
-    mut special_card_closed: EventWriter<SpecialCardClosed>,
-    mut highlightables: Query<
-        (&mut Visibility, &mut SpecialCardHighlight),
-        (
-            Without<SpecialCardRevealBlocker>,
-            Without<SpecialCardRevealBlockerCloseButton>,
-        ),
+        (With<SpecialCardPreviewText>, Without<SpecialCardHighlight>),
     >,
+    mut highlightables: Query<(&mut Visibility, &mut SpecialCardHighlight)>,
+    mut special_card_played: EventWriter<SpecialCardPlayedEvent>,
+    mut gameplay_log: EventWriter<GameplayEvent>,
+) {
+    let mut picked = None;
+
+    for ev in events.read() {
+        let ModalContent::CardDetails(entity) = ev.content else {
+            continue;
+        };
+
+        let Ok((entity, mut transform, card, in_hand)) = cards.get_mut(entity) else {
+            continue;
+        };
+
+        if let Ok(mut preview) = preview.get_single_mut() {
+            *preview = Visibility::Hidden;
+        }
+
+        if in_hand {
+            special_card_played.send(SpecialCardPlayedEvent(entity));
+            continue;
+        }
+
+        gameplay_log.send(GameplayEvent::SpecialCardPicked {
+            name: card.name.clone(),
+        });
+
+        transform.scale.x = HAND_SCALE;
+        transform.scale.y = HAND_SCALE;
+        transform.translation.z = -1.0;
+        commands.entity(entity).remove::<Focused>();
+
+        picked = Some((entity, card.is_forced()));
+    }
+
+    let Some((picked, forced)) = picked else {
+        return;
+    };
+
+    for (entity, _, _, _) in &cards {
+        commands.entity(entity).remove::<SpecialCardSelectable>();
+
+        for (mut vis, mut highlight) in &mut highlightables {
+            if highlight.0 == entity {
+                *vis = Visibility::Hidden;
+            }
+        }
+    }
+
+    hand.picked_this_turn = true;
+
+    if forced {
+        commands.entity(picked).insert(SpecialCardPending);
+    } else {
+        commands.entity(picked).insert(SpecialCardInHand);
+        hand.discarding = hand_cards.iter().len() >= hand.limit;
+    }
+}
+
+/// Resolves played special cards and moves them, along with discarded ones, to the discard pile.
+pub fn on_special_card_played(
+    mut commands: Commands,
+    mut events: EventReader<SpecialCardPlayedEvent>,
+    mut discard_events: EventReader<SpecialCardDiscardEvent>,
+    mut cards: Query<(Entity, &mut Transform, &mut SpecialCard), Without<MovementCard>>,
+    pending_cards: Query<Entity, With<SpecialCardPending>>,
     mut draw_card_event: EventWriter<DrawCardEvent>,
-    mut movement_points_update: EventWriter<MovementPointsUpdateEvent>,
-    mut turns_update: EventWriter<TurnsUpdateEvent>,
+    mut effect_queue: ResMut<EffectQueue>,
     mut tiles: Query<
         (Entity, &mut Transform, &mut Tile, &Children),
         (
@@ -487,166 +914,230 @@ pub fn selected_special_card_close(
             Without<Tile>,
         ),
     >,
+    mut gameplay_log: EventWriter<GameplayEvent>,
+    mut run_rng: ResMut<RunRng>,
 ) {
-    let mut card_clone = SpecialCard { ..default() };
-
-    for (entity, mut transform, mut card) in &mut cards {
-        commands.entity(entity).remove::<SpecialCardSelectable>();
-
-        for (mut vis, mut highlight) in &mut highlightables {
-            if highlight.0 == entity {
-                *vis = Visibility::Hidden;
-            }
+    for ev in discard_events.read() {
+        if let Ok((entity, mut transform, _)) = cards.get_mut(ev.0) {
+            discard_special_card(&mut commands, entity, &transform);
         }
+    }
 
-        if transform.scale.x > SELECTED_SCALE {
-            transform.scale.x = FINAL_SCALE;
-            transform.scale.y = FINAL_SCALE;
-            transform.translation.z = -1.0;
-
-            let mut blocker = blocker.single_mut();
-            *blocker = Visibility::Hidden;
-
-            let mut close_button = close_button.single_mut();
-            *close_button.0 = Visibility::Hidden;
-
-            info!("Tile: {:?}", card);
-
-            commands.entity(entity).insert(SpecialCardDiscarded);
-
-            let diff_x = X_FINAL - transform.translation.x;
-            let diff_y = Y_FINAL - transform.translation.y;
-
-            transform.translation.x += diff_x;
-            transform.translation.y += diff_y;
-
-            let mut rng = rand::thread_rng();
-
-            transform.rotate_z(rng.gen_range(-0.1..=0.1));
+    for ev in events.read() {
+        let Ok((entity, mut transform, card)) = cards.get_mut(ev.0) else {
+            continue;
+        };
 
-            card_clone = card.clone();
-        }
-    }
+        discard_special_card(&mut commands, entity, &transform);
 
-    //check for add entity
+        let card_clone = card.clone();
 
-    match card_clone.card_type {
-        CardType::DrawMovementCard => {
-            draw_card_event.send(DrawCardEvent(card_clone.value as u32));
-        }
-        CardType::MovementPointsUpdate => {
-            movement_points_update.send(MovementPointsUpdateEvent(card_clone.value));
-        }
-        CardType::TurnUpdate => {
-            turns_update.send(TurnsUpdateEvent(card_clone.value));
-        }
-        CardType::MovementPointsSubHighest => {
-            let mut m_card = drawn_cards_query
-                .iter()
-                .map(|mut m| (m.0, m.2.value))
-                .max_by_key(|&m| m.1)
-                .unwrap();
-
-            for (entity, _, mut movement_card, mut children) in &mut drawn_cards_query {
-                if entity == m_card.0 {
-                    movement_card.value -= card_clone.value as u32;
-                }
+        gameplay_log.send(GameplayEvent::SpecialCardPlayed {
+            name: card_clone.name.clone(),
+            card_type: format!("{:?}", card_clone.card_type),
+        });
 
-                for child in children {
-                    if let Ok(mut text) = card_points_texts.get_mut(*child) {
-                        text.sections[0].value = format!("+{}", movement_card.value);
-                    }
-                }
+        match card_clone.card_type {
+            CardType::DrawMovementCard => {
+                draw_card_event.send(DrawCardEvent(card_clone.value as u32));
             }
-        }
-        CardType::CurrentTileCostDirectChange => {
-            for (_, _, mut tile, mut children) in &mut tiles {
-                if tile.current {
-                    tile.cost = card_clone.value as u32;
+            CardType::MovementPointsUpdate => {
+                effect_queue.push(PendingEffect::once(
+                    TimingWindow::BeforeCostCheck,
+                    EffectSource::SpecialCard(card_clone.name.clone()),
+                    EffectKind::MovementPoints(card_clone.value),
+                ));
+            }
+            CardType::TurnUpdate => {
+                effect_queue.push(PendingEffect::once(
+                    TimingWindow::BeforeCostCheck,
+                    EffectSource::SpecialCard(card_clone.name.clone()),
+                    EffectKind::Turns(card_clone.value),
+                ));
+            }
+            CardType::MovementPointsSubHighest
+            | CardType::MovementPointsMultiplyLeastCard
+            | CardType::MovementPointsReductionAllCards => {
+                let mut values: Vec<u32> = drawn_cards_query.iter().map(|m| m.2.value).collect();
+                card_clone.apply_to_hand(&mut values);
+
+                for ((_, _, mut movement_card, children), value) in
+                    drawn_cards_query.iter_mut().zip(values)
+                {
+                    movement_card.value = value;
 
                     for child in children {
-                        if let Ok(mut text) = tile_cost_texts.get_mut(*child) {
-                            text.sections[0].value = format!("{}", tile.cost);
+                        if let Ok(mut text) = card_points_texts.get_mut(*child) {
+                            text.sections[0].value = format!("+{}", movement_card.value);
                         }
                     }
-                    break;
                 }
             }
-        }
-        CardType::CurrentTileCostIndirectChange => {
-            for (_, _, mut tile, mut children) in &mut tiles {
-                if tile.current {
-                    tile.cost += card_clone.value as u32;
+            CardType::CurrentTileCostDirectChange | CardType::CurrentTileCostIndirectChange => {
+                for (_, _, mut tile, mut children) in &mut tiles {
+                    if tile.current {
+                        tile.cost = card_clone.tile_cost_after(tile.cost).unwrap_or_else(|| {
+                            run_rng.0.gen_range(0..=MUTATED_COST_MAX)
+                        });
 
-                    for child in children {
-                        if let Ok(mut text) = tile_cost_texts.get_mut(*child) {
-                            text.sections[0].value = format!("{}", tile.cost);
+                        for child in children {
+                            if let Ok(mut text) = tile_cost_texts.get_mut(*child) {
+                                text.sections[0].value = format!("{}", tile.cost);
+                            }
                         }
+                        break;
                     }
-                    break;
                 }
             }
-        }
-        CardType::MovementPointsMultiplyLeastCard => {
-            let mut m_card = drawn_cards_query
-                .iter()
-                .map(|mut m| (m.0, m.2.value))
-                .min_by_key(|&m| m.1)
-                .unwrap();
-
-            for (entity, _, mut movement_card, mut children) in &mut drawn_cards_query {
-                if entity == m_card.0 {
-                    movement_card.value *= card_clone.value as u32;
-                }
+            CardType::Erase => {
+                for (_, _, mut tile, mut children) in &mut tiles {
+                    if tile.current {
+                        tile.cost = card_clone.tile_cost_after(tile.cost).unwrap_or(tile.cost);
+                        tile.tile_type = TileType::Plain;
+
+                        let source = EffectSource::Tile(tile.number);
+                        effect_queue.cancel_where(|effect| effect.source == source);
+
+                        for child in children {
+                            if let Ok(mut text) = tile_cost_texts.get_mut(*child) {
+                                text.sections[0].value = format!("{}", tile.cost);
+                            }
+                        }
 
-                for child in children {
-                    if let Ok(mut text) = card_points_texts.get_mut(*child) {
-                        text.sections[0].value = format!("+{}", movement_card.value);
+                        for child in children {
+                            if let Ok(mut text) = tile_desc_texts.get_mut(*child) {
+                                text.sections[0].value = format!("Erased!");
+                            }
+                        }
+                        break;
                     }
                 }
             }
-        }
-        CardType::MovementPointsReductionAllCards => {
-            for (_, _, mut movement_card, mut children) in &mut drawn_cards_query {
-                movement_card.value = card_clone.value as u32;
-
-                for child in children {
-                    if let Ok(mut text) = card_points_texts.get_mut(*child) {
-                        text.sections[0].value = format!("+{}", movement_card.value);
+            CardType::CancelNaughty => {
+                for pending in &pending_cards {
+                    if let Ok((entity, mut transform, _)) = cards.get_mut(pending) {
+                        discard_special_card(&mut commands, entity, &transform);
                     }
                 }
             }
         }
-        CardType::Erase => {
-            for (_, _, mut tile, mut children) in &mut tiles {
-                if tile.current {
-                    tile.cost += card_clone.value as u32;
-                    tile.tile_type = TileType::Plain;
+    }
+}
 
-                    for child in children {
-                        if let Ok(mut text) = tile_cost_texts.get_mut(*child) {
-                            text.sections[0].value = format!("{}", tile.cost);
-                        }
-                    }
+fn discard_special_card(commands: &mut Commands, entity: Entity, transform: &Transform) {
+    let mut rng = rand::thread_rng();
+    let tween = Tween::new(DISCARD_SECONDS, Ease::QuadInOut)
+        .translate_to(Vec3::new(X_FINAL, Y_FINAL, -1.0))
+        .rotate_to(Quat::from_rotation_z(lean(transform.rotation) + rng.gen_range(-0.1..=0.1)))
+        .scale_to(Vec3::new(FINAL_SCALE, FINAL_SCALE, transform.scale.z));
 
-                    for child in children {
-                        if let Ok(mut text) = tile_desc_texts.get_mut(*child) {
-                            text.sections[0].value = format!("Erased!");
-                        }
-                    }
-                    break;
-                }
-            }
+    commands
+        .entity(entity)
+        .remove::<(SpecialCardInHand, SpecialCardPending, SpecialCardSelectable, Focused)>()
+        .insert((SpecialCardDiscarded, tween));
+}
+
+#[derive(Clone, Event)]
+pub struct SpecialHandConfirmed;
+
+impl From<ListenerInput<Pointer<Click>>> for SpecialHandConfirmed {
+    fn from(_event: ListenerInput<Pointer<Click>>) -> Self {
+        SpecialHandConfirmed
+    }
+}
+
+/// Ends the special-card part of the turn, resolving any Naughty card still waiting.
+pub fn on_special_hand_confirmed(
+    mut events: EventReader<SpecialHandConfirmed>,
+    mut hand: ResMut<SpecialCardHand>,
+    pending_cards: Query<Entity, With<SpecialCardPending>>,
+    mut special_card_played: EventWriter<SpecialCardPlayedEvent>,
+    mut special_card_closed: EventWriter<SpecialCardClosed>,
+    mut handled: EventWriter<InputHandled>,
+) {
+    events.clear();
+
+    if !hand.phase_open || !hand.picked_this_turn || hand.discarding {
+        return;
+    }
+
+    handled.send(InputHandled::ConfirmHand);
+
+    for entity in &pending_cards {
+        special_card_played.send(SpecialCardPlayedEvent(entity));
+    }
+
+    hand.phase_open = false;
+    special_card_closed.send(SpecialCardClosed);
+}
+
+/// Lays out the hand and the waiting Naughty card, and shows the confirm button when the hand can be played.
+pub fn arrange_special_card_hand(
+    mut commands: Commands,
+    hand: Res<SpecialCardHand>,
+    cards: Query<
+        (Entity, &Transform, Has<SpecialCardPending>, Has<Tween>),
+        (
+            Or<(With<SpecialCardInHand>, With<SpecialCardPending>)>,
+            Without<SpecialCardDiscarded>,
+        ),
+    >,
+    modal: Res<Modal>,
+    mut confirm_button: Query<&mut Visibility, With<SpecialHandConfirmButton>>,
+    mut prompt: Query<(&mut Visibility, &Children), (With<SpecialHandPromptText>, Without<SpecialHandConfirmButton>)>,
+    mut prompt_texts: Query<&mut Text>,
+) {
+    let mut slot = 0;
+
+    for (entity, transform, pending, tweening) in &cards {
+        if modal.shows(entity) {
+            continue;
         }
-        _ => {
-            info!("Else")
+
+        let x = if pending {
+            PENDING_X
+        } else {
+            let x = HAND_X_START + (slot as f32 * HAND_X_STEP);
+            slot += 1;
+            x
+        };
+        let target = Vec3::new(x, HAND_Y, transform.translation.z);
+
+        if !tweening && transform.translation != target {
+            commands
+                .entity(entity)
+                .insert(Tween::new(HAND_SECONDS, Ease::CubicOut).translate_to(target));
         }
     }
 
-    special_card_closed.send(SpecialCardClosed);
+    let revealing = modal.is_open();
+
+    let mut confirm_button = confirm_button.single_mut();
+    *confirm_button = if hand.phase_open && hand.picked_this_turn && !hand.discarding && !revealing {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    // The prompt lives on the HUD, which is optional.
+    let Ok((mut prompt_vis, children)) = prompt.get_single_mut() else {
+        return;
+    };
+    *prompt_vis = if hand.discarding && !revealing {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    for child in children {
+        if let Ok(mut text) = prompt_texts.get_mut(*child) {
+            text.sections[0].value = format!("Hand is full ({}), pick a card to discard", hand.limit);
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CardType {
     #[default]
     DrawMovementCard,
@@ -658,6 +1149,56 @@ pub enum CardType {
     MovementPointsMultiplyLeastCard,
     MovementPointsReductionAllCards,
     Erase,
+    CancelNaughty,
+}
+
+/// The special cards runs are dealt from.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpecialCardPool(pub Vec<SpecialCard>);
+
+impl Default for SpecialCardPool {
+    fn default() -> Self {
+        SpecialCardPool(generate_cards())
+    }
+}
+
+impl SpecialCardPool {
+    /// Reads a JSON list of special cards, such as the ones `generate_cards` makes.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let cards: Vec<SpecialCard> = serde_json::from_str(&json)?;
+
+        if cards.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no special cards in the file"));
+        }
+
+        Ok(SpecialCardPool(cards))
+    }
+}
+
+/// The special cards laid out on the board, column by column. A card drawn for a column is not drawn again.
+pub fn deal_special_cards(pool: &[SpecialCard], rng: &mut impl Rng) -> Vec<SpecialCard> {
+    let mut card_res = pool.to_vec();
+    let mut dealt = Vec::with_capacity(BOARD_COLUMNS * BOARD_ROWS);
+
+    for _ in 0..BOARD_COLUMNS {
+        let mut card_res_index = card_res.len();
+
+        for _ in 0..BOARD_ROWS {
+            if card_res.is_empty() {
+                continue;
+            }
+
+            card_res_index = rng.gen_range(0..card_res.len());
+            dealt.push(card_res[card_res_index].clone());
+        }
+
+        if card_res_index < card_res.len() {
+            card_res.remove(card_res_index);
+        }
+    }
+
+    dealt
 }
 
 pub fn generate_cards() -> Vec<SpecialCard> {
@@ -777,6 +1318,14 @@ pub fn generate_cards() -> Vec<SpecialCard> {
         card_type: CardType::Erase,
     };
 
+    let mut card_15 = SpecialCard {
+        name: String::from("Lucky Charm"),
+        tag: String::from("<Nice>"),
+        description: String::from("Cancel the Naughty card waiting to be played"),
+        value: 0,
+        card_type: CardType::CancelNaughty,
+    };
+
     card_res.push(card_1);
     card_res.push(card_2);
     card_res.push(card_3);
@@ -791,6 +1340,7 @@ pub fn generate_cards() -> Vec<SpecialCard> {
     card_res.push(card_12);
     card_res.push(card_13);
     card_res.push(card_14);
+    card_res.push(card_15);
 
     card_res
 }
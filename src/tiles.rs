@@ -1,36 +1,157 @@
+use crate::board::{BoardConstraints, BoardGenerator, BoardLayout, TilePool};
+use crate::effects::{EffectKind, EffectQueue, EffectSource, PendingEffect, TimingWindow};
+use crate::event_log::GameplayEvent;
+use crate::game_state::{RunRng, RunSeed};
+use crate::history::{draw_trail, MovementRules, RunEndedEvent, RunSummary};
+use crate::layout::{LayoutRoot, Zone};
+use crate::ledger::Cause;
+use crate::modal::{open_modal, Modal, ModalAnswer, ModalClosed, ModalContent, OpenModal};
 use crate::movement::{
-    MovementCardsDrawnEvent, MovementCardsPlayedEvent, MovementPoints, MovementPointsUpdateEvent,
+    draw_odds, MovementCard, MovementCardDiscarded, MovementCardDrawn, MovementCardsDrawnEvent,
+    MovementCardsPlayedEvent, MovementPoints, MovementPointsUpdateEvent, CARDS_TO_DRAW,
 };
+use crate::replay::InputHandled;
 use crate::turns::{TurnsLeft, TurnsUpdateEvent};
-use crate::ui::{GameOverText, WonText};
+use crate::tween::{AnimationGate, Ease, Flip, Tween};
+use bevy::app::AppExit;
 use bevy::text::{BreakLineOn, Text2dBounds, TextLayoutInfo};
-use bevy::{ecs::system::EntityCommands, prelude::*, sprite::Anchor};
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam},
+    prelude::*,
+    sprite::Anchor,
+};
 use bevy_mod_picking::prelude::*;
-use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub(crate) const FOCUS_SCALE: f32 = 0.1;
+pub const MOVEMENT_POINTS_INIT_VALUE: i32 = 0;
+pub const TURNS_INIT_VALUE: i32 = 7;
+/// How long the player's piece takes to move to a tile, in seconds.
+const MOVE_SECONDS: f32 = 0.45;
+
+/// Sizes on the board, in board units.
+const TILE_SIZE: Vec2 = Vec2::new(80.0 * 3.0, 97.5 * 3.0);
+const PIECE_SIZE: Vec2 = Vec2::new(64.0 * 2.5, 64.0 * 2.5);
+const GOAL_SIZE: Vec2 = Vec2::new(64.0 * 4.0, 64.0 * 3.0);
+/// Where the piece waits before the run, from the first tile, and where the goal is, from the last.
+const PIECE_START_OFFSET: Vec2 = Vec2::new(-192.0, -87.5);
+const GOAL_OFFSET: Vec2 = Vec2::new(252.0, -7.5);
+
+/// Where the tile in column `x` and row `y` goes, in board units.
+fn tile_position(x: usize, y: u32) -> Vec2 {
+    const X_START: f32 = -64.0;
+    const X_STEP: f32 = 128.0;
+    const Y_START: f32 = -97.5;
+    const Y_STEP: f32 = 195.0;
+    const SPACING: f32 = 150.0;
+
+    Vec2::new(
+        (X_START + (x as f32 * X_STEP)) + (x as f32 * SPACING),
+        (Y_START + (y as f32 * Y_STEP)) + (y as f32 * SPACING),
+    )
+}
+
+/// Where the piece waits before the run and where the goal is, beside the first and the last tile.
+fn piece_and_goal_positions(layout: &BoardLayout) -> (Vec2, Vec2) {
+    let position = |number: u32| {
+        layout
+            .position(number)
+            .map_or(Vec2::ZERO, |(x, y)| tile_position(x, y))
+    };
 
-const FOCUS_SCALE: f32 = 0.1;
-const SELECTED_SCALE: f32 = 2.0;
-const BLOCKER_COLOR_VALUE: f32 = 0.1;
-const MOVEMENT_POINTS_INIT_VALUE: i32 = 0;
-const TURNS_INIT_VALUE: i32 = 7;
+    (
+        position(0) + PIECE_START_OFFSET,
+        position(layout.last_tile()) + GOAL_OFFSET,
+    )
+}
+
+/// The tiles, the player's piece and the goal share these board units, so a piece put on a tile's position sits on it.
+/// Its design covers every tile of `layout`, the piece and the goal, so a bigger board is fitted smaller.
+pub fn board_root(layout: &BoardLayout) -> LayoutRoot {
+    let (piece, goal) = piece_and_goal_positions(layout);
+
+    let design = (0..layout.tile_count())
+        .filter_map(|number| layout.position(number))
+        .map(|(x, y)| Rect::from_center_size(tile_position(x, y), TILE_SIZE))
+        .fold(
+            Rect::from_center_size(piece, PIECE_SIZE).union(Rect::from_center_size(goal, GOAL_SIZE)),
+            |design, tile| design.union(tile),
+        );
+
+    LayoutRoot {
+        zone: Zone::Board,
+        design,
+    }
+}
+
+/// The board: its tiles, the player on it and the turn ending once a move is made.
+/// Needs [`TweenPlugin`](crate::tween::TweenPlugin), [`ModalPlugin`](crate::modal::ModalPlugin)
+/// and [`MovementPlugin`](crate::movement::MovementPlugin); the HUD and the event log are optional.
+pub struct TilesPlugin;
+
+impl Plugin for TilesPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<RunSeed>() {
+            app.insert_resource(RunSeed(rand::random()));
+        }
+
+        app.init_resource::<VisitedTiles>()
+            .add_event::<GameplayEvent>()
+            .init_resource::<TileChoice>()
+            .init_resource::<BoardLayout>()
+            .init_resource::<TilePool>()
+            .init_resource::<MovementRules>()
+            .init_resource::<RunSummary>()
+            .add_event::<TileSelected>()
+            .add_event::<TileSetupComplete>()
+            .add_event::<TileClosedEvent>()
+            .add_event::<OverTile>()
+            .add_event::<OffTile>()
+            .add_event::<InputHandled>()
+            .add_event::<RunEndedEvent>()
+            .add_event::<MovementCardsPlayedEvent>()
+            // Before anything else is dealt from the run's seed, as setting up the board may move it.
+            .add_systems(PreStartup, setup_tiles)
+            .add_systems(
+                Update,
+                (
+                    on_tile_selected.run_if(on_event::<TileSelected>()).before(open_modal),
+                    on_tile_choice_answered.run_if(on_event::<ModalClosed>()),
+                    turn_shown_tile_over.run_if(resource_changed::<Modal>()),
+                    tile_selected_close.run_if(on_event::<ModalClosed>()),
+                    on_tile_setup_complete.run_if(on_event::<TileSetupComplete>()),
+                    on_over_tile.run_if(on_event::<OverTile>()),
+                    on_off_tile.run_if(on_event::<OffTile>()),
+                    on_turn_ended.run_if(on_event::<MovementCardsPlayedEvent>()),
+                    draw_trail.run_if(resource_changed::<VisitedTiles>()),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        crate::require::<AnimationGate>(app, "TilesPlugin", "TweenPlugin");
+        crate::require::<Modal>(app, "TilesPlugin", "ModalPlugin");
+        crate::require::<MovementPoints>(app, "TilesPlugin", "MovementPlugin");
+    }
+}
 
-#[derive(Component, Debug, Clone, Default)]
+#[derive(Component, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Tile {
     pub cost: u32,
     pub description: String,
+    /// Given when the board is laid out.
+    #[serde(skip)]
     pub number: u32,
     pub tile_type: TileType,
+    #[serde(default)]
     pub value: i32,
+    #[serde(default)]
     pub duration: i32,
+    #[serde(skip)]
     pub current: bool,
 }
 
-#[derive(Component)]
-pub struct TileRevealBlocker;
-
-#[derive(Component)]
-pub struct TileRevealBlockerCloseButton;
-
 #[derive(Component)]
 pub struct Selectable;
 
@@ -43,12 +164,19 @@ pub struct TileSetupComplete;
 #[derive(Event)]
 pub struct TileClosedEvent;
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct VisitedTiles(pub Vec<u32>);
 
+/// A tile the player has picked and not yet confirmed moving to.
+#[derive(Resource, Default, Debug)]
+pub struct TileChoice(pub Option<Entity>);
+
 #[derive(Component)]
 pub struct Player;
 
+#[derive(Component)]
+pub struct Goal;
+
 #[derive(Component)]
 pub struct TileHighlight(pub Entity);
 
@@ -62,265 +190,173 @@ pub fn setup_tiles(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut tile_setup_complete: EventWriter<TileSetupComplete>,
+    layout: Res<BoardLayout>,
+    pool: Res<TilePool>,
+    run_seed: Res<RunSeed>,
+    mut exit: EventWriter<AppExit>,
 ) {
-    let len = 80.0 * 3.0;
-    let height = 97.5 * 3.0;
-    let sprite_size = Some(Vec2::new(len, height));
-    let mut tile_res = generate_tiles();
-    // let mut rng = rand::thread_rng();
+    let sprite_size = Some(TILE_SIZE);
+    let root = board_root(&layout);
+    let (piece_start, goal) = piece_and_goal_positions(&layout);
+    let generator = BoardGenerator::with_pool(layout.clone(), pool.clone());
+
+    let tile_res = match generator.generate_near(run_seed.0) {
+        Ok((seed, board)) => {
+            // The run goes on from the seed that built its board, so that seed is the one it reports.
+            if seed != run_seed.0 {
+                info!("Seed {} gives no board, playing seed {} instead", run_seed.0, seed);
+                commands.insert_resource(RunSeed(seed));
+                commands.insert_resource(RunRng(RunSeed(seed).rng(0)));
+            }
+
+            board
+        }
+        Err(err) => {
+            error!("No board can be built near seed {}: {}", run_seed.0, err);
+            exit.send(AppExit);
+            return;
+        }
+    };
 
     commands
         .spawn((
-            SpatialBundle {
-                transform: Transform::from_scale(Vec3 {
-                    x: 0.3,
-                    y: 0.3,
-                    z: 1.0,
-                }),
-                ..default()
-            },
+            SpatialBundle::default(),
+            root,
             PickableBundle::default(),
             On::<Pointer<Down>>::send_event::<TileSelected>(),
             On::<Pointer<Over>>::send_event::<OverTile>(),
             On::<Pointer<Out>>::send_event::<OffTile>(),
         ))
         .with_children(|commands| {
-            const X_START: f32 = -64.0;
-            const X_STEP: f32 = 128.0;
-            const Y_START: f32 = -97.5;
-            const Y_STEP: f32 = 195.0;
-            const SPACING: f32 = 150.0;
-
-            let mut counter = 0;
-            let mut tile_res_index: usize = 30;
-
-            for x in 0..5 {
-                for y in 0..3 {
-                    if (x == 0 || x == 4) && (y == 0 || y == 2) {
-                        continue;
-                    }
-
-                    let mut rng = rand::thread_rng();
-
-                    tile_res_index = rng.gen_range(0..tile_res.len());
-                    let tile = &mut tile_res[tile_res_index];
-
-                    tile.number = counter;
-
-                    // tile.current = if counter == 0 { true } else { false };
-
-                    commands
-                        .spawn((
-                            SpriteBundle {
-                                sprite: Sprite {
-                                    custom_size: sprite_size,
-                                    // color: Color::BLACK,
+            for tile in &tile_res {
+                let Some((x, y)) = layout.position(tile.number) else {
+                    continue;
+                };
+
+                commands
+                    .spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                custom_size: sprite_size,
+                                // color: Color::BLACK,
+                                ..default()
+                            },
+                            texture: asset_server.load("cardBack_blue1.png"),
+                            transform: Transform::from_translation(tile_position(x, y).extend(-1.0)),
+                            ..default()
+                        },
+                        tile.clone(),
+                    ))
+                    .with_children(|parent: &mut ChildBuilder<'_, '_, '_>| {
+                        let text_style = TextStyle {
+                            font_size: 40.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                            ..default()
+                        };
+
+                        parent.spawn((
+                            Text2dBundle {
+                                text: Text::from_section(
+                                    format!("{}", &tile.cost),
+                                    TextStyle {
+                                        font_size: 70.0,
+                                        color: Color::rgb(1.0, 1.0, 0.2),
+                                        ..default()
+                                    },
+                                ),
+                                transform: Transform {
+                                    translation: Vec3::new(0.0, 140.0, 1.0),
                                     ..default()
                                 },
-                                texture: asset_server.load("cardBack_blue1.png"),
-                                transform: Transform::from_xyz(
-                                    (X_START + (x as f32 * X_STEP)) + (x as f32 * SPACING),
-                                    (Y_START + (y as f32 * Y_STEP)) + (y as f32 * (SPACING)),
-                                    -1.0,
-                                ),
+                                text_anchor: Anchor::TopCenter,
                                 ..default()
                             },
-                            tile.clone(),
-                        ))
-                        .with_children(|parent: &mut ChildBuilder<'_, '_, '_>| {
-                            let text_style = TextStyle {
-                                font_size: 40.0,
-                                color: Color::rgb(0.9, 0.9, 0.9),
-                                ..default()
-                            };
+                            TileCostText,
+                        ));
 
-                            parent.spawn((
-                                Text2dBundle {
-                                    text: Text::from_section(
-                                        format!("{}", &tile.cost),
+                        let other_box_size = Vec2::new(190.0, 350.0);
+
+                        parent.spawn((
+                            Text2dBundle {
+                                text: Text {
+                                    sections: vec![TextSection::new(
+                                        format!("{}", &tile.description),
                                         TextStyle {
-                                            font_size: 70.0,
-                                            color: Color::rgb(1.0, 1.0, 0.2),
+                                            font_size: 25.0,
+                                            color: Color::rgb(0.9, 0.9, 0.9),
                                             ..default()
                                         },
-                                    ),
-                                    transform: Transform {
-                                        translation: Vec3::new(0.0, 140.0, 1.0),
-                                        ..default()
-                                    },
-                                    text_anchor: Anchor::TopCenter,
-                                    ..default()
+                                    )],
+                                    linebreak_behavior: BreakLineOn::WordBoundary,
+                                    alignment: TextAlignment::Left,
                                 },
-                                TileCostText,
-                            ));
-
-                            let other_box_size = Vec2::new(190.0, 350.0);
-
-                            parent.spawn((
-                                Text2dBundle {
-                                    text: Text {
-                                        sections: vec![TextSection::new(
-                                            format!("{}", &tile.description),
-                                            TextStyle {
-                                                font_size: 25.0,
-                                                color: Color::rgb(0.9, 0.9, 0.9),
-                                                ..default()
-                                            },
-                                        )],
-                                        linebreak_behavior: BreakLineOn::WordBoundary,
-                                        alignment: TextAlignment::Left,
-                                    },
-                                    text_2d_bounds: Text2dBounds {
-                                        // Wrap text in the rectangle
-                                        size: other_box_size,
-                                    },
-                                    transform: Transform {
-                                        translation: Vec3::new(0.0, 75.0, 1.0),
-                                        ..default()
-                                    },
-                                    text_anchor: Anchor::TopCenter,
+                                text_2d_bounds: Text2dBounds {
+                                    // Wrap text in the rectangle
+                                    size: other_box_size,
+                                },
+                                transform: Transform {
+                                    translation: Vec3::new(0.0, 75.0, 1.0),
                                     ..default()
                                 },
-                                TileDescriptionText,
-                            ));
+                                text_anchor: Anchor::TopCenter,
+                                ..default()
+                            },
+                            TileDescriptionText,
+                        ));
 
-                            parent.spawn((
-                                SpriteBundle {
-                                    sprite: Sprite {
-                                        custom_size: sprite_size,
-                                        ..default()
-                                    },
-                                    texture: asset_server.load("cardBack_blue2.png"),
-                                    transform: Transform::from_xyz(0.0, 0.0, 1.1),
+                        parent.spawn((
+                            SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: sprite_size,
                                     ..default()
                                 },
-                                TileCover,
+                                texture: asset_server.load("cardBack_blue2.png"),
+                                transform: Transform::from_xyz(0.0, 0.0, 1.1),
+                                ..default()
+                            },
+                            TileCover,
+                            Pickable::IGNORE,
+                        ));
+
+                        let highlight_size = Some(TILE_SIZE + Vec2::splat(20.0));
+
+                        parent
+                            .spawn((
+                                SpatialBundle {
+                                    transform: Transform::from_xyz(0.0, 0.0, -1.1),
+                                    visibility: Visibility::Hidden,
+                                    ..Default::default()
+                                },
+                                TileHighlight(parent.parent_entity()),
                                 Pickable::IGNORE,
-                            ));
-
-                            let highlight_size = Some(Vec2::new(len + 20.0, height + 20.0));
-
-                            parent
-                                .spawn((
-                                    SpatialBundle {
-                                        transform: Transform::from_xyz(0.0, 0.0, -1.1),
-                                        visibility: Visibility::Hidden,
-                                        ..Default::default()
-                                    },
-                                    TileHighlight(parent.parent_entity()),
-                                    Pickable::IGNORE,
-                                ))
-                                .with_children(|commands| {
-                                    commands.spawn((SpriteBundle {
-                                        sprite: Sprite {
-                                            custom_size: highlight_size,
-                                            color: Color::FUCHSIA,
-                                            ..default()
-                                        },
-                                        // texture: asset_server.load("images/boovy.png"),
+                            ))
+                            .with_children(|commands| {
+                                commands.spawn((SpriteBundle {
+                                    sprite: Sprite {
+                                        custom_size: highlight_size,
+                                        color: Color::FUCHSIA,
                                         ..default()
-                                    },));
-                                });
-                        });
-
-                    counter += 1;
-                }
-
-                if tile_res_index < tile_res.len() {
-                    tile_res.remove(tile_res_index);
-                }
+                                    },
+                                    // texture: asset_server.load("images/boovy.png"),
+                                    ..default()
+                                },));
+                            });
+                    });
             }
         });
 
     commands
-        .spawn((
-            SpatialBundle {
-                transform: Transform::from_scale(Vec3::splat(100.0)),
-                visibility: Visibility::Hidden,
-                ..Default::default()
-            },
-            TileRevealBlocker,
-            PickableBundle::default(),
-        ))
-        .with_children(|commands| {
-            commands.spawn((SpriteBundle {
-                sprite: Sprite {
-                    custom_size: sprite_size,
-                    color: Color::rgba(
-                        BLOCKER_COLOR_VALUE,
-                        BLOCKER_COLOR_VALUE,
-                        BLOCKER_COLOR_VALUE,
-                        0.2,
-                    ),
-                    ..default()
-                },
-                // texture: asset_server.load("images/boovy.png"),
-                ..default()
-            },));
-        });
-
-    commands
-        .spawn((
-            ButtonBundle {
-                style: Style {
-                    width: Val::Percent(10.0),
-                    height: Val::Px(42.0),
-                    margin: UiRect::top(Val::Percent(2.0)),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    left: Val::Percent(85.0),
-                    top: Val::Percent(10.0),
-                    ..default()
-                },
-                background_color: BackgroundColor(Color::RED),
-                transform: Transform::from_xyz(0.0, 0.0, 1.0),
-                visibility: Visibility::Hidden,
-                ..default()
-            },
-            On::<Pointer<Click>>::send_event::<TileSelectedBlockerClose>(),
-            NoDeselect,
-            TileRevealBlockerCloseButton,
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                TextBundle {
-                    text: Text::from_section(
-                        "X",
-                        TextStyle {
-                            font_size: 40.0,
-                            color: Color::rgb(0.9, 0.9, 0.9),
-                            ..default()
-                        },
-                    ),
-                    ..default()
-                },
-                Pickable::IGNORE,
-            ));
-        });
-
-    let len = 64.0 * 2.5;
-    let height = 64.0 * 2.5;
-    let piece_size = Some(Vec2::new(len, height));
-
-    commands
-        .spawn((SpatialBundle {
-            transform: Transform::from_scale(Vec3 {
-                x: 0.3,
-                y: 0.3,
-                z: 1.0,
-            }),
-            ..default()
-        },))
+        .spawn((SpatialBundle::default(), root))
         .with_children(|commands| {
             commands.spawn((
                 SpriteBundle {
                     sprite: Sprite {
-                        custom_size: piece_size,
+                        custom_size: Some(PIECE_SIZE),
                         // color: Color::BLACK,
                         ..default()
                     },
                     texture: asset_server.load("pieceYellow_border01.png"),
-                    transform: Transform::from_xyz(-256.0, 160.0, 0.0),
+                    transform: Transform::from_translation(piece_start.extend(0.0)),
                     ..default()
                 },
                 Player,
@@ -328,37 +364,29 @@ pub fn setup_tiles(
             ));
         });
 
-    let len = 64.0 * 4.0;
-    let height = 64.0 * 3.0;
-    let piece_size = Some(Vec2::new(len, height));
-
     commands
-        .spawn((SpatialBundle {
-            transform: Transform::from_scale(Vec3 {
-                x: 0.3,
-                y: 0.3,
-                z: 1.0,
-            }),
-            ..default()
-        },))
+        .spawn((SpatialBundle::default(), root))
         .with_children(|commands| {
-            commands.spawn((SpriteBundle {
-                sprite: Sprite {
-                    custom_size: piece_size,
-                    // color: Color::BLACK,
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(GOAL_SIZE),
+                        // color: Color::BLACK,
+                        ..default()
+                    },
+                    texture: asset_server.load("pieceYellow_border12.png"),
+                    transform: Transform::from_translation(goal.extend(0.0)),
                     ..default()
                 },
-                texture: asset_server.load("pieceYellow_border12.png"),
-                transform: Transform::from_xyz(1300.0, 240.0, 0.0),
-                ..default()
-            },));
+                Goal,
+            ));
         });
 
     tile_setup_complete.send(TileSetupComplete);
 }
 
 #[derive(Event)]
-pub struct TileSelected(Entity);
+pub struct TileSelected(pub Entity);
 
 impl From<ListenerInput<Pointer<Down>>> for TileSelected {
     fn from(event: ListenerInput<Pointer<Down>>) -> Self {
@@ -366,64 +394,248 @@ impl From<ListenerInput<Pointer<Down>>> for TileSelected {
     }
 }
 
+type DeckFilter = (Without<MovementCardDrawn>, Without<MovementCardDiscarded>);
+
+/// What a move is previewed against: the points and the deck, the way there and what a covered tile may be.
+#[derive(SystemParam)]
+pub struct MoveOdds<'w, 's> {
+    deck: Query<'w, 's, &'static MovementCard, DeckFilter>,
+    tiles: Query<'w, 's, (&'static Tile, &'static Children)>,
+    covers: Query<'w, 's, &'static Visibility, With<TileCover>>,
+    movement_points: Res<'w, MovementPoints>,
+    visited_tiles: Res<'w, VisitedTiles>,
+    layout: Res<'w, BoardLayout>,
+    rules: Res<'w, MovementRules>,
+    pool: Res<'w, TilePool>,
+    constraints: Local<'s, BoardConstraints>,
+}
+
+impl MoveOdds<'_, '_> {
+    fn preview(&self, tile: &Tile, children: &Children) -> String {
+        let penalty = self.visited_tiles.0.last().map_or(0, |from| {
+            self.rules.penalty(&self.layout, &self.visited_tiles.0, *from, tile.number)
+        });
+        let deck: Vec<u32> = self.deck.iter().map(|card| card.value).collect();
+
+        // The cost of a tile still face down is not the player's to know yet, only what its column allows
+        // and which unique tiles are already showing elsewhere.
+        let options = self.is_covered(children).then(|| {
+            let (column, _) = self.layout.position(tile.number).unwrap_or_default();
+            let face_up: Vec<&Tile> = self
+                .tiles
+                .iter()
+                .filter(|(_, children)| !self.is_covered(children))
+                .map(|(tile, _)| tile)
+                .collect();
+
+            self.pool.covered_options(self.constraints.cost_tier(column), &face_up)
+        });
+
+        move_preview(tile, options.as_ref(), penalty, self.movement_points.0, &deck)
+    }
+
+    fn is_covered(&self, children: &Children) -> bool {
+        children
+            .iter()
+            .any(|child| self.covers.get(*child).is_ok_and(|vis| *vis != Visibility::Hidden))
+    }
+}
+
+/// The tiles the pointer can pick, and what it may land on instead.
+#[derive(SystemParam)]
+pub struct PickableTiles<'w, 's> {
+    tiles: Query<'w, 's, (&'static Tile, &'static Children, Has<Selectable>, Has<Focused>)>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl PickableTiles<'_, '_> {
+    /// The tile `target` is, or is part of; the pointer may land on its highlight rather than the tile itself.
+    fn under(&self, target: Entity) -> Option<Entity> {
+        std::iter::successors(Some(target), |e| self.parents.get(*e).ok().map(Parent::get))
+            .find(|e| self.tiles.contains(*e))
+    }
+}
+
 pub fn on_tile_selected(
-    mut commands: Commands,
     mut events: EventReader<TileSelected>,
-    mut tiles: Query<(Entity, &mut Transform, &mut Tile, &Children)>,
-    mut blocker: Query<&mut Visibility, With<TileRevealBlocker>>,
-    mut close_button: Query<(
-        &mut Visibility,
-        &TileRevealBlockerCloseButton,
-        Without<TileRevealBlocker>,
-    )>,
-    mut highlightables: Query<
-        (&mut Visibility, &mut TileHighlight),
-        (
-            Without<TileRevealBlocker>,
-            Without<TileRevealBlockerCloseButton>,
-        ),
-    >,
-    mut tile_cover_query: Query<
-        &mut Visibility,
-        (
-            With<TileCover>,
-            Without<TileHighlight>,
-            Without<TileRevealBlocker>,
-            Without<TileRevealBlockerCloseButton>,
-        ),
-    >,
+    modal: Res<Modal>,
+    mut choice: ResMut<TileChoice>,
+    mut open_modal: EventWriter<OpenModal>,
+    mut handled: EventWriter<InputHandled>,
+    pickable: PickableTiles,
+    odds: MoveOdds,
 ) {
-    for (entity, mut transform, mut tile, mut children) in &mut tiles {
-        if transform.scale.x > 1.0 {
-            if transform.scale.x < FOCUS_SCALE + SELECTED_SCALE {
-                transform.scale.x += SELECTED_SCALE;
-                transform.scale.y += SELECTED_SCALE;
-                transform.translation.z = 1.0;
+    if modal.is_open() {
+        return;
+    }
 
-                let mut blocker = blocker.single_mut();
-                *blocker = Visibility::Visible;
+    for ev in events.read() {
+        let Some(entity) = pickable.under(ev.0) else {
+            continue;
+        };
+        let (tile, children, selectable, focused) = pickable.tiles.get(entity).unwrap();
+
+        // Only a tile in focus can be picked, and only pickable tiles are given focus.
+        if !focused {
+            continue;
+        }
 
-                let mut close_button = close_button.single_mut();
-                *close_button.0 = Visibility::Visible;
+        // The game picks the first tile itself; moves the player picks are confirmed first.
+        if !selectable {
+            open_modal.send(OpenModal(ModalContent::TileDetails(entity)));
+            break;
+        }
 
-                for (mut vis, mut highlight) in &mut highlightables {
-                    if highlight.0 == entity {
-                        *vis = Visibility::Hidden;
-                    }
-                }
+        choice.0 = Some(entity);
+        handled.send(InputHandled::SelectTile(entity));
+        open_modal.send(OpenModal(ModalContent::Confirmation(odds.preview(tile, children))));
 
-                for child in children {
-                    if let Ok(mut vis) = tile_cover_query.get_mut(*child) {
-                        *vis = Visibility::Hidden;
-                    }
-                }
+        break;
+    }
+}
+
+/// What moving to `tile` takes against the points there are and the cards left to draw.
+/// A covered tile is given with the `pool` of what it may still be, such as [`TilePool::covered_options`],
+/// and its odds are taken over what it may cost.
+pub fn move_preview(tile: &Tile, pool: Option<&TilePool>, penalty: i32, points: i32, deck: &[u32]) -> String {
+    let way_there = if penalty == 0 {
+        String::new()
+    } else {
+        format!(" {:+} for the way there", penalty)
+    };
+
+    let (cost, shortfall, odds) = match pool {
+        None => {
+            let cost = tile.cost as i32 + penalty;
+            let shortfall = (cost - points).max(0);
+            let odds = draw_odds(deck, CARDS_TO_DRAW as usize, shortfall);
+
+            let cost = if penalty == 0 {
+                cost.to_string()
+            } else {
+                format!("{} ({}{})", cost, tile.cost, way_there)
+            };
+
+            (cost, shortfall.to_string(), odds)
+        }
+        Some(pool) => {
+            // Each cost weighed by how often a column draws it from these entries, worked out once.
+            // The checks and repairs a drawn board goes through after are left out.
+            let mut weights: BTreeMap<u32, u32> = BTreeMap::new();
+            for entry in &pool.entries {
+                *weights.entry(entry.tile.cost).or_default() += entry.weight;
             }
+
+            let total: u32 = weights.values().sum();
+            let odds = weights
+                .iter()
+                .map(|(cost, weight)| {
+                    let shortfall = (*cost as i32 + penalty - points).max(0);
+                    draw_odds(deck, CARDS_TO_DRAW as usize, shortfall) * *weight as f32
+                })
+                .sum::<f32>()
+                / total.max(1) as f32;
+
+            let min = weights.keys().next().copied().unwrap_or(0);
+            let max = weights.keys().next_back().copied().unwrap_or(0);
+            let short = |cost: u32| (cost as i32 + penalty - points).max(0);
+
+            (
+                format!("? ({}{})", span(min, max), way_there),
+                span(short(min), short(max)),
+                odds,
+            )
+        }
+    };
+
+    format!(
+        "Move to tile {}?\nCost: {}\nPoints: {}\nShort by: {}\nChance the next draw covers it: {:.0}%",
+        tile.number,
+        cost,
+        points,
+        shortfall,
+        odds * 100.0
+    )
+}
+
+/// `min to max`, or just the one number when they are the same.
+fn span<T: PartialEq + std::fmt::Display>(min: T, max: T) -> String {
+    if min == max {
+        min.to_string()
+    } else {
+        format!("{} to {}", min, max)
+    }
+}
+
+/// Reveals the tile the player confirmed moving to, or puts it back if they cancelled.
+pub fn on_tile_choice_answered(
+    mut commands: Commands,
+    mut events: EventReader<ModalClosed>,
+    mut choice: ResMut<TileChoice>,
+    mut open_modal: EventWriter<OpenModal>,
+    mut tiles: Query<&mut Transform, With<Tile>>,
+) {
+    for ev in events.read() {
+        if !matches!(ev.content, ModalContent::Confirmation(_)) {
+            continue;
+        }
+
+        let Some(entity) = choice.0.take() else {
+            continue;
+        };
+
+        if ev.answer == ModalAnswer::Confirm {
+            open_modal.send(OpenModal(ModalContent::TileDetails(entity)));
+        } else if let Ok(mut transform) = tiles.get_mut(entity) {
+            // Whether or not it still has the focus it was picked with.
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<Focused>();
+        }
+    }
+}
+
+/// Turns over the tile a modal is showing, and drops its highlight.
+pub fn turn_shown_tile_over(
+    mut commands: Commands,
+    modal: Res<Modal>,
+    tiles: Query<(&Children, Has<Flip>), With<Tile>>,
+    mut highlightables: Query<(&mut Visibility, &TileHighlight)>,
+    tile_cover_query: Query<&Visibility, (With<TileCover>, Without<TileHighlight>)>,
+) {
+    let Some(ModalContent::TileDetails(entity)) = modal.content() else {
+        return;
+    };
+    let Ok((children, flipping)) = tiles.get(*entity) else {
+        return;
+    };
+
+    for (mut vis, highlight) in &mut highlightables {
+        if highlight.0 == *entity {
+            *vis = Visibility::Hidden;
+        }
+    }
+
+    if flipping {
+        return;
+    }
+
+    for child in children {
+        if tile_cover_query.get(*child).is_ok_and(|vis| *vis != Visibility::Hidden) {
+            commands.entity(*entity).insert(Flip::reveal(*child));
         }
     }
 }
 
+/// On a tile or special card with the focus, from the pointer over it or from keyboard and gamepad navigation,
+/// which draws it `FOCUS_SCALE` larger. Only a focused tile or card can be picked.
+#[derive(Component)]
+pub struct Focused;
+
+type UnfocusedTileFilter = (With<Selectable>, Without<Focused>);
+type FocusedTileFilter = (With<Tile>, With<Focused>);
+
 #[derive(Event)]
-pub struct OverTile(Entity);
+pub struct OverTile(pub Entity);
 
 impl From<ListenerInput<Pointer<Over>>> for OverTile {
     fn from(event: ListenerInput<Pointer<Over>>) -> Self {
@@ -434,22 +646,23 @@ impl From<ListenerInput<Pointer<Over>>> for OverTile {
 pub fn on_over_tile(
     mut commands: Commands,
     mut events: EventReader<OverTile>,
-    mut tiles: Query<(Entity, &mut Transform), With<Selectable>>,
+    modal: Res<Modal>,
+    mut tiles: Query<(Entity, &mut Transform), UnfocusedTileFilter>,
 ) {
-    for ev in events.read() {
-        for (entity, mut transform) in &mut tiles {
-            if entity == ev.0 {
-                if transform.scale.x < FOCUS_SCALE + SELECTED_SCALE {
-                    transform.scale.x += FOCUS_SCALE;
-                    transform.scale.y += FOCUS_SCALE;
-                }
-            }
+    let targets: Vec<Entity> = events.read().map(|ev| ev.0).collect();
+
+    // The pointer and the keyboard may both be on it, but it is only drawn larger once.
+    for (entity, mut transform) in &mut tiles {
+        if targets.contains(&entity) && !modal.shows(entity) {
+            transform.scale.x += FOCUS_SCALE;
+            transform.scale.y += FOCUS_SCALE;
+            commands.entity(entity).insert(Focused);
         }
     }
 }
 
 #[derive(Event)]
-pub struct OffTile(Entity);
+pub struct OffTile(pub Entity);
 
 impl From<ListenerInput<Pointer<Out>>> for OffTile {
     fn from(event: ListenerInput<Pointer<Out>>) -> Self {
@@ -460,104 +673,112 @@ impl From<ListenerInput<Pointer<Out>>> for OffTile {
 pub fn on_off_tile(
     mut commands: Commands,
     mut events: EventReader<OffTile>,
-    mut tiles: Query<(Entity, &mut Transform), With<Selectable>>,
+    modal: Res<Modal>,
+    mut tiles: Query<(Entity, &mut Transform), FocusedTileFilter>,
 ) {
-    for ev in events.read() {
-        for (entity, mut transform) in &mut tiles {
-            if entity == ev.0 {
-                if transform.scale.x < FOCUS_SCALE + SELECTED_SCALE {
-                    transform.scale.x -= FOCUS_SCALE;
-                    transform.scale.y -= FOCUS_SCALE;
-                }
-            }
+    let targets: Vec<Entity> = events.read().map(|ev| ev.0).collect();
+
+    // Whether or not it can still be picked, so a tile that stops being one does not stay larger.
+    for (entity, mut transform) in &mut tiles {
+        if targets.contains(&entity) && !modal.shows(entity) {
+            transform.scale.x -= FOCUS_SCALE;
+            transform.scale.y -= FOCUS_SCALE;
+            commands.entity(entity).remove::<Focused>();
         }
     }
 }
 
-#[derive(Clone, Event)]
-pub struct TileSelectedBlockerClose(Entity);
-
-impl From<ListenerInput<Pointer<Click>>> for TileSelectedBlockerClose {
-    fn from(event: ListenerInput<Pointer<Click>>) -> Self {
-        TileSelectedBlockerClose(event.target)
-    }
-}
-
 pub fn tile_selected_close(
     mut commands: Commands,
-    mut events: EventReader<TileSelectedBlockerClose>,
-    mut tiles: Query<(Entity, &mut Transform, &mut Tile)>,
-    mut blocker: Query<&mut Visibility, With<TileRevealBlocker>>,
-    mut close_button: Query<(
-        &mut Visibility,
-        &TileRevealBlockerCloseButton,
-        Without<TileRevealBlocker>,
-    )>,
-    mut player_query: Query<(&mut Transform, &Player), Without<Tile>>,
+    mut events: EventReader<ModalClosed>,
+    mut tiles: Query<(&mut Transform, &mut Tile)>,
+    player_query: Query<(Entity, &Transform), (With<Player>, Without<Tile>)>,
     mut visited_tiles: ResMut<VisitedTiles>,
     mut movement_points_update: EventWriter<MovementPointsUpdateEvent>,
     mut turns_update: EventWriter<TurnsUpdateEvent>,
-    mut tile_closed: EventWriter<TileClosedEvent>,
+    mut gate: ResMut<AnimationGate>,
+    mut effect_queue: ResMut<EffectQueue>,
+    layout: Res<BoardLayout>,
+    rules: Res<MovementRules>,
+    mut gameplay_log: EventWriter<GameplayEvent>,
 ) {
-    let mut player = player_query.single_mut();
-
-    let mut tile_clone = Tile { ..default() };
-
-    for (entity, mut transform, mut tile) in &mut tiles {
-        if transform.scale.x > SELECTED_SCALE {
-            transform.scale.x -= (FOCUS_SCALE + SELECTED_SCALE);
-            transform.scale.y -= (FOCUS_SCALE + SELECTED_SCALE);
-            transform.translation.z = -1.0;
+    let Some(entity) = events.read().find_map(|ev| match ev.content {
+        ModalContent::TileDetails(entity) => Some(entity),
+        _ => None,
+    }) else {
+        return;
+    };
 
-            let mut blocker = blocker.single_mut();
-            *blocker = Visibility::Hidden;
+    let Ok((mut transform, mut tile)) = tiles.get_mut(entity) else {
+        return;
+    };
 
-            let mut close_button = close_button.single_mut();
-            *close_button.0 = Visibility::Hidden;
+    let (player, player_transform) = player_query.single();
 
-            info!("Tile: {:?}", tile);
+    // Whether or not it still has the focus it was picked with.
+    transform.scale = Vec3::ONE;
+    transform.translation.z = -1.0;
+    commands.entity(entity).remove::<Focused>();
 
-            //move PC
-            let mut diff = transform.translation - player.0.translation;
+    //move PC
+    let target = transform.translation.truncate().extend(player_transform.translation.z);
 
-            player.0.translation.x += diff.x;
-            player.0.translation.y += diff.y;
+    commands
+        .entity(player)
+        .insert(Tween::new(MOVE_SECONDS, Ease::QuadInOut).translate_to(target));
 
-            tile_clone = tile.clone();
+    let tile_clone = tile.clone();
 
-            tile.current = true;
-            // commands.entity(entity).insert(Selectable);
-        }
-    }
+    tile.current = true;
 
     if tile_clone.current {
         return;
     }
 
-    //check for add entity
+    if let Some(from) = visited_tiles.0.last() {
+        let penalty = rules.penalty(&layout, &visited_tiles.0, *from, tile_clone.number);
 
-    match tile_clone.tile_type {
-        TileType::Plain => {}
-        TileType::MovementPointsUpdate => {
-            movement_points_update.send(MovementPointsUpdateEvent(tile_clone.value));
-
-            if tile_clone.duration < 0 {
-                //create or add to movement curse comp
-            } else if tile_clone.duration > 0 {
-            }
-        }
-        TileType::TurnUpdate => {
-            turns_update.send(TurnsUpdateEvent(tile_clone.value));
+        if penalty != 0 {
+            effect_queue.push(PendingEffect::once(
+                TimingWindow::OnReveal,
+                EffectSource::Tile(tile_clone.number),
+                EffectKind::MovementPoints(-penalty),
+            ));
         }
-        TileType::Blessing => {
-            //remove ailments components
+    }
+
+    gameplay_log.send(GameplayEvent::Moved {
+        from: visited_tiles.0.last().copied(),
+        to: tile_clone.number,
+    });
+    gameplay_log.send(GameplayEvent::TileRevealed {
+        tile: tile_clone.number,
+        cost: tile_clone.cost,
+        tile_type: format!("{:?}", tile_clone.tile_type),
+        description: tile_clone.description.clone(),
+    });
+
+    // A tile gives what it gives once, however often the path comes back to it.
+    if !visited_tiles.0.contains(&tile_clone.number) {
+        for effect in tile_clone.effects() {
+            effect_queue.push(effect);
         }
     }
 
-    tile_closed.send(TileClosedEvent);
+    visited_tiles.0.push(tile_clone.number);
+
+    effect_queue.resolve(
+        TimingWindow::OnReveal,
+        &mut movement_points_update,
+        &mut turns_update,
+    );
+
+    // The turn starts once the piece is on the tile.
+    gate.send(TileClosedEvent);
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TileType {
     #[default]
     Plain,
@@ -566,19 +787,41 @@ pub enum TileType {
     Blessing,
 }
 
-pub fn get_neighbours(index: u32) -> Vec<u32> {
-    match index {
-        0 => vec![1, 2, 3],
-        1 => vec![2, 4, 5],
-        2 => vec![1, 3, 4, 5, 6],
-        3 => vec![2, 5, 6],
-        4 => vec![5, 7, 8],
-        5 => vec![4, 6, 7, 8, 9],
-        6 => vec![5, 8, 9],
-        7 => vec![8, 10],
-        8 => vec![7, 9, 10],
-        9 => vec![8, 10],
-        _ => vec![0],
+impl Tile {
+    /// Effects the tile registers once revealed. Lasting ones resolve at the start of each turn.
+    /// One-off effects wait for the first hand played on the tile, rather than landing at the reveal,
+    /// so a card played in that hand, such as Erase, can still cancel them.
+    pub fn effects(&self) -> Vec<PendingEffect> {
+        let source = EffectSource::Tile(self.number);
+
+        match self.tile_type {
+            TileType::Plain => vec![],
+            TileType::MovementPointsUpdate if self.duration != 0 => vec![PendingEffect {
+                window: TimingWindow::StartOfTurn,
+                source,
+                kind: EffectKind::MovementPoints(self.value),
+                remaining: if self.duration < 0 {
+                    None
+                } else {
+                    Some(self.duration as u32)
+                },
+            }],
+            TileType::MovementPointsUpdate => vec![PendingEffect::once(
+                TimingWindow::OnEnter,
+                source,
+                EffectKind::MovementPoints(self.value),
+            )],
+            TileType::TurnUpdate => vec![PendingEffect::once(
+                TimingWindow::OnEnter,
+                source,
+                EffectKind::Turns(self.value),
+            )],
+            TileType::Blessing => vec![PendingEffect::once(
+                TimingWindow::OnEnter,
+                source,
+                EffectKind::ClearAfflictions,
+            )],
+        }
     }
 }
 
@@ -587,23 +830,17 @@ pub fn on_tile_setup_complete(
     mut events: EventReader<TileSetupComplete>,
     mut tiles: Query<(Entity, &mut Transform, &mut Tile, &Children)>,
     mut tile_selected: EventWriter<TileSelected>,
-    mut tile_cover_query: Query<&mut Visibility, With<TileCover>>,
     mut movement_points_update: EventWriter<MovementPointsUpdateEvent>,
     mut turns_update: EventWriter<TurnsUpdateEvent>,
 ) {
-    movement_points_update.send(MovementPointsUpdateEvent(MOVEMENT_POINTS_INIT_VALUE));
-    turns_update.send(TurnsUpdateEvent(TURNS_INIT_VALUE));
+    movement_points_update.send(MovementPointsUpdateEvent(MOVEMENT_POINTS_INIT_VALUE, Cause::Start));
+    turns_update.send(TurnsUpdateEvent(TURNS_INIT_VALUE, Cause::Start));
 
     for (entity, mut transform, mut tile, mut children) in &mut tiles {
         if tile.number == 0 {
-            for child in children {
-                if let Ok(mut vis) = tile_cover_query.get_mut(*child) {
-                    *vis = Visibility::Hidden;
-                }
-            }
-
             transform.scale.x += FOCUS_SCALE;
             transform.scale.y += FOCUS_SCALE;
+            commands.entity(entity).insert(Focused);
 
             tile_selected.send(TileSelected(entity));
         }
@@ -619,19 +856,35 @@ pub fn on_turn_ended(
     mut tile_closed: EventWriter<TileClosedEvent>,
     mut turns_update: EventWriter<TurnsUpdateEvent>,
     mut turns_left: ResMut<TurnsLeft>,
-    mut game_over: Query<&mut Visibility, (With<GameOverText>, Without<WonText>, Without<TileHighlight>)>,
-    mut game_won: Query<&mut Visibility, (With<WonText>, Without<GameOverText>, Without<TileHighlight>)>,
+    layout: Res<BoardLayout>,
+    rules: Res<MovementRules>,
+    visited_tiles: Res<VisitedTiles>,
+    mut run_ended: EventWriter<RunEndedEvent>,
+    mut gameplay_log: EventWriter<GameplayEvent>,
 ) {
-    turns_update.send(TurnsUpdateEvent(-1));
+    turns_update.send(TurnsUpdateEvent(-1, Cause::TurnTick));
+
+    // The tick sent above is only counted once this system is done. A run lost on its last turn ends on none left.
+    let turns_after_tick = (turns_left.0 - 1).max(0);
+    let run_ended_event = |won| RunEndedEvent {
+        won,
+        turns_left: turns_after_tick,
+        movement_points: movement_points.0,
+    };
+    let game_ended = |won| GameplayEvent::GameEnded {
+        won,
+        turns_left: turns_after_tick,
+        movement_points: movement_points.0,
+    };
 
     if turns_left.0 <= 0{
-        let mut vis = game_over.single_mut();
-
-        *vis = Visibility::Visible;
+        run_ended.send(run_ended_event(false));
+        gameplay_log.send(game_ended(false));
         return;
     }
 
     let mut costs_met = false;
+    let mut won = false;
 
     for (entity, mut tile) in &mut query {
         if tile.current {
@@ -641,16 +894,18 @@ pub fn on_turn_ended(
                 false
             };
 
-            if costs_met && tile.number == 10{
-                let mut vis = game_won.single_mut();
+            won = costs_met && tile.number == layout.last_tile();
 
-                *vis = Visibility::Visible;
-            }
+            gameplay_log.send(GameplayEvent::CostCheck {
+                tile: tile.number,
+                cost: tile.cost,
+                movement_points: movement_points.0,
+                met: costs_met,
+            });
         }
     }
 
     if costs_met {
-
         let mut tile_number = 0;
 
         for (entity, mut tile) in &mut query {
@@ -668,7 +923,13 @@ pub fn on_turn_ended(
             }
         }
 
-        let mut neighbours = get_neighbours(tile_number);
+        if won {
+            run_ended.send(run_ended_event(true));
+            gameplay_log.send(game_ended(true));
+            return;
+        }
+
+        let mut neighbours = rules.next_tiles(&layout, &visited_tiles.0, tile_number);
 
         for (entity, mut tile) in &mut query {
             if neighbours.contains(&tile.number) {
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use crate::{game_state::{GameState, GameStates}, ui::TurnsLeftText};
+use crate::{game_state::{GameState, GameStates}, ledger::{Cause, Ledger, Tally}, ui::TurnsLeftText};
 
 // mod game_state;
 
@@ -8,7 +8,7 @@ use crate::{game_state::{GameState, GameStates}, ui::TurnsLeftText};
 pub struct TurnsLeft(pub i32);
 
 #[derive(Event)]
-pub struct TurnsUpdateEvent(pub i32);
+pub struct TurnsUpdateEvent(pub i32, pub Cause);
 
 
 pub fn update_turns_left(
@@ -16,12 +16,14 @@ pub fn update_turns_left(
     mut turns_left: ResMut<TurnsLeft>,
     mut turns_update: EventReader<TurnsUpdateEvent>,
     mut texts: Query<&mut Text, With<TurnsLeftText>>, 
+    mut ledger: ResMut<Ledger>,
 ){
-    let mut text = texts.single_mut();
-
     for ev in turns_update.read() {
         turns_left.0 += ev.0;
+        ledger.record(Tally::Turns, ev.0, ev.1.clone());
     }
 
-    text.sections[0].value = format!("Points: {:?}", turns_left.0);
+    if let Ok(mut text) = texts.get_single_mut() {
+        text.sections[0].value = format!("Points: {:?}", turns_left.0);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,378 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{ecs::system::SystemParam, prelude::*, transform::TransformSystem};
+
+use crate::settings::Settings;
+
+/// How long turning a card over takes, in seconds.
+pub const FLIP_SECONDS: f32 = 0.3;
+
+/// Moves, turns and scales sprites with a `Tween` over time and turns cards over with a `Flip`,
+/// at the speed chosen in the settings, and holds gameplay queued on the `AnimationGate` until they have all finished.
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationGate>()
+            .add_event::<CardTurned>()
+            .add_systems(
+                PostUpdate,
+                (advance_tweens, advance_flips, release_held_gameplay)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// How a tween's progress is shaped between its start and its end.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Ease {
+    Linear,
+    QuadIn,
+    QuadOut,
+    #[default]
+    QuadInOut,
+    CubicOut,
+    /// Overshoots a little before settling, for things landing in place.
+    BackOut,
+}
+
+impl Ease {
+    /// Progress at `t`, both running from 0 to 1.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Ease::Linear => t,
+            Ease::QuadIn => t * t,
+            Ease::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Ease::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Ease::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Ease::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Ease::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                let u = t - 1.0;
+                1.0 + (OVERSHOOT + 1.0) * u.powi(3) + OVERSHOOT * u.powi(2)
+            }
+        }
+    }
+}
+
+/// Animates the parts of an entity's `Transform` it is given targets for, from wherever they are when it starts.
+/// Removed once it and any tweens chained after it are done.
+#[derive(Component, Debug, Clone)]
+pub struct Tween {
+    /// In seconds, at normal animation speed.
+    pub duration: f32,
+    pub ease: Ease,
+    pub translation: Option<Vec3>,
+    pub rotation: Option<Quat>,
+    pub scale: Option<Vec3>,
+    elapsed: f32,
+    start: Option<Transform>,
+    next: Option<Box<Tween>>,
+}
+
+impl Tween {
+    pub fn new(duration: f32, ease: Ease) -> Self {
+        Tween {
+            duration,
+            ease,
+            translation: None,
+            rotation: None,
+            scale: None,
+            elapsed: 0.0,
+            start: None,
+            next: None,
+        }
+    }
+
+    pub fn translate_to(mut self, translation: Vec3) -> Self {
+        self.translation = Some(translation);
+        self
+    }
+
+    pub fn rotate_to(mut self, rotation: Quat) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    pub fn scale_to(mut self, scale: Vec3) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Plays `tween` once this one, and whatever is already chained after it, is done.
+    pub fn then(mut self, tween: Tween) -> Self {
+        self.next = Some(Box::new(match self.next.take() {
+            Some(next) => next.then(tween),
+            None => tween,
+        }));
+        self
+    }
+
+    /// Moves `transform` on by `seconds`, returning the time left over once this tween has finished.
+    fn advance(&mut self, transform: &mut Transform, seconds: f32) -> Option<f32> {
+        let start = *self.start.get_or_insert(*transform);
+        self.elapsed += seconds;
+
+        if self.elapsed >= self.duration {
+            // Exactly on the targets, so whoever set them can tell they have been reached.
+            self.land(transform);
+            return Some(self.elapsed - self.duration);
+        }
+
+        let t = self.ease.apply(self.elapsed / self.duration);
+
+        if let Some(translation) = self.translation {
+            transform.translation = start.translation.lerp(translation, t);
+        }
+        if let Some(rotation) = self.rotation {
+            transform.rotation = start.rotation.slerp(rotation, t);
+        }
+        if let Some(scale) = self.scale {
+            transform.scale = start.scale.lerp(scale, t);
+        }
+
+        None
+    }
+
+    fn land(&self, transform: &mut Transform) {
+        if let Some(translation) = self.translation {
+            transform.translation = translation;
+        }
+        if let Some(rotation) = self.rotation {
+            transform.rotation = rotation;
+        }
+        if let Some(scale) = self.scale {
+            transform.scale = scale;
+        }
+    }
+
+    /// Puts `transform` where this tween and those chained after it end up.
+    fn finish(&self, transform: &mut Transform) {
+        let mut tween = Some(self);
+
+        while let Some(current) = tween {
+            current.land(transform);
+            tween = current.next.as_deref();
+        }
+    }
+}
+
+/// Turns a card over about its upright axis. Once it is edge on its cover is hidden, or shown,
+/// and `CardTurned` is sent; then it turns back to face the player. Removed when done.
+#[derive(Component, Debug, Clone)]
+pub struct Flip {
+    /// A child sprite covering the card's face.
+    pub cover: Entity,
+    /// Whether the cover is shown rather than hidden once the card is turned.
+    pub face_down: bool,
+    /// In seconds, at normal animation speed.
+    pub duration: f32,
+    delay: f32,
+    elapsed: f32,
+    turned: bool,
+}
+
+impl Flip {
+    /// Turns the card face up, hiding `cover`.
+    pub fn reveal(cover: Entity) -> Self {
+        Flip {
+            cover,
+            face_down: false,
+            duration: FLIP_SECONDS,
+            delay: 0.0,
+            elapsed: 0.0,
+            turned: false,
+        }
+    }
+
+    /// Turns the card face down, showing `cover`.
+    pub fn conceal(cover: Entity) -> Self {
+        Flip {
+            face_down: true,
+            ..Flip::reveal(cover)
+        }
+    }
+
+    /// Waits `seconds` before starting, e.g. for the card to get where it is going.
+    pub fn after(mut self, seconds: f32) -> Self {
+        self.delay = seconds;
+        self
+    }
+}
+
+/// Sent when a card being flipped is edge on and its cover has just been hidden or shown.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CardTurned {
+    pub card: Entity,
+    pub face_up: bool,
+}
+
+/// Skips animations while present, whatever the settings say, such as while a loaded run catches up.
+#[derive(Resource, Default)]
+pub struct FastForward;
+
+type Held = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// Gameplay that waits for the animations playing now, such as the next phase of a turn.
+#[derive(Resource, Default)]
+pub struct AnimationGate {
+    running: usize,
+    held: Vec<Held>,
+}
+
+impl AnimationGate {
+    /// Sends `event` once every tween has finished, straight away if none are playing.
+    pub fn send<E: Event>(&mut self, event: E) {
+        self.held.push(Box::new(move |world: &mut World| {
+            world.send_event(event);
+        }));
+    }
+
+    /// Whether nothing is animating and nothing is waiting for it to.
+    pub fn is_idle(&self) -> bool {
+        self.running == 0 && self.held.is_empty()
+    }
+}
+
+/// Whether animations are playing or holding gameplay back.
+pub fn animating(gate: Option<Res<AnimationGate>>) -> bool {
+    gate.is_some_and(|gate| !gate.is_idle())
+}
+
+/// How far animations get each frame.
+#[derive(SystemParam)]
+pub struct AnimationClock<'w> {
+    time: Res<'w, Time>,
+    settings: Option<Res<'w, Settings>>,
+    fast_forward: Option<Res<'w, FastForward>>,
+}
+
+impl AnimationClock<'_> {
+    /// Animation seconds gone by this frame: infinite when animations are skipped.
+    fn seconds(&self) -> f32 {
+        match &self.settings {
+            _ if self.fast_forward.is_some() => f32::INFINITY,
+            Some(settings) if settings.skip_animations => f32::INFINITY,
+            Some(settings) => self.time.delta_seconds() * settings.animation_speed as f32,
+            None => self.time.delta_seconds(),
+        }
+    }
+}
+
+pub fn advance_tweens(
+    mut commands: Commands,
+    clock: AnimationClock,
+    mut gate: ResMut<AnimationGate>,
+    mut tweens: Query<(Entity, &mut Tween, &mut Transform)>,
+) {
+    let elapsed = clock.seconds();
+    let mut running = 0;
+
+    for (entity, mut tween, mut transform) in &mut tweens {
+        if elapsed.is_infinite() {
+            tween.finish(&mut transform);
+            commands.entity(entity).remove::<Tween>();
+            continue;
+        }
+
+        let mut seconds = elapsed;
+
+        loop {
+            let Some(left) = tween.advance(&mut transform, seconds) else {
+                running += 1;
+                break;
+            };
+
+            match tween.next.take() {
+                Some(next) => {
+                    *tween = *next;
+                    seconds = left;
+                }
+                None => {
+                    commands.entity(entity).remove::<Tween>();
+                    break;
+                }
+            }
+        }
+    }
+
+    gate.running = running;
+}
+
+pub fn advance_flips(
+    mut commands: Commands,
+    clock: AnimationClock,
+    mut gate: ResMut<AnimationGate>,
+    mut flips: Query<(Entity, &mut Flip, &mut Transform)>,
+    mut covers: Query<&mut Visibility>,
+    mut card_turned: EventWriter<CardTurned>,
+) {
+    let elapsed = clock.seconds();
+
+    for (entity, mut flip, mut transform) in &mut flips {
+        flip.elapsed += elapsed;
+        let t = (flip.elapsed - flip.delay) / flip.duration.max(f32::EPSILON);
+
+        if t < 0.0 {
+            gate.running += 1;
+            continue;
+        }
+
+        if t >= 0.5 && !flip.turned {
+            flip.turned = true;
+
+            if let Ok(mut vis) = covers.get_mut(flip.cover) {
+                *vis = if flip.face_down {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+            }
+
+            card_turned.send(CardTurned {
+                card: entity,
+                face_up: !flip.face_down,
+            });
+        }
+
+        let angle = if t >= 1.0 {
+            commands.entity(entity).remove::<Flip>();
+            0.0
+        } else if t < 0.5 {
+            gate.running += 1;
+            FRAC_PI_2 * Ease::QuadIn.apply(t * 2.0)
+        } else {
+            gate.running += 1;
+            FRAC_PI_2 * (1.0 - Ease::QuadOut.apply(t * 2.0 - 1.0))
+        };
+
+        transform.rotation = Quat::from_rotation_z(lean(transform.rotation)) * Quat::from_rotation_y(angle);
+    }
+}
+
+/// How far `rotation` tilts a card about the axis facing the camera, which is the only way cards lean.
+/// A flip turns them about another axis, so their lean survives it.
+pub fn lean(rotation: Quat) -> f32 {
+    let up = rotation * Vec3::Y;
+    (-up.x).atan2(up.y)
+}
+
+pub fn release_held_gameplay(world: &mut World) {
+    let held = {
+        let mut gate = world.resource_mut::<AnimationGate>();
+
+        if gate.running > 0 || gate.held.is_empty() {
+            return;
+        }
+
+        std::mem::take(&mut gate.held)
+    };
+
+    for release in held {
+        release(world);
+    }
+}
+
@@ -1,5 +1,52 @@
 use bevy::prelude::*;
 
+use crate::{
+    bindings::{bindings_screen_closed, ActionMap, GameAction},
+    history::{show_run_summary, RunEndedEvent},
+    ledger::{toggle_ledger_panel, update_ledger_panel, Ledger},
+    settings::settings_screen_closed,
+    tiles::on_turn_ended,
+};
+
+/// How long a notice stays up.
+const NOTICE_SECONDS: f32 = 2.0;
+
+/// The HUD text, the ledger panel and the run summary.
+/// Needs [`BindingsPlugin`](crate::bindings::BindingsPlugin) and [`MovementPlugin`](crate::movement::MovementPlugin).
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        let toggle_ledger = toggle_ledger_panel.into_configs();
+        let announce = announce_unavailable_actions
+            .run_if(bindings_screen_closed)
+            .run_if(settings_screen_closed);
+        #[cfg(feature = "dev")]
+        let toggle_ledger = toggle_ledger.run_if(crate::console::console_closed);
+        #[cfg(feature = "dev")]
+        let announce = announce.run_if(crate::console::console_closed);
+
+        app.add_event::<RunEndedEvent>()
+            .add_systems(Startup, setup_game_ui)
+            .add_systems(
+                Update,
+                (
+                    show_run_summary
+                        .run_if(on_event::<RunEndedEvent>())
+                        .after(on_turn_ended),
+                    toggle_ledger,
+                    update_ledger_panel.run_if(resource_changed::<Ledger>()),
+                    announce,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        crate::require::<ActionMap>(app, "HudPlugin", "BindingsPlugin");
+        crate::require::<Ledger>(app, "HudPlugin", "MovementPlugin");
+    }
+}
+
 #[derive(Component)]
 pub struct MovementPointsText;
 
@@ -12,6 +59,21 @@ pub struct GameOverText;
 #[derive(Component)]
 pub struct WonText;
 
+#[derive(Component)]
+pub struct SpecialCardPreviewText;
+
+#[derive(Component)]
+pub struct SpecialHandPromptText;
+
+#[derive(Component)]
+pub struct RunSummaryText;
+
+#[derive(Component)]
+pub struct LedgerPanel;
+
+#[derive(Component)]
+pub struct NoticeText;
+
 pub fn setup_game_ui(mut commands: Commands) {
     commands
         .spawn((NodeBundle {
@@ -133,4 +195,197 @@ pub fn setup_game_ui(mut commands: Commands) {
                 ..default()
             },));
         });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    left: Val::Percent(25.0),
+                    top: Val::Percent(3.0),
+                    width: Val::Percent(50.0),
+                    height: Val::Percent(8.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::BEIGE.into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            SpecialCardPreviewText,
+        ))
+        .with_children(|commands| {
+            commands.spawn((TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 28.0,
+                        color: Color::DARK_GRAY,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    left: Val::Percent(25.0),
+                    top: Val::Percent(12.0),
+                    width: Val::Percent(50.0),
+                    height: Val::Percent(8.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::ORANGE.into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            SpecialHandPromptText,
+        ))
+        .with_children(|commands| {
+            commands.spawn((TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 28.0,
+                        color: Color::DARK_GRAY,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    left: Val::Percent(30.0),
+                    top: Val::Percent(30.0),
+                    width: Val::Percent(40.0),
+                    padding: UiRect::all(Val::Px(16.0)),
+                    ..default()
+                },
+                background_color: Color::BEIGE.into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            RunSummaryText,
+        ))
+        .with_children(|commands| {
+            commands.spawn((TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 28.0,
+                        color: Color::DARK_GRAY,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    left: Val::Percent(75.0),
+                    top: Val::Percent(12.0),
+                    width: Val::Percent(25.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            LedgerPanel,
+        ))
+        .with_children(|commands| {
+            commands.spawn((TextBundle {
+                text: Text::from_section(
+                    "History",
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                ..default()
+            },));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    left: Val::Percent(25.0),
+                    bottom: Val::Percent(3.0),
+                    width: Val::Percent(50.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::BEIGE.into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            NoticeText,
+        ))
+        .with_children(|commands| {
+            commands.spawn((TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 28.0,
+                        color: Color::DARK_GRAY,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },));
+        });
+}
+
+/// Says for a moment that undo or a hint was asked for, neither of which the game has yet.
+pub fn announce_unavailable_actions(
+    keys: Res<Input<KeyCode>>,
+    map: Res<ActionMap>,
+    time: Res<Time>,
+    mut shown_for: Local<f32>,
+    mut notice: Query<(&mut Visibility, &Children), With<NoticeText>>,
+    mut texts: Query<&mut Text>,
+) {
+    let Ok((mut vis, children)) = notice.get_single_mut() else {
+        return;
+    };
+
+    let pressed = [GameAction::Undo, GameAction::Hint]
+        .into_iter()
+        .find(|action| map.just_pressed(*action, &keys));
+
+    if let Some(action) = pressed {
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = format!("{} is not available", action);
+            }
+        }
+
+        *shown_for = 0.0;
+        *vis = Visibility::Visible;
+    } else if *vis == Visibility::Visible {
+        *shown_for += time.delta_seconds();
+
+        if *shown_for >= NOTICE_SECONDS {
+            *vis = Visibility::Hidden;
+        }
+    }
 }
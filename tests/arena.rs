@@ -0,0 +1,230 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpStream},
+    thread,
+    time::Duration,
+};
+
+use treacherous_safari::{
+    arena::{write_standings_csv, Arena, ArenaConfig, ArenaMessage, BotStanding},
+    rules::Action,
+};
+
+/// How a test bot answers a turn, `None` to say nothing.
+type Strategy = fn(&[Action]) -> Option<Action>;
+
+/// Connects a bot that answers every turn with `choose`, returning the end of every game.
+fn spawn_bot(
+    address: SocketAddr,
+    name: &str,
+    choose: Strategy,
+) -> thread::JoinHandle<Vec<ArenaMessage>> {
+    spawn_client(address, name, move |stream, _, legal_actions| {
+        if let Some(action) = choose(legal_actions) {
+            writeln!(stream, "{}", serde_json::to_string(&action).unwrap()).unwrap();
+        }
+    })
+}
+
+/// Connects a bot that writes its own answer to the `n`th turn, returning the end of every game.
+fn spawn_client(
+    address: SocketAddr,
+    name: &str,
+    mut answer: impl FnMut(&mut TcpStream, usize, &[Action]) + Send + 'static,
+) -> thread::JoinHandle<Vec<ArenaMessage>> {
+    let name = name.to_string();
+
+    thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.set_nodelay(true).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut ends = vec![];
+        let mut turns = 0;
+
+        writeln!(stream, r#"{{"name":"{}"}}"#, name).unwrap();
+
+        let mut line = String::new();
+
+        while reader.read_line(&mut line).unwrap_or(0) > 0 {
+            match serde_json::from_str(&line).unwrap() {
+                ArenaMessage::Turn { legal_actions, .. } => {
+                    answer(&mut stream, turns, &legal_actions);
+                    turns += 1;
+                }
+                end @ ArenaMessage::End { .. } => ends.push(end),
+            }
+
+            line.clear();
+        }
+
+        ends
+    })
+}
+
+fn tournament(config: ArenaConfig, bots: &[(&str, Strategy)]) -> Vec<BotStanding> {
+    let arena = Arena::bind("127.0.0.1:0", config).unwrap();
+    let address = arena.local_addr().unwrap();
+    let games = thread::spawn(move || arena.run().unwrap());
+
+    // One at a time, so the standings come back in this order.
+    let mut clients = vec![];
+    for (name, choose) in bots {
+        clients.push(spawn_bot(address, name, *choose));
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let standings = games.join().unwrap();
+
+    for client in clients {
+        client.join().unwrap();
+    }
+
+    standings
+}
+
+fn first(actions: &[Action]) -> Option<Action> {
+    actions.first().copied()
+}
+
+fn sleepy(_: &[Action]) -> Option<Action> {
+    None
+}
+
+fn cheater(_: &[Action]) -> Option<Action> {
+    Some(Action::MoveTo(99))
+}
+
+fn config(bots: usize) -> ArenaConfig {
+    ArenaConfig {
+        bots,
+        seeds: vec![1, 2, 3],
+        move_time: Duration::from_millis(100),
+        ..ArenaConfig::default()
+    }
+}
+
+#[test]
+fn every_bot_plays_every_seed() {
+    let standings = tournament(config(2), &[("first", first), ("first", first)]);
+
+    assert_eq!(standings.len(), 2);
+    assert_eq!(standings[0].name, "first");
+    assert_eq!(standings[1].name, "first-2");
+
+    for standing in &standings {
+        assert_eq!(standing.games, 3);
+        assert_eq!(standing.forfeits, 0);
+    }
+
+    // The same seeds played the same way end the same way.
+    assert_eq!(standings[0].wins, standings[1].wins);
+    assert_eq!(standings[0].surplus_turns, standings[1].surplus_turns);
+}
+
+#[test]
+fn slow_and_illegal_bots_forfeit() {
+    let standings = tournament(config(2), &[("sleepy", sleepy), ("cheater", cheater)]);
+
+    for standing in &standings {
+        assert_eq!(standing.games, 3);
+        assert_eq!(standing.wins, 0);
+        assert_eq!(standing.forfeits, 3);
+    }
+}
+
+#[test]
+fn a_bot_is_told_why_it_forfeited() {
+    let arena = Arena::bind("127.0.0.1:0", config(1)).unwrap();
+    let address = arena.local_addr().unwrap();
+    let games = thread::spawn(move || arena.run().unwrap());
+
+    let ends = spawn_bot(address, "cheater", cheater);
+    games.join().unwrap();
+    let ends = ends.join().unwrap();
+
+    assert_eq!(ends.len(), 3);
+    assert!(matches!(
+        &ends[0],
+        ArenaMessage::End { seed: 1, won: false, forfeit: Some(reason), .. } if reason.contains("not allowed")
+    ));
+}
+
+#[test]
+fn a_bot_late_on_one_turn_plays_the_next_games_in_step() {
+    let arena = Arena::bind("127.0.0.1:0", config(2)).unwrap();
+    let address = arena.local_addr().unwrap();
+    let games = thread::spawn(move || arena.run().unwrap());
+
+    let prompt = spawn_bot(address, "first", first);
+    thread::sleep(Duration::from_millis(50));
+
+    // Its first answer comes a byte at a time, each well within the move time but not all of them together.
+    let late = spawn_client(address, "late", |stream, turn, legal_actions| {
+        let answer = format!("{}\n", serde_json::to_string(&legal_actions[0]).unwrap());
+
+        if turn == 0 {
+            for byte in answer.bytes() {
+                thread::sleep(Duration::from_millis(5));
+                let _ = stream.write_all(&[byte]);
+            }
+        } else {
+            let _ = stream.write_all(answer.as_bytes());
+        }
+    });
+
+    games.join().unwrap();
+    let prompt = prompt.join().unwrap();
+    let late = late.join().unwrap();
+
+    assert_eq!(late.len(), 3);
+    assert!(matches!(
+        &late[0],
+        ArenaMessage::End { seed: 1, forfeit: Some(reason), .. } if reason.contains("took longer")
+    ));
+    assert_eq!(late[1..], prompt[1..]);
+}
+
+#[test]
+fn a_bot_that_never_answers_is_dropped() {
+    let arena = Arena::bind("127.0.0.1:0", config(1)).unwrap();
+    let address = arena.local_addr().unwrap();
+    let games = thread::spawn(move || arena.run().unwrap());
+
+    let ends = spawn_bot(address, "sleepy", sleepy);
+    let standings = games.join().unwrap();
+    let ends = ends.join().unwrap();
+
+    assert_eq!(standings[0].forfeits, 3);
+    // Told about the game it lost on time, then hung up on.
+    assert_eq!(ends.len(), 1);
+}
+
+#[test]
+fn standings_are_written_as_csv() {
+    let standings = vec![
+        BotStanding {
+            name: String::from("careful, slow"),
+            games: 4,
+            wins: 2,
+            surplus_turns: 5,
+            forfeits: 1,
+        },
+        BotStanding {
+            name: String::from("greedy"),
+            games: 4,
+            wins: 0,
+            surplus_turns: 0,
+            forfeits: 0,
+        },
+    ];
+
+    let mut csv = vec![];
+    write_standings_csv(&standings, &mut csv).unwrap();
+
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "bot,games,wins,forfeits,win_rate,average_surplus_turns\n\
+         \"careful, slow\",4,2,1,0.500,2.50\n\
+         greedy,4,0,0,0.000,0.00\n"
+    );
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use common::Harness;
+use treacherous_safari::{
+    bindings::{ActionMap, BindingsScreen, GameAction, BINDINGS_FILE},
+    config::ConfigDirectory,
+    navigation::Focus,
+    ui::NoticeText,
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("safari-bindings-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn with_config(seed: u64, dir: &Path) -> Harness {
+    let dir = dir.to_path_buf();
+    Harness::new_with(seed, move |app| {
+        app.insert_resource(ConfigDirectory(Some(dir)));
+    })
+}
+
+#[test]
+fn the_defaults_do_not_conflict() {
+    let map = ActionMap::default();
+
+    assert!(map.conflicts().is_empty());
+    assert_eq!(map.action(KeyCode::Escape), Some(GameAction::Inspector));
+    assert_eq!(map.keys(GameAction::Confirm), &[KeyCode::Return, KeyCode::Space]);
+}
+
+#[test]
+fn a_key_belongs_to_one_action() {
+    let mut map = ActionMap::default();
+
+    assert_eq!(map.rebind(GameAction::Confirm, KeyCode::L), Err(GameAction::Ledger));
+    assert_eq!(map.keys(GameAction::Confirm), &[KeyCode::Return, KeyCode::Space]);
+
+    assert_eq!(map.rebind(GameAction::Confirm, KeyCode::E), Ok(()));
+    assert_eq!(map.keys(GameAction::Confirm), &[KeyCode::E]);
+    assert_eq!(map.action(KeyCode::Return), None);
+}
+
+#[test]
+fn bindings_round_trip_through_a_file() {
+    let path = temp_dir("round-trip").join(BINDINGS_FILE);
+    let mut map = ActionMap::default();
+    map.rebind(GameAction::Ledger, KeyCode::K).unwrap();
+
+    map.save(&path).unwrap();
+
+    assert_eq!(ActionMap::load(&path).unwrap(), map);
+}
+
+#[test]
+fn unusable_files_fall_back_to_the_defaults() {
+    let dir = temp_dir("unusable");
+    fs::create_dir_all(&dir).unwrap();
+
+    let unknown = dir.join("unknown.json");
+    fs::write(&unknown, r#"{"bindings":{"confirm":["NoSuchKey"]}}"#).unwrap();
+    assert!(ActionMap::load(&unknown).is_err());
+    assert_eq!(ActionMap::load_or_default(Some(&unknown)), ActionMap::default());
+
+    let conflicting = dir.join("conflicting.json");
+    fs::write(&conflicting, r#"{"bindings":{"confirm":["L"]}}"#).unwrap();
+    assert!(ActionMap::load(&conflicting).is_err());
+    assert_eq!(ActionMap::load_or_default(Some(&conflicting)), ActionMap::default());
+}
+
+#[test]
+fn saved_bindings_load_at_startup() {
+    let dir = temp_dir("startup");
+    let mut map = ActionMap::default();
+    map.rebind(GameAction::Right, KeyCode::K).unwrap();
+    map.save(&dir.join(BINDINGS_FILE)).unwrap();
+
+    let mut harness = with_config(51, &dir);
+    assert_eq!(*harness.world().resource::<ActionMap>(), map);
+
+    // Navigation follows the new key.
+    harness.press(KeyCode::Return);
+    harness.press(KeyCode::K);
+    assert!(harness.world().resource::<Focus>().0.is_some());
+}
+
+#[test]
+fn the_screen_rebinds_and_saves() {
+    let dir = temp_dir("screen");
+    let mut harness = with_config(52, &dir);
+
+    harness.press(KeyCode::F1);
+    assert!(harness.world().resource::<BindingsScreen>().open);
+
+    // The second row is Down.
+    harness.press(KeyCode::Down);
+    harness.press(KeyCode::Return);
+    assert!(harness.world().resource::<BindingsScreen>().listening);
+
+    harness.press(KeyCode::J);
+
+    assert_eq!(harness.world().resource::<ActionMap>().keys(GameAction::Down), &[KeyCode::J]);
+    let saved = ActionMap::load(&dir.join(BINDINGS_FILE)).unwrap();
+    assert_eq!(saved.keys(GameAction::Down), &[KeyCode::J]);
+
+    // Arrows do not move the game's focus while the screen is open.
+    assert_eq!(harness.world().resource::<Focus>().0, None);
+
+    harness.press(KeyCode::Back);
+    assert!(!harness.world().resource::<BindingsScreen>().open);
+}
+
+#[test]
+fn the_screen_refuses_conflicts() {
+    let mut harness = Harness::new(53);
+
+    harness.press(KeyCode::F1);
+    harness.press(KeyCode::Return);
+    harness.press(KeyCode::L);
+
+    let screen = harness.world().resource::<BindingsScreen>();
+    assert_eq!(screen.message.as_deref(), Some("L is already bound to History panel"));
+    assert_eq!(
+        harness.world().resource::<ActionMap>().keys(GameAction::Up),
+        &[KeyCode::Up, KeyCode::W]
+    );
+}
+
+#[test]
+fn undo_says_it_is_not_available() {
+    let mut harness = Harness::new(54);
+    assert!(!harness.is_visible::<NoticeText>());
+
+    harness.press(KeyCode::Z);
+
+    assert!(harness.is_visible::<NoticeText>());
+    let world = harness.world();
+    let shown = world
+        .query::<&Text>()
+        .iter(world)
+        .any(|text| text.sections.iter().any(|s| s.value == "Undo is not available"));
+    assert!(shown);
+}
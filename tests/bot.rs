@@ -0,0 +1,180 @@
+mod common;
+
+use std::{
+    io::{self, Write},
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use bevy::{app::AppExit, prelude::*, time::TimeUpdateStrategy};
+use common::Harness;
+use treacherous_safari::{
+    bot::{BotIo, BotPlugin, BotResponse},
+    replay::PlayerAction,
+    tween::AnimationGate,
+};
+
+/// Frames a response is waited for before giving up.
+const RESPONSE_FRAMES: usize = 200;
+
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Bot {
+    harness: Harness,
+    commands: Sender<String>,
+    output: Output,
+    read: usize,
+}
+
+impl Bot {
+    fn new(seed: u64) -> Self {
+        Bot::new_with(seed, |_| {})
+    }
+
+    /// A bot on a game that plays its animations, a frame being a sixtieth of a second.
+    fn animated(seed: u64) -> Self {
+        let mut bot = Bot::new_with(seed, |app| {
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(1.0 / 60.0)));
+        });
+        bot.harness.animate();
+        bot
+    }
+
+    fn new_with(seed: u64, configure: impl FnOnce(&mut App)) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let output = Output::default();
+        let io = BotIo::new(receiver, output.clone());
+
+        let harness = Harness::new_with(seed, |app| {
+            app.insert_resource(io).add_plugins(BotPlugin);
+            configure(app);
+        });
+
+        Bot {
+            harness,
+            commands,
+            output,
+            read: 0,
+        }
+    }
+
+    fn response(&mut self) -> BotResponse {
+        for _ in 0..RESPONSE_FRAMES {
+            let lines: Vec<String> = String::from_utf8(self.output.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect();
+
+            if let Some(line) = lines.get(self.read) {
+                self.read += 1;
+                return serde_json::from_str(line).unwrap();
+            }
+
+            self.harness.step();
+        }
+
+        panic!("no response from the bot");
+    }
+
+    fn send(&mut self, line: &str) -> BotResponse {
+        self.commands.send(line.to_string()).unwrap();
+        self.response()
+    }
+}
+
+#[test]
+fn the_first_response_is_the_first_reveal() {
+    let mut bot = Bot::new(31);
+    let response = bot.response();
+
+    assert_eq!(response.legal_actions, vec![PlayerAction::CloseReveal]);
+    assert_eq!(response.state.seed, Some(31));
+    assert_eq!(response.won, None);
+    assert_eq!(response.error, None);
+}
+
+#[test]
+fn commands_are_played() {
+    let mut bot = Bot::new(32);
+    bot.response();
+
+    let response = bot.send(r#"{"action":"close_reveal"}"#);
+
+    assert_eq!(response.error, None);
+    assert_eq!(response.state.drawn_cards.len(), 2);
+    assert!(response.state.phase_open);
+    assert!(response
+        .legal_actions
+        .iter()
+        .any(|action| matches!(action, PlayerAction::SelectSpecial { .. })));
+}
+
+#[test]
+fn animated_turns_are_answered_once_they_have_played_out() {
+    let mut bot = Bot::animated(34);
+    let mut response = bot.response();
+
+    while response.won.is_none() {
+        let action = response.legal_actions[0].clone();
+        response = bot.send(&serde_json::to_string(&action).unwrap());
+
+        assert_eq!(response.error, None);
+        assert!(bot.harness.world().resource::<AnimationGate>().is_idle(), "answered during {:?}", action);
+    }
+}
+
+#[test]
+fn bad_commands_are_answered_with_an_error() {
+    let mut bot = Bot::new(33);
+    let first = bot.response();
+
+    let unreadable = bot.send("select the first tile");
+    assert!(unreadable.error.unwrap().starts_with("unreadable command"));
+
+    let illegal = bot.send(r#"{"action":"confirm_hand"}"#);
+    assert!(illegal.error.unwrap().starts_with("not a legal action"));
+    assert_eq!(illegal.state, first.state);
+}
+
+#[test]
+fn a_bot_can_play_a_whole_run() {
+    let mut bot = Bot::new(34);
+    let mut response = bot.response();
+
+    while response.won.is_none() {
+        let action = response.legal_actions.first().expect("nothing to do before the end");
+        response = bot.send(&serde_json::to_string(action).unwrap());
+
+        assert_eq!(response.error, None);
+    }
+
+    assert!(response.legal_actions.is_empty());
+}
+
+#[test]
+fn the_app_exits_when_the_commands_run_out() {
+    let mut bot = Bot::new(35);
+    bot.response();
+
+    let Bot { mut harness, commands, .. } = bot;
+    drop(commands);
+    harness.step();
+
+    let exits = harness.world().resource::<Events<AppExit>>();
+    assert!(!exits.is_empty());
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+use bevy::prelude::*;
+use common::Harness;
+use treacherous_safari::{
+    board::TilePool,
+    bug_report::{BugReport, BugReportPlugin, CrashContext},
+    diagnostics::RunSnapshot,
+    event_log::RunDirectory,
+    replay::{ActionLog, PlayerAction},
+    special_cards::{generate_cards, SpecialCardPool},
+    tiles::generate_tiles,
+};
+
+#[derive(Resource)]
+struct Explode;
+
+fn explode(explode: Option<Res<Explode>>) {
+    if explode.is_some() {
+        panic!("boom");
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("safari-bug-report-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Plays a few turns on the board as dealt, whatever the draws.
+fn play_turns(harness: &mut Harness, turns: usize) {
+    harness.close_tile();
+
+    for _ in 0..turns {
+        harness.finish_turn();
+
+        if let Some(tile) = harness.selectable_tiles().first() {
+            harness.select_tile(*tile);
+        }
+    }
+}
+
+#[test]
+fn player_actions_are_logged() {
+    let mut harness = Harness::new(21);
+    harness.close_tile();
+
+    let card = harness.board_special_cards()[0];
+    harness.reveal_special_card(card);
+    harness.confirm_hand();
+
+    let actions = &harness.world().resource::<ActionLog>().0;
+
+    assert_eq!(actions[0], PlayerAction::CloseReveal);
+    assert!(matches!(actions[1], PlayerAction::SelectSpecial { .. }));
+    assert_eq!(actions[2], PlayerAction::CloseReveal);
+    assert_eq!(actions[3], PlayerAction::ConfirmHand);
+}
+
+#[test]
+fn a_report_replays_to_the_same_state() {
+    let mut harness = Harness::new(22);
+    play_turns(&mut harness, 4);
+
+    let report = BugReport::capture(harness.world());
+    let dir = report.write(&temp_dir("replay")).unwrap();
+    let loaded = BugReport::load(&dir).unwrap();
+
+    assert_eq!(loaded, report);
+
+    let mut replay = Harness::new_with(0, |app| loaded.install_replay(app));
+    replay.play_queued_actions();
+
+    assert_eq!(RunSnapshot::capture(replay.world()), report.snapshot);
+    assert_eq!(replay.world().resource::<ActionLog>().0, report.actions);
+}
+
+#[test]
+fn a_panic_writes_a_report() {
+    let base = temp_dir("panic");
+
+    let context = CrashContext::default();
+    context.install_panic_hook();
+
+    let mut harness = Harness::new_with(23, |app| {
+        app.insert_resource(RunDirectory(Some(base.clone())))
+            .insert_resource(context)
+            .add_plugins(BugReportPlugin)
+            .add_systems(Update, explode);
+    });
+    play_turns(&mut harness, 2);
+    let actions = harness.world().resource::<ActionLog>().0.clone();
+    let tiles = harness.world().resource::<TilePool>().clone();
+
+    harness.world().insert_resource(Explode);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| harness.step()));
+    assert!(result.is_err());
+
+    let run_dir = harness.world().resource::<RunDirectory>().0.clone().unwrap();
+    let report_dir = fs::read_dir(&run_dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| path.file_name().unwrap().to_string_lossy().starts_with("bug-report-"))
+        .expect("no bug report written");
+
+    let report = BugReport::load(&report_dir).unwrap();
+
+    assert!(report.panic.unwrap().contains("boom"));
+    assert_eq!(report.seed, 23);
+    assert_eq!(report.actions, actions);
+    assert_eq!(report.tiles, tiles);
+    assert_eq!(report.snapshot.seed, Some(23));
+    assert!(!report.recent_events.is_empty());
+}
+
+#[test]
+fn a_report_keeps_the_pools_the_run_was_drawn_from() {
+    let tiles = TilePool::from_tiles(generate_tiles().into_iter().filter(|tile| tile.cost < 8).collect());
+    let cards = SpecialCardPool(generate_cards().into_iter().take(3).collect());
+
+    let mut harness = Harness::new_with(24, |app| {
+        app.insert_resource(tiles.clone()).insert_resource(cards.clone());
+    });
+    play_turns(&mut harness, 1);
+
+    let report = BugReport::capture(harness.world());
+    assert_eq!(report.tiles, tiles);
+    assert_eq!(report.special_cards, cards);
+
+    let mut replay = Harness::new_with(0, |app| report.install_replay(app));
+    replay.play_queued_actions();
+
+    assert_eq!(*replay.world().resource::<TilePool>(), tiles);
+    assert_eq!(RunSnapshot::capture(replay.world()), report.snapshot);
+}
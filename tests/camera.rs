@@ -0,0 +1,245 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::PrimaryWindow,
+};
+use common::Harness;
+use treacherous_safari::{
+    camera::BoardCamera,
+    layout::{Layout, LayoutRoot, Zone},
+    tiles::{Player, Tile},
+};
+
+/// A harness with a window whose cursor is over the middle of the board.
+fn with_steady_time(seed: u64) -> Harness {
+    let mut harness = Harness::new_with(seed, |app| {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(1.0 / 60.0)))
+            .world
+            .spawn((Window::default(), PrimaryWindow));
+    });
+
+    let board = harness.world().resource::<Layout>().board.center();
+    move_cursor(&mut harness, board);
+    harness
+}
+
+/// Puts the cursor over `point`, in world units.
+fn move_cursor(harness: &mut Harness, point: Vec2) {
+    let world = harness.world();
+    let size = world.resource::<Layout>().window;
+    let mut window = world.query::<&mut Window>().single_mut(world);
+    window.set_cursor_position(Some(Vec2::new(point.x + size.x / 2.0, size.y / 2.0 - point.y)));
+}
+
+/// The tiles drawn on screen.
+fn shown_tiles(harness: &mut Harness) -> usize {
+    let world = harness.world();
+    world
+        .query_filtered::<&Visibility, With<Tile>>()
+        .iter(world)
+        .filter(|visibility| **visibility != Visibility::Hidden)
+        .count()
+}
+
+fn camera(harness: &mut Harness) -> BoardCamera {
+    harness.world().resource::<BoardCamera>().clone()
+}
+
+fn board_design(harness: &mut Harness) -> Rect {
+    let world = harness.world();
+    world
+        .query::<&LayoutRoot>()
+        .iter(world)
+        .find(|root| root.zone == Zone::Board)
+        .unwrap()
+        .design
+}
+
+/// Where the roots of the cards are on screen.
+fn card_roots(harness: &mut Harness) -> Vec<Vec3> {
+    let world = harness.world();
+    world
+        .query::<(&LayoutRoot, &GlobalTransform)>()
+        .iter(world)
+        .filter(|(root, _)| root.zone != Zone::Board)
+        .map(|(_, transform)| transform.translation())
+        .collect()
+}
+
+fn scroll(harness: &mut Harness, lines: f32) {
+    harness.send(MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: 0.0,
+        y: lines,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+#[test]
+fn the_board_starts_fitted_to_its_zone() {
+    let mut harness = with_steady_time(81);
+    let camera = camera(&mut harness);
+
+    assert_eq!(camera.zoom, 1.0);
+    assert_eq!(camera.center, board_design(&mut harness).center());
+
+    let zone = harness.world().resource::<Layout>().board;
+    let world = harness.world();
+    for transform in world.query_filtered::<&GlobalTransform, With<Tile>>().iter(world) {
+        assert!(zone.contains(transform.translation().truncate()));
+    }
+
+    let tiles = harness.entities::<With<Tile>>().len();
+    assert_eq!(shown_tiles(&mut harness), tiles);
+}
+
+#[test]
+fn zoom_stays_within_its_limits() {
+    let mut harness = with_steady_time(83);
+
+    scroll(&mut harness, 3.0);
+    assert!(camera(&mut harness).zoom > 1.0);
+
+    scroll(&mut harness, 100.0);
+    assert_eq!(camera(&mut harness).zoom, 4.0);
+
+    // Never further out than the whole board.
+    scroll(&mut harness, -100.0);
+    assert_eq!(camera(&mut harness).zoom, 1.0);
+
+    harness.press(KeyCode::Equals);
+    assert!(camera(&mut harness).zoom > 1.0);
+
+    harness.press(KeyCode::Home);
+    assert_eq!(camera(&mut harness).zoom, 1.0);
+}
+
+#[test]
+fn dragging_pans_within_the_board() {
+    let mut harness = with_steady_time(84);
+    scroll(&mut harness, 100.0);
+    let zoomed = camera(&mut harness);
+
+    harness.world().resource_mut::<Input<MouseButton>>().press(MouseButton::Right);
+    harness.send(MouseMotion {
+        delta: Vec2::new(-40.0, 0.0),
+    });
+
+    // Dragging left shows what is to the right.
+    let moved = camera(&mut harness);
+    let expected = zoomed.center + Vec2::new(40.0 / zoomed.scale, 0.0);
+    assert!(moved.center.abs_diff_eq(expected, 0.01));
+
+    harness.send(MouseMotion {
+        delta: Vec2::new(-100_000.0, 100_000.0),
+    });
+
+    let clamped = camera(&mut harness);
+    let design = board_design(&mut harness);
+    let half = harness.world().resource::<Layout>().board.size() / clamped.scale / 2.0;
+    assert!(clamped.center.abs_diff_eq(design.max - half, 0.01));
+}
+
+#[test]
+fn the_cards_stay_put_while_the_board_moves() {
+    let mut harness = with_steady_time(86);
+    let cards = card_roots(&mut harness);
+    let tile = harness.tile(0);
+    let before = harness.world().get::<GlobalTransform>(tile).unwrap().translation();
+
+    scroll(&mut harness, 10.0);
+    harness.world().resource_mut::<Input<MouseButton>>().press(MouseButton::Middle);
+    harness.send(MouseMotion {
+        delta: Vec2::new(30.0, 20.0),
+    });
+
+    let after = harness.world().get::<GlobalTransform>(tile).unwrap().translation();
+    assert_ne!(before, after);
+    assert_eq!(card_roots(&mut harness), cards);
+}
+
+#[test]
+fn the_view_follows_the_player() {
+    let mut harness = with_steady_time(85);
+    scroll(&mut harness, 100.0);
+    harness.flatten_board(1);
+    harness.fill_deck(5);
+    harness.close_tile();
+    harness.finish_turn();
+
+    let player = |harness: &mut Harness| {
+        let world = harness.world();
+        world
+            .query_filtered::<&Transform, With<Player>>()
+            .single(world)
+            .translation
+            .truncate()
+    };
+
+    let before = player(&mut harness);
+    let next = harness.selectable_tiles()[0];
+    let start = camera(&mut harness).center;
+
+    harness.select_tile(next);
+    let after = player(&mut harness);
+    assert_ne!(before, after);
+
+    for _ in 0..120 {
+        harness.step();
+    }
+
+    let camera = camera(&mut harness);
+    assert!(camera.center.distance(after) < start.distance(after));
+}
+
+#[test]
+fn the_wheel_and_dragging_only_count_over_the_board() {
+    let mut harness = with_steady_time(87);
+    let cards = harness.world().resource::<Layout>().special_cards.center();
+    move_cursor(&mut harness, cards);
+
+    scroll(&mut harness, 3.0);
+    assert_eq!(camera(&mut harness).zoom, 1.0);
+
+    let board = harness.world().resource::<Layout>().board.center();
+    move_cursor(&mut harness, board);
+    scroll(&mut harness, 3.0);
+    let zoomed = camera(&mut harness);
+    assert!(zoomed.zoom > 1.0);
+
+    move_cursor(&mut harness, cards);
+    harness.world().resource_mut::<Input<MouseButton>>().press(MouseButton::Right);
+    harness.send(MouseMotion {
+        delta: Vec2::new(-40.0, 0.0),
+    });
+    assert_eq!(camera(&mut harness).center, zoomed.center);
+}
+
+#[test]
+fn tiles_pushed_out_of_the_zone_are_hidden() {
+    let mut harness = with_steady_time(88);
+    harness.close_tile();
+    let tiles = harness.entities::<With<Tile>>().len();
+
+    scroll(&mut harness, 100.0);
+    harness.step();
+    let shown = shown_tiles(&mut harness);
+    assert!(shown > 0 && shown < tiles);
+
+    let zone = harness.world().resource::<Layout>().board;
+    let world = harness.world();
+    for (transform, visibility) in world.query_filtered::<(&GlobalTransform, &Visibility), With<Tile>>().iter(world) {
+        if *visibility != Visibility::Hidden {
+            assert!(zone.contains(transform.translation().truncate()));
+        }
+    }
+
+    harness.press(KeyCode::Home);
+    harness.step();
+    assert_eq!(shown_tiles(&mut harness), tiles);
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{plain_tile, special_card, Harness};
+use treacherous_safari::{
+    movement::{MovementCard, MovementCardDiscarded, MovementCardDrawn},
+    special_cards::{generate_cards, CardType, SpecialCard, SpecialCardDiscarded, SpecialCardPending},
+    tiles::{Tile, TileType},
+};
+
+fn harness() -> Harness {
+    let mut harness = Harness::new(20);
+    harness.flatten_board(100);
+    harness.fill_deck(2);
+    harness.blank_special_cards();
+    harness.close_tile();
+    harness
+}
+
+/// Picks `card` from the board and, unless it is forced, plays it from the hand. Returns its entity.
+fn play(harness: &mut Harness, card: SpecialCard) -> Entity {
+    let entity = harness.board_special_cards()[0];
+    let forced = card.is_forced();
+    harness.set_special_card(entity, card);
+
+    harness.reveal_special_card(entity);
+
+    if !forced {
+        harness.reveal_special_card(entity);
+    }
+
+    entity
+}
+
+fn tile_cost(harness: &mut Harness, number: u32) -> u32 {
+    let entity = harness.tile(number);
+    harness.world().get::<Tile>(entity).unwrap().cost
+}
+
+#[test]
+fn draw_movement_card_adds_to_the_hand() {
+    let mut harness = harness();
+
+    play(&mut harness, special_card(CardType::DrawMovementCard, "<Nice>", 1));
+    assert_eq!(harness.drawn_values().len(), 3);
+
+    harness.confirm_hand();
+    assert_eq!(harness.movement_points(), 6);
+}
+
+#[test]
+fn movement_points_update_applies_before_the_cost_check() {
+    let mut harness = harness();
+
+    play(&mut harness, special_card(CardType::MovementPointsUpdate, "<Nice>", 3));
+    assert_eq!(harness.movement_points(), 0);
+
+    harness.confirm_hand();
+    assert_eq!(harness.movement_points(), 7);
+}
+
+#[test]
+fn turn_update_changes_the_turns_left() {
+    let mut harness = harness();
+
+    play(&mut harness, special_card(CardType::TurnUpdate, "<Nice>", 2));
+    harness.confirm_hand();
+
+    assert_eq!(harness.turns_left(), 8);
+}
+
+#[test]
+fn forced_sub_highest_resolves_when_the_hand_is_played() {
+    let mut harness = harness();
+    harness.set_drawn_values(&[3, 1]);
+
+    let entity = play(
+        &mut harness,
+        special_card(CardType::MovementPointsSubHighest, "<Naughty>", 2),
+    );
+    assert!(harness.world().get::<SpecialCardPending>(entity).is_some());
+
+    harness.confirm_hand();
+
+    assert!(harness.world().get::<SpecialCardDiscarded>(entity).is_some());
+    assert_eq!(harness.movement_points(), 2);
+}
+
+#[test]
+fn multiply_least_card_multiplies_the_lowest_card() {
+    let mut harness = harness();
+    harness.set_drawn_values(&[3, 1]);
+
+    play(
+        &mut harness,
+        special_card(CardType::MovementPointsMultiplyLeastCard, "<Nice>", 3),
+    );
+    let mut values = harness.drawn_values();
+    values.sort();
+    assert_eq!(values, vec![3, 3]);
+
+    harness.confirm_hand();
+    assert_eq!(harness.movement_points(), 6);
+}
+
+#[test]
+fn reduction_all_cards_sets_every_card() {
+    let mut harness = harness();
+    harness.set_drawn_values(&[3, 2]);
+
+    play(
+        &mut harness,
+        special_card(CardType::MovementPointsReductionAllCards, "<Naughty>", 1),
+    );
+    harness.confirm_hand();
+
+    assert_eq!(harness.movement_points(), 2);
+}
+
+#[test]
+fn direct_cost_change_sets_the_current_tile_cost() {
+    let mut harness = harness();
+
+    play(
+        &mut harness,
+        special_card(CardType::CurrentTileCostDirectChange, "<Nice>", 0),
+    );
+    assert_eq!(tile_cost(&mut harness, 0), 0);
+
+    harness.confirm_hand();
+    assert_eq!(harness.selectable_tiles(), vec![1, 2, 3]);
+}
+
+#[test]
+fn negative_direct_cost_change_rolls_a_new_cost() {
+    let mut harness = harness();
+
+    play(
+        &mut harness,
+        special_card(CardType::CurrentTileCostDirectChange, "<Naughty or Nice>", -1),
+    );
+
+    assert!(tile_cost(&mut harness, 0) <= 10);
+}
+
+#[test]
+fn indirect_cost_change_adds_to_the_current_tile_cost() {
+    let mut harness = harness();
+
+    play(
+        &mut harness,
+        special_card(CardType::CurrentTileCostIndirectChange, "<Naughty>", 1),
+    );
+    harness.confirm_hand();
+
+    assert_eq!(tile_cost(&mut harness, 0), 101);
+}
+
+#[test]
+fn erase_clears_the_current_tile_and_its_effects() {
+    let mut harness = Harness::new(21);
+    harness.flatten_board(100);
+    harness.fill_deck(2);
+    harness.blank_special_cards();
+    harness.set_tile(
+        0,
+        Tile {
+            tile_type: TileType::MovementPointsUpdate,
+            value: -1,
+            duration: -1,
+            ..plain_tile(100)
+        },
+    );
+    harness.close_tile();
+
+    play(&mut harness, special_card(CardType::Erase, "<Nice>", 0));
+    harness.confirm_hand();
+
+    let entity = harness.tile(0);
+    let tile = harness.world().get::<Tile>(entity).unwrap().clone();
+    assert_eq!(tile.cost, 0);
+    assert_eq!(tile.tile_type, TileType::Plain);
+    assert_eq!(harness.movement_points(), 3);
+
+    harness.select_tile(1);
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 7);
+}
+
+#[test]
+fn cancel_naughty_discards_the_pending_card() {
+    let mut harness = harness();
+
+    let charm = play(&mut harness, special_card(CardType::CancelNaughty, "<Nice>", 0));
+    assert!(harness.world().get::<SpecialCardDiscarded>(charm).is_none());
+    harness.confirm_hand();
+    assert_eq!(harness.movement_points(), 4);
+
+    let naughty = play(
+        &mut harness,
+        special_card(CardType::MovementPointsSubHighest, "<Naughty>", 2),
+    );
+    harness.reveal_special_card(charm);
+
+    assert!(harness.world().get::<SpecialCardDiscarded>(naughty).is_some());
+    assert!(harness.world().get::<SpecialCardDiscarded>(charm).is_some());
+
+    harness.confirm_hand();
+    assert_eq!(harness.movement_points(), 8);
+}
+
+#[test]
+fn a_board_of_unplayable_naughty_cards_can_still_be_confirmed() {
+    let mut harness = Harness::new(21);
+    harness.flatten_board(100);
+
+    // Scathe takes from the highest drawn card, so with nothing left to draw it has no target.
+    let scathe = generate_cards().into_iter().find(|card| card.name == "Scathe").unwrap();
+    assert!(scathe.is_forced());
+
+    for entity in harness.board_special_cards() {
+        harness.set_special_card(entity, scathe.clone());
+    }
+
+    let deck = harness.entities::<(
+        With<MovementCard>,
+        Without<MovementCardDrawn>,
+        Without<MovementCardDiscarded>,
+    )>();
+    for card in deck {
+        harness.world().entity_mut(card).insert(MovementCardDiscarded);
+    }
+
+    harness.close_tile();
+    assert!(harness.hand().picked_this_turn);
+
+    let turns_left = harness.turns_left();
+    harness.confirm_hand();
+    assert_eq!(harness.turns_left(), turns_left - 1);
+}
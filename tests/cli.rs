@@ -0,0 +1,227 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use common::Harness;
+use treacherous_safari::{
+    board::TilePool,
+    bug_report::BugReport,
+    cli::{CliError, CliOptions},
+    diagnostics::RunSnapshot,
+    game_state::RunSeed,
+    history::MovementRules,
+    replay::{ActionQueue, PlayerAction},
+    settings::{Difficulty, DisplayMode, Settings},
+    special_cards::{generate_cards, SpecialCard},
+    tiles::{generate_tiles, Tile},
+    tween::FastForward,
+};
+
+fn parse(args: &[&str]) -> Result<CliOptions, CliError> {
+    CliOptions::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("safari-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn options_are_read() {
+    let options = parse(&[
+        "--seed",
+        "42",
+        "--preset",
+        "hard",
+        "--window-size",
+        "1600x900",
+        "--fullscreen",
+        "--tiles",
+        "tiles.json",
+    ])
+    .unwrap();
+
+    assert_eq!(options.seed, Some(42));
+    assert_eq!(options.preset, Some(Difficulty::Hard));
+    assert_eq!(options.tiles, Some(PathBuf::from("tiles.json")));
+
+    let mut settings = Settings::default();
+    options.apply_window(&mut settings);
+    assert_eq!((settings.window_width, settings.window_height), (1600, 900));
+    assert_eq!(settings.display_mode, DisplayMode::Fullscreen);
+
+    assert!(parse(&["--help"]).unwrap().help);
+    assert_eq!(parse(&[]).unwrap(), CliOptions::default());
+}
+
+#[test]
+fn mistakes_are_explained() {
+    let message = |args: &[&str]| parse(args).unwrap_err().to_string();
+
+    assert_eq!(message(&["--sed", "1"]), "unknown option --sed");
+    assert_eq!(message(&["--seed"]), "--seed needs a value");
+    assert_eq!(
+        message(&["--seed", "many"]),
+        "bad value for --seed: \"many\", expected a whole number"
+    );
+    assert_eq!(
+        message(&["--preset", "brutal"]),
+        "bad value for --preset: \"brutal\", expected easy, normal, hard"
+    );
+    assert!(message(&["--window-size", "10x10"]).starts_with("bad value for --window-size"));
+    assert_eq!(
+        message(&["--replay", "a.json", "--seed", "3"]),
+        "--seed cannot be used with --replay"
+    );
+    assert_eq!(
+        message(&["--load", "a.json", "--replay", "b.json"]),
+        "--load cannot be used with --replay"
+    );
+    assert_eq!(
+        message(&["--load", "a.json", "--tiles", "tiles.json"]),
+        "--tiles cannot be used with --load"
+    );
+    assert_eq!(
+        message(&["--headless", "--fullscreen"]),
+        "--fullscreen cannot be used with --headless"
+    );
+}
+
+#[test]
+fn seed_and_preset_set_up_the_run() {
+    let options = parse(&["--seed", "77", "--preset", "easy"]).unwrap();
+    let mut harness = Harness::new_with(1, |app| options.install(app).unwrap());
+
+    assert_eq!(harness.world().resource::<RunSeed>().0, 77);
+    assert!(harness.world().resource::<MovementRules>().allow_backward);
+}
+
+#[test]
+fn boards_and_cards_come_from_the_given_files() {
+    let dir = temp_dir("pools");
+
+    // One tile of each cost, all plain, so every board is made of them alone.
+    let tiles: Vec<Tile> = (1..=9)
+        .map(|cost| Tile {
+            cost,
+            description: format!("Plain ground {}", cost),
+            ..Tile::default()
+        })
+        .collect();
+    fs::write(dir.join("tiles.json"), serde_json::to_string(&tiles).unwrap()).unwrap();
+
+    let cards: Vec<SpecialCard> = generate_cards().into_iter().take(1).collect();
+    fs::write(dir.join("cards.json"), serde_json::to_string(&cards).unwrap()).unwrap();
+
+    let tiles_arg = dir.join("tiles.json").display().to_string();
+    let cards_arg = dir.join("cards.json").display().to_string();
+    let options = parse(&["--tiles", &tiles_arg, "--cards", &cards_arg]).unwrap();
+    let mut harness = Harness::new_with(5, |app| options.install(app).unwrap());
+
+    let world = harness.world();
+    let descriptions: Vec<String> = world
+        .query::<&Tile>()
+        .iter(world)
+        .map(|tile| tile.description.clone())
+        .collect();
+    assert!(!descriptions.is_empty());
+    assert!(descriptions.iter().all(|d| d.starts_with("Plain ground")));
+
+    let names: Vec<String> = world
+        .query::<&SpecialCard>()
+        .iter(world)
+        .map(|card| card.name.clone())
+        .collect();
+    assert!(!names.is_empty());
+    assert!(names.iter().all(|name| *name == cards[0].name));
+}
+
+#[test]
+fn unusable_files_are_reported() {
+    let dir = temp_dir("unusable");
+    let mut app = App::new();
+
+    let missing = parse(&["--cards", &dir.join("missing.json").display().to_string()]).unwrap();
+    assert!(missing
+        .install(&mut app)
+        .unwrap_err()
+        .starts_with("could not read special cards from"));
+
+    // Nothing cheap enough for the first column.
+    let dear: Vec<Tile> = generate_tiles().into_iter().filter(|tile| tile.cost > 5).collect();
+    fs::write(dir.join("dear.json"), serde_json::to_string(&dear).unwrap()).unwrap();
+
+    let dear = parse(&["--tiles", &dir.join("dear.json").display().to_string()]).unwrap();
+    let err = dear.install(&mut app).unwrap_err();
+    assert!(err.contains("cannot make a board"), "{}", err);
+}
+
+#[test]
+fn a_replay_queues_the_recorded_run() {
+    let dir = temp_dir("replay");
+    let report = BugReport {
+        seed: 9,
+        actions: vec![PlayerAction::CloseReveal],
+        ..BugReport::default()
+    };
+    let written = report.write(&dir).unwrap();
+
+    let options = parse(&["--replay", &written.display().to_string()]).unwrap();
+    let mut app = App::new();
+    options.install(&mut app).unwrap();
+
+    assert_eq!(app.world.resource::<RunSeed>().0, 9);
+    assert_eq!(
+        app.world.resource::<ActionQueue>().actions,
+        vec![PlayerAction::CloseReveal]
+    );
+}
+
+#[test]
+fn a_loaded_run_catches_up_without_animations_then_plays_on() {
+    let mut played = Harness::new(23);
+    played.close_tile();
+
+    for _ in 0..3 {
+        played.finish_turn();
+
+        if let Some(tile) = played.selectable_tiles().first() {
+            played.select_tile(*tile);
+        }
+    }
+
+    let report = BugReport::capture(played.world());
+    let written = report.write(&temp_dir("load")).unwrap();
+
+    let options = parse(&["--load", &written.display().to_string()]).unwrap();
+    let mut harness = Harness::new_with(0, |app| options.install(app).unwrap());
+    harness.animate();
+    assert!(harness.world().contains_resource::<FastForward>());
+
+    harness.play_queued_actions();
+
+    assert_eq!(RunSnapshot::capture(harness.world()), report.snapshot);
+    assert!(!harness.world().contains_resource::<FastForward>());
+    assert!(!harness.world().resource::<Settings>().skip_animations);
+}
+
+#[test]
+fn the_tiles_are_checked_on_the_seed_of_the_run() {
+    let dir = temp_dir("seeded");
+    fs::write(dir.join("tiles.json"), serde_json::to_string(&generate_tiles()).unwrap()).unwrap();
+
+    let options = parse(&["--seed", "12", "--tiles", &dir.join("tiles.json").display().to_string()]).unwrap();
+    let mut app = App::new();
+    options.install(&mut app).unwrap();
+    assert_eq!(app.world.resource::<RunSeed>().0, 12);
+    assert_eq!(*app.world.resource::<TilePool>(), TilePool::default());
+
+    // Without a seed one is picked, so the board checked is the board played.
+    let options = parse(&["--tiles", &dir.join("tiles.json").display().to_string()]).unwrap();
+    let mut app = App::new();
+    options.install(&mut app).unwrap();
+    assert!(app.world.contains_resource::<RunSeed>());
+}
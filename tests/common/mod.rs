@@ -0,0 +1,314 @@
+#![allow(dead_code)]
+
+use bevy::{
+    ecs::query::ReadOnlyWorldQuery,
+    input::{keyboard::KeyboardInput, ButtonState, InputPlugin},
+    prelude::*,
+};
+use treacherous_safari::{
+    config::ConfigDirectory,
+    game_state::RunSeed,
+    modal::{CloseModal, ModalAnswer},
+    settings::Settings,
+    replay::ActionQueue,
+    movement::{MovementCard, MovementCardDiscarded, MovementCardDrawn, MovementPoints},
+    special_cards::{
+        CardType, OverSpecialCard, SpecialCard, SpecialCardDiscarded, SpecialCardHand,
+        SpecialCardInHand, SpecialCardPending, SpecialCardSelectable, SpecialCardSelected,
+        SpecialHandConfirmed,
+    },
+    tiles::{OverTile, Selectable, Tile, TileSelected, TileType},
+    turns::TurnsLeft,
+    TreacherousSafariPlugin,
+};
+
+/// Frames needed for a chain of events to run through its systems.
+const SETTLE_FRAMES: usize = 5;
+
+/// Runs the game without a window or renderer, driven by the same events pointer input sends.
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    /// A run on `seed`, stopped at the reveal of the first tile.
+    pub fn new(seed: u64) -> Self {
+        Harness::new_with(seed, |_| {})
+    }
+
+    /// Like `new`, with `configure` run on the app before the game is added.
+    pub fn new_with(seed: u64, configure: impl FnOnce(&mut App)) -> Self {
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            InputPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+        ))
+        .init_asset::<Image>()
+        .insert_resource(RunSeed(seed))
+        // Never the player's own bindings or settings.
+        .insert_resource(ConfigDirectory(None));
+
+        configure(&mut app);
+        app.add_plugins(TreacherousSafariPlugin);
+        // Tests count frames, not seconds; see `animate` for the ones about animations.
+        app.world.resource_mut::<Settings>().skip_animations = true;
+
+        app.finish();
+        app.cleanup();
+
+        let mut harness = Harness { app };
+        harness.settle();
+        harness
+    }
+
+    /// Plays animations again, at their normal speed, from here on.
+    pub fn animate(&mut self) {
+        self.app.world.resource_mut::<Settings>().skip_animations = false;
+    }
+
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    pub fn settle(&mut self) {
+        for _ in 0..SETTLE_FRAMES {
+            self.step();
+        }
+    }
+
+    /// Steps until every queued action has been played.
+    pub fn play_queued_actions(&mut self) {
+        while !self.app.world.resource::<ActionQueue>().is_idle() {
+            self.step();
+        }
+
+        self.settle();
+    }
+
+    pub fn send<E: Event>(&mut self, event: E) {
+        self.app.world.send_event(event);
+        self.settle();
+    }
+
+    /// Presses and releases `key`, as a keyboard would.
+    pub fn press(&mut self, key: KeyCode) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            self.app.world.send_event(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key),
+                state,
+                window: Entity::PLACEHOLDER,
+            });
+            self.step();
+        }
+
+        self.settle();
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    pub fn entities<F: ReadOnlyWorldQuery>(&mut self) -> Vec<Entity> {
+        self.app
+            .world
+            .query_filtered::<Entity, F>()
+            .iter(&self.app.world)
+            .collect()
+    }
+
+    pub fn is_visible<M: Component>(&mut self) -> bool {
+        self.app
+            .world
+            .query_filtered::<&Visibility, With<M>>()
+            .iter(&self.app.world)
+            .any(|vis| *vis == Visibility::Visible)
+    }
+
+    pub fn movement_points(&self) -> i32 {
+        self.app.world.resource::<MovementPoints>().0
+    }
+
+    pub fn turns_left(&self) -> i32 {
+        self.app.world.resource::<TurnsLeft>().0
+    }
+
+    pub fn hand(&self) -> &SpecialCardHand {
+        self.app.world.resource::<SpecialCardHand>()
+    }
+
+    pub fn tile(&mut self, number: u32) -> Entity {
+        self.app
+            .world
+            .query::<(Entity, &Tile)>()
+            .iter(&self.app.world)
+            .find(|(_, tile)| tile.number == number)
+            .map(|(entity, _)| entity)
+            .unwrap_or_else(|| panic!("no tile {}", number))
+    }
+
+    pub fn current_tile(&mut self) -> Option<u32> {
+        self.app
+            .world
+            .query::<&Tile>()
+            .iter(&self.app.world)
+            .find(|tile| tile.current)
+            .map(|tile| tile.number)
+    }
+
+    pub fn selectable_tiles(&mut self) -> Vec<u32> {
+        let mut tiles: Vec<u32> = self
+            .app
+            .world
+            .query_filtered::<&Tile, With<Selectable>>()
+            .iter(&self.app.world)
+            .map(|tile| tile.number)
+            .collect();
+        tiles.sort();
+        tiles
+    }
+
+    /// Replaces the tile numbered `number`, keeping its number.
+    pub fn set_tile(&mut self, number: u32, mut tile: Tile) {
+        let entity = self.tile(number);
+        tile.number = number;
+        *self.app.world.get_mut::<Tile>(entity).unwrap() = tile;
+    }
+
+    /// Makes every tile a plain one with the given cost.
+    pub fn flatten_board(&mut self, cost: u32) {
+        let count = self.app.world.query::<&Tile>().iter(&self.app.world).count() as u32;
+
+        for number in 0..count {
+            self.set_tile(number, plain_tile(cost));
+        }
+    }
+
+    /// Gives every movement card left in the deck the same value.
+    pub fn fill_deck(&mut self, value: u32) {
+        let mut query = self.app.world.query_filtered::<&mut MovementCard, (
+            Without<MovementCardDrawn>,
+            Without<MovementCardDiscarded>,
+        )>();
+
+        for mut card in query.iter_mut(&mut self.app.world) {
+            card.value = value;
+        }
+    }
+
+    pub fn drawn_values(&mut self) -> Vec<u32> {
+        self.app
+            .world
+            .query_filtered::<&MovementCard, (With<MovementCardDrawn>, Without<MovementCardDiscarded>)>()
+            .iter(&self.app.world)
+            .map(|card| card.value)
+            .collect()
+    }
+
+    pub fn set_drawn_values(&mut self, values: &[u32]) {
+        let mut query = self.app.world.query_filtered::<&mut MovementCard, (
+            With<MovementCardDrawn>,
+            Without<MovementCardDiscarded>,
+        )>();
+
+        for (mut card, value) in query.iter_mut(&mut self.app.world).zip(values) {
+            card.value = *value;
+        }
+    }
+
+    /// Turns every special card still on the board into one that does nothing, so turns add up predictably.
+    pub fn blank_special_cards(&mut self) {
+        let mut query = self
+            .app
+            .world
+            .query_filtered::<&mut SpecialCard, Without<SpecialCardDiscarded>>();
+
+        for mut card in query.iter_mut(&mut self.app.world) {
+            *card = special_card(CardType::TurnUpdate, "<Nice>", 0);
+        }
+    }
+
+    pub fn board_special_cards(&mut self) -> Vec<Entity> {
+        self.entities::<(
+            With<SpecialCard>,
+            Without<SpecialCardDiscarded>,
+            Without<SpecialCardInHand>,
+            Without<SpecialCardPending>,
+        )>()
+    }
+
+    pub fn set_special_card(&mut self, entity: Entity, card: SpecialCard) {
+        *self.app.world.get_mut::<SpecialCard>(entity).unwrap() = card;
+        self.settle();
+    }
+
+    /// Hovers `number`, confirms moving there, and opens and closes its reveal, as a player clicking it would.
+    pub fn select_tile(&mut self, number: u32) {
+        let entity = self.tile(number);
+        self.send(OverTile(entity));
+        self.send(TileSelected(entity));
+        self.send(CloseModal(ModalAnswer::Confirm));
+        self.close_tile();
+    }
+
+    /// Closes the open tile reveal, which starts the turn.
+    pub fn close_tile(&mut self) {
+        self.send(CloseModal(ModalAnswer::Dismiss));
+    }
+
+    /// Hovers, opens and closes the reveal of a special card on the board or in the hand.
+    pub fn reveal_special_card(&mut self, entity: Entity) {
+        self.send(OverSpecialCard(entity));
+        self.send(SpecialCardSelected(entity));
+        self.send(CloseModal(ModalAnswer::Dismiss));
+    }
+
+    pub fn discard_special_card(&mut self, entity: Entity) {
+        self.send(SpecialCardSelected(entity));
+    }
+
+    pub fn confirm_hand(&mut self) {
+        self.send(SpecialHandConfirmed);
+    }
+
+    /// Picks `entity` from the board, discarding it straight away if the hand is full.
+    pub fn pick_special_card(&mut self, entity: Entity) {
+        self.reveal_special_card(entity);
+
+        if self.hand().discarding {
+            self.discard_special_card(entity);
+        }
+    }
+
+    /// Picks the first selectable special card, if any, and plays the hand.
+    pub fn finish_turn(&mut self) {
+        if let Some(entity) = self.entities::<With<SpecialCardSelectable>>().first() {
+            self.pick_special_card(*entity);
+        }
+
+        self.confirm_hand();
+    }
+}
+
+pub fn plain_tile(cost: u32) -> Tile {
+    Tile {
+        cost,
+        description: String::from("Plain"),
+        tile_type: TileType::Plain,
+        ..default()
+    }
+}
+
+pub fn special_card(card_type: CardType, tag: &str, value: i32) -> SpecialCard {
+    SpecialCard {
+        name: format!("{:?}", card_type),
+        tag: String::from(tag),
+        description: String::new(),
+        value,
+        card_type,
+    }
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "dev")]
+
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use treacherous_safari::{
+    console::{ConsoleCommand, DevConsole},
+    modal::ModalBlocker,
+    navigation::Focus,
+    special_cards::{SpecialCard, SpecialCardInHand, HAND_LIMIT},
+    tiles::{TileCover, VisitedTiles},
+    ui::LedgerPanel,
+};
+
+fn run(harness: &mut Harness, line: &str) {
+    harness.world().resource_mut::<DevConsole>().submit(line);
+    harness.settle();
+}
+
+fn hand(harness: &mut Harness) -> Vec<String> {
+    harness
+        .world()
+        .query_filtered::<&SpecialCard, With<SpecialCardInHand>>()
+        .iter(&harness.app.world)
+        .map(|card| card.name.clone())
+        .collect()
+}
+
+fn run_queued(harness: &mut Harness, line: &str) {
+    run(harness, line);
+    harness.play_queued_actions();
+}
+
+#[test]
+fn parses_commands() {
+    assert_eq!(
+        ConsoleCommand::parse("points +5"),
+        Ok(ConsoleCommand::Points { value: 5, relative: true })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("turns 3"),
+        Ok(ConsoleCommand::Turns { value: 3, relative: false })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("give special Lucky Charm"),
+        Ok(ConsoleCommand::GiveSpecial(String::from("Lucky Charm")))
+    );
+    assert_eq!(ConsoleCommand::parse("goto tile 7"), Ok(ConsoleCommand::GotoTile(7)));
+    assert!(ConsoleCommand::parse("goto tile seven").is_err());
+    assert!(ConsoleCommand::parse("fly").is_err());
+}
+
+#[test]
+fn points_and_turns_go_through_the_update_events() {
+    let mut harness = Harness::new(40);
+    harness.close_tile();
+
+    run(&mut harness, "points +5");
+    assert_eq!(harness.movement_points(), 5);
+
+    run(&mut harness, "points 2");
+    assert_eq!(harness.movement_points(), 2);
+
+    run(&mut harness, "turns 3");
+    assert_eq!(harness.turns_left(), 3);
+
+    run(&mut harness, "turns -1");
+    assert_eq!(harness.turns_left(), 2);
+}
+
+#[test]
+fn reveal_all_hides_every_tile_cover() {
+    let mut harness = Harness::new(41);
+
+    run(&mut harness, "reveal all");
+
+    assert!(!harness.is_visible::<TileCover>());
+}
+
+#[test]
+fn give_special_picks_the_card_into_the_hand() {
+    let mut harness = Harness::new(42);
+    harness.close_tile();
+
+    run_queued(&mut harness, "give special Erase");
+    assert_eq!(hand(&mut harness), vec![String::from("Erase")]);
+
+    // One pick a turn, even from the console.
+    run_queued(&mut harness, "give special Erase");
+    assert_eq!(hand(&mut harness).len(), 1);
+    let output = harness.world().resource::<DevConsole>().output.clone();
+    assert_eq!(output.last().unwrap(), "no special card can be picked now");
+}
+
+#[test]
+fn give_special_asks_for_a_discard_when_the_hand_is_full() {
+    let mut harness = Harness::new(45);
+    let board = harness.board_special_cards();
+
+    for entity in &board[..HAND_LIMIT] {
+        harness.world().entity_mut(*entity).insert(SpecialCardInHand);
+    }
+
+    harness.close_tile();
+    run_queued(&mut harness, "give special Erase");
+
+    assert!(harness.hand().discarding);
+    harness.discard_special_card(board[0]);
+    assert_eq!(hand(&mut harness).len(), HAND_LIMIT);
+    assert!(hand(&mut harness).contains(&String::from("Erase")));
+}
+
+#[test]
+fn goto_tile_moves_to_the_tile_as_a_pick_would() {
+    let mut harness = Harness::new(43);
+    harness.flatten_board(1);
+    harness.fill_deck(5);
+    harness.blank_special_cards();
+    harness.close_tile();
+
+    run(&mut harness, "goto tile 7");
+    let output = harness.world().resource::<DevConsole>().output.clone();
+    assert_eq!(
+        output.last().unwrap(),
+        "tiles can only be gone to while choosing the next one"
+    );
+
+    harness.finish_turn();
+    run_queued(&mut harness, "goto tile 7");
+    harness.close_tile();
+
+    assert_eq!(harness.current_tile(), Some(7));
+    assert_eq!(harness.world().resource::<VisitedTiles>().0, vec![0, 7]);
+}
+
+#[test]
+fn seed_and_dump_state_print_to_the_console() {
+    let mut harness = Harness::new(44);
+
+    run(&mut harness, "seed");
+    run(&mut harness, "dump state");
+
+    let output = harness.world().resource::<DevConsole>().output.clone();
+    assert!(output.contains(&String::from("seed: 44")));
+    assert!(output.iter().any(|line| line.starts_with("turns left:")));
+}
+
+#[test]
+fn keys_typed_into_the_console_are_not_game_input() {
+    let mut harness = Harness::new(46);
+    harness.press(KeyCode::Return);
+    let ledger_shown = harness.is_visible::<LedgerPanel>();
+
+    harness.press(KeyCode::Grave);
+    assert!(harness.world().resource::<DevConsole>().open);
+
+    harness.press(KeyCode::Right);
+    harness.press(KeyCode::L);
+    harness.press(KeyCode::Return);
+
+    assert_eq!(harness.world().resource::<Focus>().0, None);
+    assert_eq!(harness.is_visible::<LedgerPanel>(), ledger_shown);
+    assert!(!harness.is_visible::<ModalBlocker>());
+    assert!(harness.hand().phase_open);
+}
@@ -0,0 +1,136 @@
+use std::time::Instant;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use treacherous_safari::{
+    board::BoardLayout,
+    env::SafariEnv,
+    history::MovementRules,
+    rules::{Action, Phase, RulesError},
+};
+
+fn env() -> SafariEnv {
+    SafariEnv::new(BoardLayout::default(), MovementRules::default()).unwrap()
+}
+
+/// Plays random legal actions until the run ends, returning the final reward and the steps taken.
+fn random_episode(env: &mut SafariEnv, seed: u64, rng: &mut StdRng) -> (f32, usize) {
+    env.reset(seed).unwrap();
+    let mut steps = 0;
+
+    loop {
+        let action = *env.legal_actions().choose(rng).expect("no legal action before the end");
+        let step = env.step(action).unwrap();
+        steps += 1;
+
+        assert_eq!(step.observation.len(), env.observation_len());
+
+        if step.done {
+            return (step.reward, steps);
+        }
+    }
+}
+
+#[test]
+fn reset_starts_on_the_first_tile() {
+    let mut env = env();
+    let obs = env.reset(5).unwrap();
+    let run = env.run();
+
+    assert_eq!(obs.len(), env.observation_len());
+    assert_eq!(run.current, 0);
+    assert_eq!(run.visited, vec![0]);
+    assert_eq!(run.turns_left, 7);
+    assert_eq!(run.drawn.len(), 2);
+    assert_eq!(run.phase, Phase::SpecialCards);
+    assert!(!env.legal_actions().is_empty());
+}
+
+#[test]
+fn the_same_seed_plays_the_same() {
+    let mut first = env();
+    let mut second = env();
+
+    assert_eq!(first.reset(9).unwrap(), second.reset(9).unwrap());
+
+    while !first.run().is_over() {
+        let action = first.legal_actions()[0];
+        assert_eq!(first.step(action).unwrap(), second.step(action).unwrap());
+    }
+}
+
+#[test]
+fn illegal_actions_are_rejected() {
+    let mut env = env();
+    env.reset(3).unwrap();
+
+    assert_eq!(
+        env.step(Action::MoveTo(10)),
+        Err(RulesError::Illegal(Action::MoveTo(10)))
+    );
+    assert_eq!(env.step(Action::ConfirmHand), Err(RulesError::Illegal(Action::ConfirmHand)));
+}
+
+#[test]
+fn action_indices_round_trip() {
+    let env = env();
+
+    for index in 0..env.action_count() {
+        let action = env.action_from_index(index).unwrap();
+        assert_eq!(env.action_index(action), index);
+    }
+
+    assert_eq!(env.action_from_index(env.action_count()), None);
+}
+
+#[test]
+fn the_mask_matches_the_legal_actions() {
+    let mut env = env();
+    env.reset(4).unwrap();
+
+    let mask = env.action_mask();
+    let legal: Vec<Action> = (0..env.action_count())
+        .filter(|i| mask[*i])
+        .map(|i| env.action_from_index(i).unwrap())
+        .collect();
+
+    assert_eq!(legal.len(), env.legal_actions().len());
+    assert!(legal.iter().all(|a| env.legal_actions().contains(a)));
+}
+
+#[test]
+fn random_play_always_ends() {
+    let mut env = env();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut steps = 0;
+    let mut wins = 0;
+
+    for seed in 0..2000 {
+        let (reward, taken) = random_episode(&mut env, seed, &mut rng);
+        steps += taken;
+
+        if reward > 0.0 {
+            wins += 1;
+            assert_eq!(env.run().current, env.layout.last_tile());
+        } else {
+            assert_eq!(env.run().phase, Phase::Lost);
+        }
+    }
+
+    assert!(steps > 10_000);
+    assert!(wins > 0);
+}
+
+#[test]
+fn thousands_of_steps_run_every_second() {
+    let mut env = env();
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut steps = 0;
+    let started = Instant::now();
+
+    for seed in 0..500 {
+        steps += random_episode(&mut env, seed, &mut rng).1;
+    }
+
+    let rate = steps as f64 / started.elapsed().as_secs_f64();
+    assert!(rate > 2000.0, "only {:.0} steps a second", rate);
+}
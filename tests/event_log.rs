@@ -0,0 +1,118 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::Harness;
+use serde_json::Value;
+use treacherous_safari::event_log::{GameplayLog, RunDirectory};
+
+fn logged_harness(seed: u64, name: &str) -> Harness {
+    let base = std::env::temp_dir().join(format!("safari-event-log-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+
+    Harness::new_with(seed, |app| {
+        app.insert_resource(RunDirectory(Some(base)));
+    })
+}
+
+fn log_lines(harness: &mut Harness) -> Vec<Value> {
+    let path: PathBuf = harness
+        .world()
+        .resource::<GameplayLog>()
+        .path
+        .clone()
+        .expect("no event log opened");
+
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn events(lines: &[Value]) -> Vec<&str> {
+    lines.iter().map(|line| line["event"].as_str().unwrap()).collect()
+}
+
+#[test]
+fn a_turn_is_logged_in_order() {
+    let mut harness = logged_harness(11, "turn");
+    harness.flatten_board(1);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+
+    let card = harness.board_special_cards()[0];
+    harness.pick_special_card(card);
+    harness.reveal_special_card(card);
+    harness.confirm_hand();
+
+    let lines = log_lines(&mut harness);
+
+    assert_eq!(
+        events(&lines),
+        vec![
+            "moved",
+            "tile_revealed",
+            "card_drawn",
+            "card_drawn",
+            "special_card_picked",
+            "special_card_played",
+            "cost_check",
+        ]
+    );
+
+    for line in &lines {
+        assert_eq!(line["seed"], 11);
+        assert_eq!(line["turn"], 1);
+        assert!(line["phase"].is_string());
+    }
+
+    assert_eq!(lines[0]["from"], Value::Null);
+    assert_eq!(lines[0]["to"], 0);
+    assert_eq!(lines[6]["met"], true);
+    assert_eq!(lines[6]["movement_points"], 2);
+}
+
+#[test]
+fn the_end_of_a_run_is_logged() {
+    let mut harness = logged_harness(12, "end");
+    harness.flatten_board(1);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+
+    for tile in [1, 4, 7, 10] {
+        harness.finish_turn();
+        harness.select_tile(tile);
+    }
+
+    harness.finish_turn();
+
+    let lines = log_lines(&mut harness);
+    let last = lines.last().unwrap();
+
+    assert_eq!(last["event"], "game_ended");
+    assert_eq!(last["won"], true);
+    assert_eq!(last["turns_left"], 2);
+    assert_eq!(last["turn"], 5);
+
+    let moves: Vec<(Value, Value)> = lines
+        .iter()
+        .filter(|line| line["event"] == "moved")
+        .map(|line| (line["from"].clone(), line["to"].clone()))
+        .collect();
+
+    assert_eq!(moves.len(), 5);
+    assert_eq!(moves[4], (Value::from(7), Value::from(10)));
+}
+
+#[test]
+fn nothing_is_written_without_a_run_directory() {
+    let mut harness = Harness::new(13);
+    harness.close_tile();
+
+    assert!(harness.world().resource::<GameplayLog>().path.is_none());
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use treacherous_safari::{
+    diagnostics::InvariantViolations,
+    movement::{MovementCard, MovementCardDiscarded, MovementCardDrawn},
+    tiles::{Player, Tile, VisitedTiles},
+};
+
+fn violations(harness: &mut Harness) -> Vec<String> {
+    harness.world().resource::<InvariantViolations>().0.clone()
+}
+
+#[test]
+fn a_normal_turn_breaks_no_invariants() {
+    let mut harness = Harness::new(30);
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    assert!(violations(&mut harness).is_empty());
+}
+
+#[test]
+fn a_whole_run_breaks_no_invariants() {
+    let mut harness = Harness::new(32);
+    harness.flatten_board(1);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    // Five turns draw ten of the deck's fifteen movement cards.
+    for number in [1, 4, 7, 10] {
+        harness.select_tile(number);
+        harness.finish_turn();
+    }
+
+    assert_eq!(harness.world().resource::<VisitedTiles>().0, vec![0, 1, 4, 7, 10]);
+    assert!(violations(&mut harness).is_empty());
+}
+
+#[test]
+fn a_second_player_is_reported_at_the_next_phase_change() {
+    let mut harness = Harness::new(31);
+    harness.world().spawn(Player);
+    harness.settle();
+    assert!(violations(&mut harness).is_empty());
+
+    harness.close_tile();
+
+    let violations = violations(&mut harness);
+    assert!(!violations.is_empty());
+    assert!(violations.iter().all(|v| v == "expected one Player, found 2"));
+}
+
+#[test]
+fn a_card_both_drawn_and_discarded_is_reported() {
+    let mut harness = Harness::new(33);
+    harness.close_tile();
+
+    let drawn = harness.entities::<(With<MovementCardDrawn>, Without<MovementCardDiscarded>)>();
+    harness.world().entity_mut(drawn[0]).insert(MovementCardDiscarded);
+    harness.finish_turn();
+
+    assert!(violations(&mut harness)
+        .iter()
+        .any(|v| v == "1 movement cards both drawn and discarded"));
+}
+
+#[test]
+fn a_lost_card_is_reported() {
+    let mut harness = Harness::new(34);
+    harness.close_tile();
+
+    let deck = harness.entities::<(
+        With<MovementCard>,
+        Without<MovementCardDrawn>,
+        Without<MovementCardDiscarded>,
+    )>();
+    harness.world().despawn(deck[0]);
+    harness.finish_turn();
+
+    assert!(violations(&mut harness)
+        .iter()
+        .any(|v| v == "0 drawn, 2 discarded and 12 in the deck from a deck of 15"));
+}
+
+#[test]
+fn losing_the_current_tile_mid_turn_is_reported() {
+    let mut harness = Harness::new(35);
+    harness.close_tile();
+
+    let tile = harness.tile(0);
+    harness.world().get_mut::<Tile>(tile).unwrap().current = false;
+    harness.finish_turn();
+
+    assert!(violations(&mut harness)
+        .iter()
+        .any(|v| v == "expected one current tile, found 0"));
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+use common::Harness;
+use treacherous_safari::{
+    board::BoardLayout,
+    layout::{Layout, LayoutRoot, Zone, REFERENCE_SIZE},
+    tiles::{board_root, Tile},
+};
+
+fn overlaps(a: Rect, b: Rect) -> bool {
+    !a.intersect(b).is_empty()
+}
+
+fn resize(harness: &mut Harness, width: f32, height: f32) {
+    let world = harness.world();
+    let window = world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .iter(world)
+        .next()
+        .unwrap_or_else(|| world.spawn((Window::default(), PrimaryWindow)).id());
+
+    world.send_event(WindowResized {
+        window,
+        width,
+        height,
+    });
+    harness.settle();
+}
+
+/// Where the tiles are on screen.
+fn board_bounds(harness: &mut Harness) -> Rect {
+    let world = harness.world();
+    let mut bounds = Rect::from_center_size(Vec2::ZERO, Vec2::ZERO);
+    let mut first = true;
+
+    for transform in world.query_filtered::<&GlobalTransform, With<Tile>>().iter(world) {
+        let position = transform.translation().truncate();
+        bounds = if first {
+            Rect::from_center_size(position, Vec2::ZERO)
+        } else {
+            bounds.union_point(position)
+        };
+        first = false;
+    }
+
+    bounds
+}
+
+#[test]
+fn zones_share_the_window_without_overlapping() {
+    for size in [REFERENCE_SIZE, Vec2::new(2560.0, 1080.0), Vec2::new(800.0, 1280.0)] {
+        let layout = Layout::for_window(size);
+        let window = Rect::from_center_size(Vec2::ZERO, size);
+        let zones = [layout.special_cards, layout.board, layout.movement_cards];
+
+        for (i, zone) in zones.iter().enumerate() {
+            assert!(!zone.is_empty());
+            assert_eq!(window.union(*zone), window, "{:?} leaves the window", zone);
+            assert!(!overlaps(*zone, layout.hud), "{:?} is under the HUD", zone);
+
+            for other in &zones[i + 1..] {
+                assert!(!overlaps(*zone, *other));
+            }
+        }
+    }
+
+    assert_eq!(Layout::default().scale, 1.0);
+    assert_eq!(Layout::for_window(REFERENCE_SIZE * 2.0).scale, 2.0);
+}
+
+#[test]
+fn a_group_fits_its_zone_keeping_its_shape() {
+    let layout = Layout::default();
+    let design = Rect::new(-100.0, -50.0, 300.0, 150.0);
+
+    let (scale, offset) = layout.fit(Zone::Board, design);
+    let placed = Rect::from_corners(design.min * scale + offset, design.max * scale + offset);
+    let zone = layout.board;
+
+    assert!(zone.union(placed).size().abs_diff_eq(zone.size(), 0.01));
+    assert!(placed.center().abs_diff_eq(zone.center(), 0.01));
+    // The tighter side fills the zone.
+    assert!(
+        (placed.width() - zone.width()).abs() < 0.01 || (placed.height() - zone.height()).abs() < 0.01
+    );
+}
+
+#[test]
+fn the_board_follows_the_window_size() {
+    let mut harness = Harness::new(71);
+
+    let world = harness.world();
+    let roots: Vec<Zone> = world.query::<&LayoutRoot>().iter(world).map(|root| root.zone).collect();
+    assert!(roots.contains(&Zone::Board));
+    assert!(roots.contains(&Zone::SpecialCards));
+    assert!(roots.contains(&Zone::MovementCards));
+
+    let small = board_bounds(&mut harness);
+    assert_eq!(Layout::default().board.union(small), Layout::default().board);
+
+    resize(&mut harness, 2560.0, 1440.0);
+
+    let layout = harness.world().resource::<Layout>().clone();
+    assert_eq!(layout.window, Vec2::new(2560.0, 1440.0));
+
+    let large = board_bounds(&mut harness);
+    assert_eq!(layout.board.union(large), layout.board);
+    assert!(large.width() > small.width() * 1.9);
+
+    assert_eq!(harness.world().resource::<UiScale>().0, 2.0);
+}
+
+#[test]
+fn a_bigger_board_is_fitted_smaller() {
+    let mut columns = vec![vec![1]];
+    columns.extend(vec![vec![0, 1, 2]; 5]);
+    columns.push(vec![1]);
+    let bigger = BoardLayout { columns };
+
+    let usual = board_root(&BoardLayout::default());
+    let root = board_root(&bigger);
+    assert!(root.design.width() > usual.design.width() * 1.3);
+    assert_eq!(root.design.height(), usual.design.height());
+
+    let mut harness = Harness::new_with(72, |app| {
+        app.insert_resource(bigger);
+    });
+
+    let world = harness.world();
+    let designs: Vec<Rect> = world
+        .query::<&LayoutRoot>()
+        .iter(world)
+        .filter(|root| root.zone == Zone::Board)
+        .map(|root| root.design)
+        .collect();
+    assert!(designs.iter().all(|design| *design == root.design));
+
+    assert_eq!(harness.entities::<With<Tile>>().len(), 17);
+
+    let layout = Layout::default();
+    let bounds = board_bounds(&mut harness);
+    assert_eq!(layout.board.union(bounds), layout.board);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{plain_tile, special_card, Harness};
+use treacherous_safari::{
+    history::RunSummary,
+    ledger::{Cause, Ledger, LedgerEntry, Tally},
+    special_cards::CardType,
+    tiles::{Tile, TileType},
+};
+
+fn ledger(harness: &mut Harness) -> Vec<LedgerEntry> {
+    harness.world().resource::<Ledger>().entries.clone()
+}
+
+#[test]
+fn every_change_is_recorded_with_its_cause() {
+    let mut harness = Harness::new(50);
+    harness.flatten_board(100);
+    harness.fill_deck(2);
+    harness.blank_special_cards();
+    harness.set_tile(
+        0,
+        Tile {
+            tile_type: TileType::MovementPointsUpdate,
+            value: -2,
+            duration: 1,
+            ..plain_tile(100)
+        },
+    );
+
+    harness.close_tile();
+
+    let entity = harness.board_special_cards()[0];
+    harness.set_special_card(entity, special_card(CardType::MovementPointsUpdate, "<Nice>", 3));
+    harness.reveal_special_card(entity);
+    harness.reveal_special_card(entity);
+    harness.confirm_hand();
+
+    let turn_one: Vec<(Tally, i32, Cause)> = ledger(&mut harness)
+        .into_iter()
+        .filter(|entry| entry.turn == 1)
+        .map(|entry| match entry.cause {
+            Cause::MovementCard(_) => (entry.tally, entry.amount, Cause::MovementCard(String::new())),
+            cause => (entry.tally, entry.amount, cause),
+        })
+        .collect();
+
+    assert_eq!(
+        turn_one,
+        vec![
+            (Tally::Turns, 7, Cause::Start),
+            (Tally::MovementPoints, -2, Cause::Affliction(0)),
+            (Tally::MovementPoints, 2, Cause::MovementCard(String::new())),
+            (Tally::MovementPoints, 2, Cause::MovementCard(String::new())),
+            (
+                Tally::MovementPoints,
+                3,
+                Cause::SpecialCard(String::from("MovementPointsUpdate"))
+            ),
+            (Tally::Turns, -1, Cause::TurnTick),
+        ]
+    );
+    assert_eq!(harness.movement_points(), 5);
+}
+
+#[test]
+fn run_summary_totals_points_by_cause() {
+    let mut harness = Harness::new(51);
+    harness.flatten_board(1);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    for number in [1, 4, 7, 10] {
+        harness.select_tile(number);
+        harness.finish_turn();
+    }
+
+    let summary = harness.world().resource::<RunSummary>().clone();
+    assert!(summary.won);
+    assert!(summary.lines().contains(&String::from("  Movement cards: +10")));
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use treacherous_safari::{
+    modal::{
+        CloseModal, Modal, ModalAnswer, ModalBlocker, ModalButton, ModalButtonPressed,
+        ModalCloseButton, ModalClosed, ModalContent, ModalPanel, OpenModal,
+    },
+    tiles::{OverTile, TileSelected},
+};
+
+#[derive(Resource, Default)]
+struct Answers(Vec<ModalAnswer>);
+
+fn record_answers(mut events: EventReader<ModalClosed>, mut answers: ResMut<Answers>) {
+    answers.0.extend(events.read().map(|ev| ev.answer));
+}
+
+/// A run past its first tile, with no modal open and the answers to any recorded.
+fn answering(seed: u64) -> Harness {
+    let mut harness = Harness::new_with(seed, |app| {
+        app.init_resource::<Answers>()
+            .add_systems(Update, record_answers);
+    });
+    harness.close_tile();
+    harness.world().resource_mut::<Answers>().0.clear();
+    harness
+}
+
+fn shown(harness: &mut Harness) -> Option<ModalContent> {
+    harness.world().resource::<Modal>().content().cloned()
+}
+
+fn answers(harness: &mut Harness) -> Vec<ModalAnswer> {
+    harness.world().resource::<Answers>().0.clone()
+}
+
+fn confirmation() -> OpenModal {
+    OpenModal(ModalContent::Confirmation(String::from("Sure?")))
+}
+
+#[test]
+fn a_tile_is_shown_larger_until_closed() {
+    let mut harness = Harness::new(51);
+    let tile = harness.tile(0);
+
+    assert_eq!(shown(&mut harness), Some(ModalContent::TileDetails(tile)));
+    assert!(harness.world().get::<Transform>(tile).unwrap().scale.x > 2.0);
+    assert!(harness.is_visible::<ModalBlocker>());
+    assert!(harness.is_visible::<ModalCloseButton>());
+    assert!(!harness.is_visible::<ModalPanel>());
+
+    harness.close_tile();
+
+    assert_eq!(shown(&mut harness), None);
+    assert_eq!(harness.world().get::<Transform>(tile).unwrap().scale.x, 1.0);
+    assert!(!harness.is_visible::<ModalBlocker>());
+    assert_eq!(harness.current_tile(), Some(0));
+}
+
+#[test]
+fn a_confirmation_is_answered_with_its_buttons() {
+    let mut harness = answering(52);
+    harness.send(confirmation());
+
+    assert!(harness.is_visible::<ModalPanel>());
+    assert!(!harness.is_visible::<ModalCloseButton>());
+
+    let world = harness.world();
+    let confirm = world
+        .query::<(Entity, &ModalButton)>()
+        .iter(world)
+        .find(|(_, button)| button.0 == ModalAnswer::Confirm)
+        .map(|(entity, _)| entity)
+        .expect("no confirm button");
+
+    harness.send(ModalButtonPressed(confirm));
+
+    assert_eq!(shown(&mut harness), None);
+    assert!(!harness.is_visible::<ModalBlocker>());
+    assert_eq!(answers(&mut harness), vec![ModalAnswer::Confirm]);
+    assert!(harness.entities::<With<ModalButton>>().is_empty());
+}
+
+#[test]
+fn a_choice_gets_a_button_for_each_option() {
+    let mut harness = answering(53);
+    harness.send(OpenModal(ModalContent::Choice {
+        prompt: String::from("Which way?"),
+        choices: vec![String::from("Left"), String::from("Right"), String::from("Back")],
+    }));
+
+    assert_eq!(harness.entities::<With<ModalButton>>().len(), 3);
+
+    harness.send(CloseModal(ModalAnswer::Choose(1)));
+    assert_eq!(answers(&mut harness), vec![ModalAnswer::Choose(1)]);
+}
+
+#[test]
+fn the_keys_confirm_or_cancel_a_question() {
+    let mut harness = answering(54);
+
+    harness.send(confirmation());
+    harness.press(KeyCode::Back);
+    harness.send(confirmation());
+    harness.press(KeyCode::Return);
+
+    assert_eq!(answers(&mut harness), vec![ModalAnswer::Cancel, ModalAnswer::Confirm]);
+}
+
+#[test]
+fn only_one_modal_is_open_at_a_time() {
+    let mut harness = answering(55);
+    harness.send(confirmation());
+    harness.send(OpenModal(ModalContent::Confirmation(String::from("Really?"))));
+
+    assert_eq!(
+        shown(&mut harness),
+        Some(ModalContent::Confirmation(String::from("Sure?")))
+    );
+
+    harness.send(CloseModal(ModalAnswer::Dismiss));
+    harness.send(CloseModal(ModalAnswer::Dismiss));
+    assert_eq!(answers(&mut harness), vec![ModalAnswer::Dismiss]);
+}
+
+#[test]
+fn nothing_underneath_can_be_picked_while_a_modal_is_open() {
+    let mut harness = Harness::new(56);
+    harness.blank_special_cards();
+    harness.flatten_board(1);
+    harness.close_tile();
+    harness.fill_deck(5);
+    harness.finish_turn();
+
+    let number = harness.selectable_tiles()[0];
+    let tile = harness.tile(number);
+    harness.send(confirmation());
+    harness.send(OverTile(tile));
+    harness.send(TileSelected(tile));
+
+    assert_eq!(
+        shown(&mut harness),
+        Some(ModalContent::Confirmation(String::from("Sure?")))
+    );
+}
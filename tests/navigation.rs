@@ -0,0 +1,164 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use treacherous_safari::{
+    navigation::Focus,
+    modal::{Modal, ModalBlocker, ModalContent},
+    special_cards::{SpecialCardInHand, SpecialCardSelectable},
+    tiles::{Focused, OffTile, OverTile, Selectable, TileSelected},
+};
+
+fn focus(harness: &mut Harness) -> Option<Entity> {
+    harness.world().resource::<Focus>().0
+}
+
+fn shown(harness: &mut Harness) -> Option<ModalContent> {
+    harness.world().resource::<Modal>().content().cloned()
+}
+
+fn scale(harness: &mut Harness, entity: Entity) -> f32 {
+    harness.world().get::<Transform>(entity).unwrap().scale.x
+}
+
+fn x(harness: &mut Harness, entity: Entity) -> f32 {
+    harness.world().get::<GlobalTransform>(entity).unwrap().translation().x
+}
+
+#[test]
+fn confirm_closes_the_open_reveal() {
+    let mut harness = Harness::new(41);
+    assert!(harness.is_visible::<ModalBlocker>());
+    assert!(matches!(shown(&mut harness), Some(ModalContent::TileDetails(_))));
+
+    harness.press(KeyCode::Return);
+
+    assert!(!harness.is_visible::<ModalBlocker>());
+    assert!(harness.hand().phase_open);
+}
+
+#[test]
+fn arrows_move_the_highlight_between_cards() {
+    let mut harness = Harness::new(42);
+    harness.press(KeyCode::Return);
+
+    let cards = harness.entities::<With<SpecialCardSelectable>>();
+    let resting = scale(&mut harness, cards[0]);
+
+    harness.press(KeyCode::Right);
+    let first = focus(&mut harness).expect("nothing focused");
+    assert!(cards.contains(&first));
+    assert!(scale(&mut harness, first) > resting);
+
+    harness.press(KeyCode::Right);
+    let second = focus(&mut harness).unwrap();
+    assert_ne!(second, first);
+    assert!(x(&mut harness, second) > x(&mut harness, first));
+    assert!(scale(&mut harness, second) > resting);
+    assert_eq!(scale(&mut harness, first), resting);
+
+    harness.press(KeyCode::Left);
+    assert_eq!(focus(&mut harness), Some(first));
+    assert_eq!(scale(&mut harness, second), resting);
+}
+
+#[test]
+fn cancel_drops_the_focus() {
+    let mut harness = Harness::new(43);
+    harness.press(KeyCode::Return);
+
+    harness.press(KeyCode::Down);
+    let focused = focus(&mut harness).unwrap();
+    let highlighted = scale(&mut harness, focused);
+
+    harness.press(KeyCode::Back);
+
+    assert_eq!(focus(&mut harness), None);
+    assert!(scale(&mut harness, focused) < highlighted);
+}
+
+#[test]
+fn a_turn_can_be_played_from_the_keyboard() {
+    let mut harness = Harness::new(44);
+    harness.blank_special_cards();
+    harness.press(KeyCode::Return);
+
+    // Pick a card from the board, close its reveal, then play the hand.
+    harness.press(KeyCode::Right);
+    harness.press(KeyCode::Return);
+    assert!(harness.is_visible::<ModalBlocker>());
+    assert!(matches!(shown(&mut harness), Some(ModalContent::CardDetails(_))));
+
+    harness.press(KeyCode::Return);
+    assert_eq!(harness.entities::<With<SpecialCardInHand>>().len(), 1);
+
+    harness.press(KeyCode::Return);
+    assert!(!harness.hand().phase_open);
+}
+
+#[test]
+fn tiles_can_be_picked_from_the_keyboard() {
+    let mut harness = Harness::new(45);
+    harness.flatten_board(1);
+    harness.close_tile();
+    harness.fill_deck(5);
+    harness.finish_turn();
+
+    assert!(!harness.selectable_tiles().is_empty());
+
+    harness.press(KeyCode::Up);
+    let tile = focus(&mut harness).expect("no tile focused");
+    assert!(harness.world().get::<Selectable>(tile).is_some());
+
+    harness.press(KeyCode::Return);
+    assert!(harness.is_visible::<ModalBlocker>());
+    assert!(matches!(shown(&mut harness), Some(ModalContent::Confirmation(_))));
+    assert_eq!(focus(&mut harness), None);
+
+    harness.press(KeyCode::Return);
+    assert_eq!(shown(&mut harness), Some(ModalContent::TileDetails(tile)));
+}
+
+#[test]
+fn a_tile_under_the_pointer_and_the_focus_is_drawn_larger_once() {
+    let mut harness = Harness::new(46);
+    harness.flatten_board(1);
+    harness.close_tile();
+    harness.fill_deck(5);
+    harness.finish_turn();
+
+    harness.press(KeyCode::Up);
+    let tile = focus(&mut harness).expect("no tile focused");
+    let highlighted = scale(&mut harness, tile);
+
+    // The pointer comes over it too.
+    harness.send(OverTile(tile));
+    assert_eq!(scale(&mut harness, tile), highlighted);
+
+    harness.send(OffTile(tile));
+    assert_eq!(scale(&mut harness, tile), 1.0);
+    assert!(harness.world().get::<Focused>(tile).is_none());
+
+    // With neither on it, it cannot be picked.
+    harness.send(TileSelected(tile));
+    assert!(!harness.is_visible::<ModalBlocker>());
+}
+
+#[test]
+fn the_focus_leaves_a_tile_that_can_no_longer_be_picked() {
+    let mut harness = Harness::new(47);
+    harness.flatten_board(1);
+    harness.close_tile();
+    harness.fill_deck(5);
+    harness.finish_turn();
+
+    harness.press(KeyCode::Up);
+    let tile = focus(&mut harness).expect("no tile focused");
+
+    harness.world().entity_mut(tile).remove::<Selectable>();
+    harness.settle();
+
+    assert_eq!(focus(&mut harness), None);
+    assert_eq!(scale(&mut harness, tile), 1.0);
+    assert!(harness.world().get::<Focused>(tile).is_none());
+}
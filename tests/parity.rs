@@ -0,0 +1,110 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use treacherous_safari::{
+    board::BoardLayout,
+    history::MovementRules,
+    rules::{Action, CardPlace, Phase, Run},
+    special_cards::{SpecialCardDiscarded, SpecialCardInHand, SpecialCardPending, SpecialCardSlot},
+    tiles::{Tile, VisitedTiles},
+    ui::{GameOverText, WonText},
+};
+
+/// Plays `action` in the game the way a player would.
+fn play(harness: &mut Harness, action: Action) {
+    match action {
+        Action::MoveTo(tile) => harness.select_tile(tile),
+        Action::PickSpecial(slot) | Action::PlaySpecial(slot) => {
+            let entity = special_card(harness, slot);
+            harness.reveal_special_card(entity);
+        }
+        Action::DiscardSpecial(slot) => {
+            let entity = special_card(harness, slot);
+            harness.discard_special_card(entity);
+        }
+        Action::ConfirmHand => harness.confirm_hand(),
+    }
+}
+
+fn special_card(harness: &mut Harness, slot: usize) -> Entity {
+    harness
+        .world()
+        .query::<(Entity, &SpecialCardSlot)>()
+        .iter(&harness.app.world)
+        .find(|(_, s)| s.0 == slot)
+        .map(|(entity, _)| entity)
+        .unwrap_or_else(|| panic!("no special card in slot {}", slot))
+}
+
+fn place(harness: &mut Harness, slot: usize) -> CardPlace {
+    let entity = special_card(harness, slot);
+    let world = harness.world();
+
+    if world.get::<SpecialCardDiscarded>(entity).is_some() {
+        CardPlace::Discarded
+    } else if world.get::<SpecialCardInHand>(entity).is_some() {
+        CardPlace::Hand
+    } else if world.get::<SpecialCardPending>(entity).is_some() {
+        CardPlace::Pending
+    } else {
+        CardPlace::Board
+    }
+}
+
+/// Checks that the game shows what the rules core says, naming the step on a mismatch.
+fn assert_same(harness: &mut Harness, run: &Run, step: &str) {
+    assert_eq!(harness.movement_points(), run.movement_points, "points after {}", step);
+    assert_eq!(harness.turns_left(), run.turns_left, "turns after {}", step);
+    assert_eq!(
+        harness.world().resource::<VisitedTiles>().0,
+        run.visited,
+        "path after {}",
+        step
+    );
+
+    let mut drawn = harness.drawn_values();
+    let mut expected: Vec<u32> = run.drawn.iter().map(|card| card.value).collect();
+    drawn.sort();
+    expected.sort();
+    assert_eq!(drawn, expected, "drawn cards after {}", step);
+
+    for slot in 0..run.special_cards.len() {
+        assert_eq!(place(harness, slot), run.special_cards[slot].1, "slot {} after {}", slot, step);
+    }
+
+    for tile in &run.tiles {
+        let entity = harness.tile(tile.number);
+        let cost = harness.world().get::<Tile>(entity).unwrap().cost;
+        assert_eq!(cost, tile.cost, "cost of tile {} after {}", tile.number, step);
+    }
+
+    assert_eq!(harness.is_visible::<WonText>(), run.phase == Phase::Won, "win after {}", step);
+    assert_eq!(harness.is_visible::<GameOverText>(), run.phase == Phase::Lost, "loss after {}", step);
+}
+
+/// Plays random legal actions of the rules core on `seed` in both, until the run ends.
+fn play_both(seed: u64) {
+    let mut run = Run::new(seed, BoardLayout::default(), MovementRules::default()).unwrap();
+    let mut harness = Harness::new(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    harness.close_tile();
+    assert_same(&mut harness, &run, "the first tile");
+
+    while !run.is_over() {
+        let action = *run.legal_actions().choose(&mut rng).unwrap();
+        run.apply(action).unwrap();
+        play(&mut harness, action);
+
+        assert_same(&mut harness, &run, &format!("{:?} on seed {}", action, seed));
+    }
+}
+
+#[test]
+fn the_game_and_the_rules_core_play_the_same() {
+    for seed in 0..16 {
+        play_both(seed);
+    }
+}
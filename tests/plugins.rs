@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use treacherous_safari::{
+    config::ConfigDirectory,
+    game_state::RunSeed,
+    modal::{Modal, ModalPlugin},
+    movement::MovementPlugin,
+    special_cards::SpecialCardsPlugin,
+    tiles::{Tile, TilesPlugin},
+    tween::TweenPlugin,
+};
+
+/// An app with only the game plugins `add` puts in, run for a few frames.
+fn run_with(add: impl FnOnce(&mut App)) -> App {
+    let mut app = App::new();
+
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        bevy::input::InputPlugin,
+        TransformPlugin,
+        HierarchyPlugin,
+    ))
+    .init_asset::<Image>()
+    .insert_resource(RunSeed(7))
+    .insert_resource(ConfigDirectory(None));
+
+    add(&mut app);
+    app.finish();
+    app.cleanup();
+
+    for _ in 0..30 {
+        app.update();
+    }
+    app
+}
+
+#[test]
+fn the_board_and_the_cards_run_without_the_hud_or_the_event_log() {
+    let mut app = run_with(|app| {
+        app.add_plugins((TweenPlugin, ModalPlugin, TilesPlugin, MovementPlugin, SpecialCardsPlugin));
+    });
+
+    assert!(app.world.query::<&Tile>().iter(&app.world).next().is_some());
+    assert!(app.world.resource::<Modal>().is_open(), "the first tile is revealed");
+}
+
+#[test]
+#[should_panic(expected = "TilesPlugin needs MovementPlugin")]
+fn a_missing_plugin_is_named_when_the_app_is_built() {
+    run_with(|app| {
+        app.add_plugins((TweenPlugin, ModalPlugin, TilesPlugin));
+    });
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use common::Harness;
+use treacherous_safari::{
+    config::ConfigDirectory,
+    history::MovementRules,
+    settings::{
+        Difficulty, DisplayMode, Settings, SettingsScreen, SettingsWarnings, Theme, SETTINGS_FILE,
+    },
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("safari-settings-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn with_config(seed: u64, dir: &Path) -> Harness {
+    let dir = dir.to_path_buf();
+    Harness::new_with(seed, move |app| {
+        app.insert_resource(ConfigDirectory(Some(dir)));
+    })
+}
+
+#[test]
+fn settings_round_trip_through_a_file() {
+    let path = temp_dir("round-trip").join(SETTINGS_FILE);
+    let settings = Settings {
+        display_mode: DisplayMode::Borderless,
+        window_width: 1920,
+        window_height: 1080,
+        ui_scale: 1.5,
+        theme: Theme::Night,
+        animation_speed: 2.0,
+        skip_animations: true,
+        music_volume: 0.3,
+        difficulty: Difficulty::Hard,
+        ..Settings::default()
+    };
+
+    settings.save(&path).unwrap();
+
+    let (loaded, warnings) = Settings::load_or_default(Some(&path));
+    assert_eq!(loaded, settings);
+    assert!(warnings.is_empty(), "{:?}", warnings);
+}
+
+#[test]
+fn unusable_values_keep_their_defaults() {
+    let text = r#"
+[window]
+mode = "maximised"
+width = 100
+
+[interface]
+ui_scale = 2
+theme = 3
+
+[game]
+difficulty = "hard"
+"#;
+
+    let (settings, warnings) = Settings::from_toml(text);
+
+    assert_eq!(settings.display_mode, DisplayMode::Windowed);
+    assert_eq!(settings.window_width, Settings::default().window_width);
+    assert_eq!(settings.theme, Theme::Savanna);
+    // Whole numbers are fine where a fraction is expected.
+    assert_eq!(settings.ui_scale, 2.0);
+    assert_eq!(settings.difficulty, Difficulty::Hard);
+
+    assert_eq!(warnings.len(), 3, "{:?}", warnings);
+    assert!(warnings[0].starts_with("window.mode: \"maximised\" is not one of windowed"));
+    assert!(warnings[1].starts_with("window.width: 100 is not between 640 and 7680"));
+    assert!(warnings[2].starts_with("interface.theme: expected a name"));
+
+    let (settings, warnings) = Settings::from_toml("[window");
+    assert_eq!(settings, Settings::default());
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn normal_difficulty_plays_the_usual_rules() {
+    assert_eq!(Difficulty::Normal.rules(), MovementRules::default());
+    assert!(Difficulty::Easy.rules().allow_backward);
+    assert!(Difficulty::Hard.rules().revisit_penalty > 0);
+}
+
+#[test]
+fn saved_settings_load_at_startup() {
+    let dir = temp_dir("startup");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join(SETTINGS_FILE),
+        "[game]\ndifficulty = \"easy\"\n\n[audio]\nmaster_volume = 4.0\n",
+    )
+    .unwrap();
+
+    let mut harness = with_config(61, &dir);
+    let world = harness.world();
+
+    assert_eq!(world.resource::<Settings>().difficulty, Difficulty::Easy);
+    assert_eq!(world.resource::<Settings>().master_volume, 1.0);
+    assert!(world.resource::<MovementRules>().allow_backward);
+    assert_eq!(world.resource::<SettingsWarnings>().0.len(), 1);
+}
+
+#[test]
+fn the_screen_changes_and_saves() {
+    let dir = temp_dir("screen");
+    let mut harness = with_config(62, &dir);
+
+    harness.press(KeyCode::F2);
+    assert!(harness.world().resource::<SettingsScreen>().open);
+
+    // The fourth row is the theme.
+    harness.press(KeyCode::Down);
+    harness.press(KeyCode::Down);
+    harness.press(KeyCode::Down);
+    harness.press(KeyCode::Right);
+
+    assert_eq!(harness.world().resource::<Settings>().theme, Theme::Night);
+    assert_eq!(
+        harness.world().resource::<ClearColor>().0,
+        Theme::Night.background()
+    );
+
+    let (saved, _) = Settings::load_or_default(Some(&dir.join(SETTINGS_FILE)));
+    assert_eq!(saved.theme, Theme::Night);
+
+    harness.press(KeyCode::Back);
+    assert!(!harness.world().resource::<SettingsScreen>().open);
+}
+
+#[test]
+fn other_settings_leave_a_resized_window_alone() {
+    let mut harness = Harness::new(63);
+    let mut resized = Window::default();
+    resized.resolution.set(900.0, 600.0);
+    let window = harness.world().spawn((resized, PrimaryWindow)).id();
+
+    harness.world().resource_mut::<Settings>().master_volume = 0.5;
+    harness.step();
+    assert_eq!(harness.world().get::<Window>(window).unwrap().width(), 900.0);
+
+    harness.world().resource_mut::<Settings>().window_width = 1024;
+    harness.step();
+    assert_eq!(harness.world().get::<Window>(window).unwrap().width(), 1024.0);
+}
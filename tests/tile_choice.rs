@@ -0,0 +1,244 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{plain_tile, Harness};
+use treacherous_safari::{
+    modal::{CloseModal, Modal, ModalAnswer, ModalContent},
+    movement::draw_odds,
+    replay::{legal_actions, ActionLog, PlayerAction},
+    board::{BoardConstraints, TilePool},
+    tiles::{move_preview, OverTile, Player, Selectable, Tile, TileCover, TileSelected, VisitedTiles},
+};
+
+/// A run on its second turn, with tiles to move to.
+fn choosing(seed: u64) -> Harness {
+    let mut harness = Harness::new(seed);
+    harness.blank_special_cards();
+    harness.flatten_board(1);
+    harness.close_tile();
+    harness.fill_deck(5);
+    harness.finish_turn();
+    harness
+}
+
+fn pick(harness: &mut Harness, entity: Entity) {
+    harness.send(OverTile(entity));
+    harness.send(TileSelected(entity));
+}
+
+fn shown(harness: &mut Harness) -> Option<ModalContent> {
+    harness.world().resource::<Modal>().content().cloned()
+}
+
+#[test]
+fn the_odds_count_every_hand_the_deck_can_deal() {
+    let deck = [1, 2, 3, 4];
+
+    assert_eq!(draw_odds(&deck, 2, 0), 1.0);
+    assert_eq!(draw_odds(&deck, 2, 5), 4.0 / 6.0);
+    assert_eq!(draw_odds(&deck, 2, 8), 0.0);
+    assert_eq!(draw_odds(&[5], 2, 5), 1.0);
+    assert_eq!(draw_odds(&[], 2, 1), 0.0);
+}
+
+#[test]
+fn the_preview_shows_the_cost_the_points_and_the_shortfall() {
+    let tile = Tile {
+        number: 4,
+        ..plain_tile(6)
+    };
+
+    let preview = move_preview(&tile, None, 1, 1, &[1, 2, 3, 4]);
+
+    assert!(preview.contains("tile 4"), "{}", preview);
+    assert!(preview.contains("Cost: 7 (6 +1"), "{}", preview);
+    assert!(preview.contains("Points: 1"), "{}", preview);
+    assert!(preview.contains("Short by: 6"), "{}", preview);
+    assert!(preview.ends_with(": 33%"), "{}", preview);
+
+    let affordable = move_preview(&tile, None, 0, 9, &[]);
+    assert!(affordable.contains("Short by: 0"), "{}", affordable);
+    assert!(affordable.ends_with(": 100%"), "{}", affordable);
+}
+
+#[test]
+fn a_covered_tile_is_previewed_over_what_it_may_cost() {
+    let tile = Tile {
+        number: 4,
+        ..plain_tile(6)
+    };
+    let pool = TilePool::from_tiles(vec![plain_tile(2), plain_tile(6)]);
+
+    let preview = move_preview(&tile, Some(&pool), 0, 1, &[1, 2, 3, 4]);
+
+    assert!(preview.contains("Cost: ? (2 to 6)"), "{}", preview);
+    assert!(preview.contains("Short by: 1 to 5"), "{}", preview);
+    // Even odds of a 2, always covered, and of a 6, covered four hands in six.
+    assert!(preview.ends_with(": 83%"), "{}", preview);
+
+    let penalised = move_preview(&tile, Some(&pool), 1, 1, &[1, 2, 3, 4]);
+    assert!(penalised.contains("Cost: ? (2 to 6 +1 for the way there)"), "{}", penalised);
+}
+
+/// The lowest and highest cost a preview gives a covered tile.
+fn previewed_range(preview: &str) -> (u32, u32) {
+    let start = preview.find("Cost: ? (").unwrap() + "Cost: ? (".len();
+    let range = &preview[start..start + preview[start..].find(')').unwrap()];
+    let mut costs = range.split(" to ").map(|cost| cost.parse::<u32>().unwrap());
+    let min = costs.next().unwrap();
+    (min, costs.next().unwrap_or(min))
+}
+
+#[test]
+fn a_covered_tile_is_previewed_within_its_column() {
+    let constraints = BoardConstraints::default();
+    let pool = TilePool::default();
+
+    for (column, tier) in constraints.cost_tiers.iter().enumerate() {
+        let tile = plain_tile(tier.0);
+        let options = pool.covered_options(constraints.cost_tier(column), &[]);
+        let preview = move_preview(&tile, Some(&options), 0, 1, &[1, 2, 3, 4]);
+
+        let (min, max) = previewed_range(&preview);
+        assert!(tier.0 <= min && max <= tier.1, "column {}: {}", column, preview);
+    }
+}
+
+#[test]
+fn a_unique_tile_showing_elsewhere_is_not_previewed() {
+    let tile = plain_tile(7);
+    let mut pool = TilePool::from_tiles(vec![plain_tile(7), plain_tile(9)]);
+    pool.entries[1].tile.description = String::from("Oasis");
+    pool.entries[1].unique = true;
+
+    let options = pool.covered_options((7, 9), &[]);
+    let preview = move_preview(&tile, Some(&options), 0, 1, &[1, 2, 3, 4]);
+    assert_eq!(previewed_range(&preview), (7, 9));
+
+    let oasis = pool.entries[1].tile.clone();
+    let options = pool.covered_options((7, 9), &[&oasis]);
+    let preview = move_preview(&tile, Some(&options), 0, 1, &[1, 2, 3, 4]);
+    assert_eq!(previewed_range(&preview), (7, 7));
+}
+
+#[test]
+fn picking_a_tile_asks_before_moving() {
+    let mut harness = choosing(61);
+    let number = harness.selectable_tiles()[0];
+    let tile = harness.tile(number);
+
+    pick(&mut harness, tile);
+
+    let Some(ModalContent::Confirmation(preview)) = shown(&mut harness) else {
+        panic!("no confirmation for the move");
+    };
+    // The tiles next to the first are in the second column.
+    let (min, max) = previewed_range(&preview);
+    let tier = BoardConstraints::default().cost_tier(1);
+    assert!(tier.0 <= min && max <= tier.1, "{}", preview);
+    assert_eq!(
+        legal_actions(harness.world()),
+        vec![PlayerAction::Confirm, PlayerAction::Cancel]
+    );
+
+    harness.send(CloseModal(ModalAnswer::Confirm));
+    assert_eq!(shown(&mut harness), Some(ModalContent::TileDetails(tile)));
+
+    harness.close_tile();
+    assert_eq!(harness.current_tile(), Some(number));
+}
+
+#[test]
+fn a_revealed_tile_shows_what_it_costs() {
+    let mut harness = choosing(63);
+    let number = harness.selectable_tiles()[0];
+    let tile = harness.tile(number);
+
+    let world = harness.world();
+    for (parent, mut vis) in world
+        .query_filtered::<(&Parent, &mut Visibility), With<TileCover>>()
+        .iter_mut(world)
+    {
+        if parent.get() == tile {
+            *vis = Visibility::Hidden;
+        }
+    }
+
+    pick(&mut harness, tile);
+
+    let Some(ModalContent::Confirmation(preview)) = shown(&mut harness) else {
+        panic!("no confirmation for the move");
+    };
+    assert!(preview.contains("Cost: 1\n"), "{}", preview);
+}
+
+#[test]
+fn cancelling_leaves_the_player_where_they_are() {
+    let mut harness = choosing(62);
+    let number = harness.selectable_tiles()[0];
+    let tile = harness.tile(number);
+    let player = harness.entities::<With<Player>>()[0];
+    let before = harness.world().get::<Transform>(player).unwrap().translation;
+    let visited = harness.world().resource::<VisitedTiles>().0.clone();
+
+    pick(&mut harness, tile);
+    harness.send(CloseModal(ModalAnswer::Cancel));
+
+    assert_eq!(shown(&mut harness), None);
+    assert_eq!(harness.world().get::<Transform>(player).unwrap().translation, before);
+    assert_eq!(harness.world().resource::<VisitedTiles>().0, visited);
+    assert!(harness.world().get::<Selectable>(tile).is_some());
+    assert_eq!(harness.world().get::<Transform>(tile).unwrap().scale, Vec3::ONE);
+
+    // And the tile can still be picked afterwards.
+    pick(&mut harness, tile);
+    assert!(matches!(shown(&mut harness), Some(ModalContent::Confirmation(_))));
+}
+
+#[test]
+fn answers_are_recorded_for_replays() {
+    let mut harness = choosing(63);
+    let number = harness.selectable_tiles()[0];
+    let tile = harness.tile(number);
+
+    pick(&mut harness, tile);
+    harness.send(CloseModal(ModalAnswer::Cancel));
+    harness.select_tile(number);
+
+    let actions = &harness.world().resource::<ActionLog>().0;
+    let tail = &actions[actions.len() - 5..];
+
+    assert_eq!(
+        tail,
+        [
+            PlayerAction::SelectTile { tile: number },
+            PlayerAction::Cancel,
+            PlayerAction::SelectTile { tile: number },
+            PlayerAction::Confirm,
+            PlayerAction::CloseReveal,
+        ]
+    );
+}
+
+#[test]
+fn inputs_in_one_frame_are_recorded_as_the_game_handled_them() {
+    let mut harness = choosing(64);
+    let numbers = harness.selectable_tiles();
+    let (first, second) = (harness.tile(numbers[0]), harness.tile(numbers[1]));
+    pick(&mut harness, first);
+
+    // The pick lands while the question is still open, so only the answer counts.
+    harness.app.world.send_event(OverTile(second));
+    harness.app.world.send_event(TileSelected(second));
+    harness.app.world.send_event(CloseModal(ModalAnswer::Cancel));
+    harness.settle();
+
+    let actions = &harness.world().resource::<ActionLog>().0;
+    assert_eq!(
+        &actions[actions.len() - 2..],
+        [
+            PlayerAction::SelectTile { tile: numbers[0] },
+            PlayerAction::Cancel,
+        ]
+    );
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use bevy::{app::AppExit, prelude::*};
+use common::{plain_tile, Harness};
+use treacherous_safari::{
+    game_state::RunSeed,
+    history::MovementRules,
+    rules::Run,
+    special_cards::SpecialCardPool,
+    board::{BoardError, BoardGenerator, BoardLayout, TilePool},
+    tiles::{Tile, TileType},
+};
+
+fn harness_with_first_tile(tile: Tile) -> Harness {
+    let mut harness = Harness::new(10);
+    harness.flatten_board(100);
+    harness.fill_deck(2);
+    harness.blank_special_cards();
+    harness.set_tile(0, tile);
+    harness
+}
+
+fn tile(tile_type: TileType, cost: u32, value: i32, duration: i32) -> Tile {
+    Tile {
+        cost,
+        tile_type,
+        value,
+        duration,
+        ..plain_tile(cost)
+    }
+}
+
+#[test]
+fn plain_tile_only_counts_the_movement_cards() {
+    let mut harness = harness_with_first_tile(plain_tile(100));
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    assert_eq!(harness.movement_points(), 4);
+    assert_eq!(harness.turns_left(), 6);
+}
+
+#[test]
+fn movement_points_tile_applies_once_on_enter() {
+    let mut harness = harness_with_first_tile(tile(TileType::MovementPointsUpdate, 100, 3, 0));
+
+    // Not at the reveal, but with the first hand played on the tile.
+    harness.close_tile();
+    assert_eq!(harness.movement_points(), 0);
+
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 7);
+
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 11);
+}
+
+#[test]
+fn lasting_movement_points_tile_applies_each_turn_for_its_duration() {
+    let mut harness = harness_with_first_tile(tile(TileType::MovementPointsUpdate, 100, -1, 2));
+
+    harness.close_tile();
+    assert_eq!(harness.movement_points(), -1);
+
+    // Missing the cost starts the next turn, which takes the second point.
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 2);
+
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 6);
+}
+
+#[test]
+fn turn_tile_changes_the_turns_left() {
+    let mut harness = harness_with_first_tile(tile(TileType::TurnUpdate, 100, 2, 0));
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    assert_eq!(harness.turns_left(), 8);
+    assert_eq!(harness.movement_points(), 4);
+}
+
+#[test]
+fn blessing_tile_clears_lasting_curses() {
+    let mut harness = harness_with_first_tile(tile(TileType::MovementPointsUpdate, 0, -1, -1));
+    harness.set_tile(1, tile(TileType::Blessing, 0, 0, 0));
+    harness.set_tile(4, plain_tile(100));
+
+    harness.close_tile();
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 3);
+
+    harness.select_tile(1);
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 6);
+
+    harness.select_tile(4);
+    harness.finish_turn();
+    assert_eq!(harness.movement_points(), 10);
+}
+
+
+#[derive(Resource, Default)]
+struct Exited(bool);
+
+fn record_exit(mut events: EventReader<AppExit>, mut exited: ResMut<Exited>) {
+    exited.0 |= events.read().count() > 0;
+}
+
+#[test]
+fn a_pool_that_cannot_make_a_board_ends_the_game() {
+    // Too dear for every column, so no seed gives a board.
+    let mut harness = Harness::new_with(11, |app| {
+        app.insert_resource(TilePool::from_tiles(vec![plain_tile(50)]))
+            .init_resource::<Exited>()
+            .add_systems(Update, record_exit);
+    });
+
+    assert!(harness.world().resource::<Exited>().0);
+    assert!(harness.entities::<With<Tile>>().is_empty());
+}
+
+#[test]
+fn a_column_with_no_tiles_to_draw_from_fails_at_once() {
+    // Nothing costs enough for the third column, whatever the seed.
+    let pool = TilePool::from_tiles(vec![plain_tile(1), plain_tile(2)]);
+    let generator = BoardGenerator::with_pool(BoardLayout::default(), pool);
+
+    assert_eq!(generator.generate_near(0), Err(BoardError::EmptyTier(2)));
+}
+
+#[test]
+fn a_run_whose_seed_gives_no_board_goes_on_from_the_seed_that_does() {
+    // Plain ground may share the name of a one-off spring, and a board with two springs is rejected,
+    // so only a few seeds find a board among their attempts.
+    let layout = BoardLayout {
+        columns: vec![(0..12).collect(), vec![0]],
+    };
+    let spring = Tile {
+        tile_type: TileType::Blessing,
+        description: String::from("Spring"),
+        ..plain_tile(2)
+    };
+    let named = |description: &str| Tile {
+        description: String::from(description),
+        ..plain_tile(1)
+    };
+    let pool = TilePool::from_tiles(vec![spring, named("Spring"), named("Dust")]);
+    let generator = BoardGenerator::with_pool(layout.clone(), pool.clone());
+
+    let seed = (0..100).find(|seed| generator.generate(*seed).is_err()).unwrap();
+    let (board_seed, board) = generator.generate_near(seed).unwrap();
+    assert_ne!(board_seed, seed);
+
+    let mut harness = Harness::new_with(seed, |app| {
+        app.insert_resource(layout.clone()).insert_resource(pool.clone());
+    });
+
+    assert_eq!(harness.world().resource::<RunSeed>().0, board_seed);
+    for tile in board {
+        let entity = harness.tile(tile.number);
+        assert_eq!(harness.world().get::<Tile>(entity), Some(&tile));
+    }
+
+    let rules = MovementRules::default();
+    let run = Run::with_pools(seed, layout, rules, &pool, &SpecialCardPool::default()).unwrap();
+    assert_eq!(run.seed, board_seed);
+}
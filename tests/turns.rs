@@ -0,0 +1,158 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{plain_tile, Harness};
+use treacherous_safari::{
+    history::RunSummary,
+    ledger::{Cause, Ledger},
+    tiles::{Tile, TileType, VisitedTiles},
+    ui::{GameOverText, MovementPointsText, RunSummaryText, TurnsLeftText, WonText},
+};
+
+#[test]
+fn run_starts_on_the_first_tile() {
+    let mut harness = Harness::new(1);
+
+    harness.close_tile();
+
+    assert_eq!(harness.current_tile(), Some(0));
+    assert_eq!(harness.turns_left(), 7);
+    assert_eq!(harness.movement_points(), 0);
+    assert_eq!(harness.drawn_values().len(), 2);
+    assert!(harness.hand().phase_open);
+}
+
+#[test]
+fn meeting_the_cost_unlocks_the_neighbours() {
+    let mut harness = Harness::new(2);
+    harness.flatten_board(1);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    assert_eq!(harness.movement_points(), 2);
+    assert_eq!(harness.turns_left(), 6);
+    assert_eq!(harness.selectable_tiles(), vec![1, 2, 3]);
+    assert_eq!(harness.current_tile(), None);
+}
+
+#[test]
+fn missing_the_cost_stays_on_the_tile() {
+    let mut harness = Harness::new(3);
+    harness.flatten_board(5);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    assert_eq!(harness.movement_points(), 2);
+    assert_eq!(harness.turns_left(), 6);
+    assert!(harness.selectable_tiles().is_empty());
+    assert_eq!(harness.current_tile(), Some(0));
+    assert!(harness.hand().phase_open);
+}
+
+#[test]
+fn reaching_the_last_tile_wins() {
+    let mut harness = Harness::new(4);
+    harness.flatten_board(1);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    for number in [1, 4, 7, 10] {
+        harness.select_tile(number);
+        harness.finish_turn();
+    }
+
+    assert!(harness.is_visible::<WonText>());
+    assert!(!harness.is_visible::<GameOverText>());
+    assert!(harness.is_visible::<RunSummaryText>());
+    assert!(harness.selectable_tiles().is_empty());
+
+    let summary = harness.world().resource::<RunSummary>().clone();
+    assert!(summary.won);
+    assert_eq!(summary.path, vec![0, 1, 4, 7, 10]);
+    assert_eq!(summary.turns_left, 2);
+}
+
+#[test]
+fn running_out_of_turns_loses() {
+    let mut harness = Harness::new(5);
+    harness.flatten_board(100);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+
+    harness.close_tile();
+
+    for _ in 0..7 {
+        harness.finish_turn();
+        assert!(!harness.is_visible::<GameOverText>());
+    }
+
+    assert_eq!(harness.turns_left(), 0);
+
+    harness.finish_turn();
+
+    assert!(harness.is_visible::<GameOverText>());
+    assert!(!harness.is_visible::<WonText>());
+    assert!(!harness.world().resource::<RunSummary>().won);
+    assert_eq!(harness.world().resource::<RunSummary>().turns_left, 0);
+}
+
+#[test]
+fn coming_back_to_a_tile_does_not_give_its_points_again() {
+    let mut harness = Harness::new(6);
+    harness.flatten_board(1);
+    harness.fill_deck(1);
+    harness.blank_special_cards();
+    harness.set_tile(
+        1,
+        Tile {
+            tile_type: TileType::MovementPointsUpdate,
+            value: 3,
+            ..plain_tile(1)
+        },
+    );
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    for number in [1, 2, 1] {
+        harness.select_tile(number);
+        harness.finish_turn();
+    }
+
+    assert_eq!(harness.world().resource::<VisitedTiles>().0, vec![0, 1, 2, 1]);
+    let from_tile = harness
+        .world()
+        .resource::<Ledger>()
+        .entries
+        .iter()
+        .filter(|entry| entry.cause == Cause::Tile(1))
+        .count();
+    assert_eq!(from_tile, 1);
+}
+
+#[test]
+fn the_counts_are_kept_without_their_hud_text() {
+    let mut harness = Harness::new(5);
+    harness.flatten_board(100);
+    harness.fill_deck(2);
+    harness.blank_special_cards();
+
+    for entity in harness.entities::<Or<(With<MovementPointsText>, With<TurnsLeftText>)>>() {
+        harness.world().despawn(entity);
+    }
+
+    harness.close_tile();
+    harness.finish_turn();
+
+    assert_eq!(harness.turns_left(), 6);
+    assert_eq!(harness.movement_points(), 4);
+}
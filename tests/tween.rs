@@ -0,0 +1,203 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{ecs::event::ManualEventReader, prelude::*, time::TimeUpdateStrategy};
+use common::Harness;
+use treacherous_safari::{
+    movement::{MovementCardCover, MovementCardDrawn},
+    settings::Settings,
+    tiles::Player,
+    tween::{lean, AnimationGate, CardTurned, Ease, Flip, Tween, FLIP_SECONDS},
+};
+
+const FRAME: f32 = 0.05;
+
+fn animated(seed: u64) -> Harness {
+    let mut harness = Harness::new_with(seed, |app| {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(FRAME)));
+    });
+    harness.animate();
+    harness
+}
+
+fn steps(harness: &mut Harness, seconds: f32) {
+    for _ in 0..(seconds / FRAME).ceil() as usize {
+        harness.step();
+    }
+}
+
+fn translation(harness: &mut Harness, entity: Entity) -> Vec3 {
+    harness.world().get::<Transform>(entity).unwrap().translation
+}
+
+#[test]
+fn every_ease_starts_and_ends_in_place() {
+    for ease in [
+        Ease::Linear,
+        Ease::QuadIn,
+        Ease::QuadOut,
+        Ease::QuadInOut,
+        Ease::CubicOut,
+        Ease::BackOut,
+    ] {
+        assert_eq!(ease.apply(0.0), 0.0, "{:?}", ease);
+        assert!((ease.apply(1.0) - 1.0).abs() < 1e-6, "{:?}", ease);
+    }
+
+    assert!(Ease::QuadIn.apply(0.5) < 0.5);
+    assert!(Ease::QuadOut.apply(0.5) > 0.5);
+    assert!(Ease::BackOut.apply(0.8) > 1.0);
+}
+
+#[test]
+fn a_tween_plays_out_and_lands_on_its_target() {
+    let mut harness = animated(91);
+    let target = Vec3::new(100.0, -50.0, 0.0);
+    let entity = harness
+        .world()
+        .spawn((
+            TransformBundle::default(),
+            Tween::new(1.0, Ease::Linear)
+                .translate_to(target)
+                .then(Tween::new(1.0, Ease::Linear).scale_to(Vec3::splat(2.0))),
+        ))
+        .id();
+
+    steps(&mut harness, 0.5);
+    let halfway = translation(&mut harness, entity);
+    assert!(halfway.x > 0.0 && halfway.x < 100.0, "{:?}", halfway);
+    assert!(!harness.world().resource::<AnimationGate>().is_idle());
+
+    steps(&mut harness, 0.6);
+    assert_eq!(translation(&mut harness, entity), target);
+    assert!(harness.world().get::<Tween>(entity).is_some());
+
+    steps(&mut harness, 1.0);
+    assert_eq!(harness.world().get::<Transform>(entity).unwrap().scale, Vec3::splat(2.0));
+    assert!(harness.world().get::<Tween>(entity).is_none());
+    assert!(harness.world().resource::<AnimationGate>().is_idle());
+}
+
+#[test]
+fn the_speed_setting_shortens_animations() {
+    let mut harness = animated(92);
+    harness.world().resource_mut::<Settings>().animation_speed = 4.0;
+
+    let target = Vec3::new(100.0, 0.0, 0.0);
+    let entity = harness
+        .world()
+        .spawn((TransformBundle::default(), Tween::new(1.0, Ease::QuadInOut).translate_to(target)))
+        .id();
+
+    steps(&mut harness, 0.3);
+    assert_eq!(translation(&mut harness, entity), target);
+}
+
+#[test]
+fn the_turn_waits_for_the_piece_and_the_cards() {
+    let mut harness = animated(93);
+    let player = harness.entities::<With<Player>>()[0];
+    let start = translation(&mut harness, player);
+
+    harness.close_tile();
+
+    // Still on its way, and nothing has been drawn yet.
+    let moving = translation(&mut harness, player);
+    assert_ne!(moving, start);
+    assert!(harness.entities::<With<MovementCardDrawn>>().is_empty());
+    assert!(!harness.hand().phase_open);
+
+    steps(&mut harness, 0.5);
+    let drawn = harness.entities::<With<MovementCardDrawn>>();
+    assert_eq!(drawn.len(), 2);
+    assert!(!harness.hand().phase_open);
+
+    // Face down until they have come off the deck.
+    assert!(cover_shown(&mut harness, &drawn));
+
+    // The special cards open once the drawn cards have come off the deck and turned over.
+    steps(&mut harness, 1.0);
+    assert!(harness.hand().phase_open);
+    assert!(harness.world().resource::<AnimationGate>().is_idle());
+    assert!(!cover_shown(&mut harness, &drawn));
+
+    for card in drawn {
+        assert_eq!(harness.world().get::<Transform>(card).unwrap().rotation, Quat::IDENTITY);
+    }
+}
+
+#[test]
+fn discarded_cards_keep_their_lean_and_shrink_back() {
+    let mut harness = animated(96);
+    harness.close_tile();
+    steps(&mut harness, 1.5);
+
+    let drawn = harness.entities::<With<MovementCardDrawn>>();
+    for card in &drawn {
+        let mut transform = harness.world().get_mut::<Transform>(*card).unwrap();
+        transform.rotate_z(0.3);
+        transform.scale = Vec3::splat(1.2);
+    }
+
+    harness.finish_turn();
+    steps(&mut harness, 1.0);
+
+    for card in drawn {
+        let transform = *harness.world().get::<Transform>(card).unwrap();
+        assert!((lean(transform.rotation) - 0.3).abs() <= 0.1 + f32::EPSILON);
+        assert_eq!(transform.scale, Vec3::ONE);
+    }
+}
+
+/// Whether any of `cards` still shows its cover.
+fn cover_shown(harness: &mut Harness, cards: &[Entity]) -> bool {
+    let world = harness.world();
+    world
+        .query_filtered::<(&Parent, &Visibility), With<MovementCardCover>>()
+        .iter(world)
+        .filter(|(parent, _)| cards.contains(&parent.get()))
+        .any(|(_, vis)| *vis == Visibility::Inherited)
+}
+
+#[test]
+fn a_flip_swaps_the_cover_when_the_card_is_edge_on() {
+    let mut harness = animated(95);
+    let world = harness.world();
+    let cover = world.spawn(SpatialBundle::default()).id();
+    let card = world
+        .spawn((SpatialBundle::default(), Flip::reveal(cover)))
+        .push_children(&[cover])
+        .id();
+
+    let turned = |harness: &mut Harness| {
+        let events = harness.world().resource::<Events<CardTurned>>();
+        ManualEventReader::<CardTurned>::default()
+            .read(events)
+            .any(|event| event.card == card && event.face_up)
+    };
+
+    steps(&mut harness, FLIP_SECONDS * 0.3);
+    assert_ne!(harness.world().get::<Transform>(card).unwrap().rotation, Quat::IDENTITY);
+    assert_eq!(harness.world().get::<Visibility>(cover), Some(&Visibility::Inherited));
+
+    steps(&mut harness, FLIP_SECONDS * 0.3);
+    assert_eq!(harness.world().get::<Visibility>(cover), Some(&Visibility::Hidden));
+    assert!(turned(&mut harness));
+
+    steps(&mut harness, FLIP_SECONDS * 0.5);
+    assert!(harness.world().get::<Flip>(card).is_none());
+    assert_eq!(harness.world().get::<Transform>(card).unwrap().rotation, Quat::IDENTITY);
+}
+
+#[test]
+fn skipping_animations_puts_everything_in_place_at_once() {
+    let mut harness = animated(94);
+    harness.world().resource_mut::<Settings>().skip_animations = true;
+
+    harness.close_tile();
+
+    assert_eq!(harness.entities::<With<MovementCardDrawn>>().len(), 2);
+    assert!(harness.hand().phase_open);
+    assert!(harness.entities::<With<Tween>>().is_empty());
+}